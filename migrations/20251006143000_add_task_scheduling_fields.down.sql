@@ -0,0 +1,8 @@
+ALTER TABLE users DROP COLUMN IF EXISTS timezone;
+DROP INDEX IF EXISTS idx_tasks_assignee_id;
+DROP INDEX IF EXISTS idx_tasks_due_at;
+ALTER TABLE tasks
+    DROP COLUMN IF EXISTS assignee_id,
+    DROP COLUMN IF EXISTS due_at,
+    DROP COLUMN IF EXISTS priority;
+DROP TYPE IF EXISTS task_priority;
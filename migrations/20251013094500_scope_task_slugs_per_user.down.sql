@@ -0,0 +1,4 @@
+-- Fails if two users own tasks with the same slug
+DROP TABLE IF EXISTS task_slug_redirects;
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_user_slug_unique;
+ALTER TABLE tasks ADD CONSTRAINT tasks_slug_unique UNIQUE (slug);
@@ -0,0 +1,15 @@
+-- Slugs only need to be unique within one owner's tasks
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_slug_unique;
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_slug_key;
+ALTER TABLE tasks ADD CONSTRAINT tasks_user_slug_unique UNIQUE (user_id, slug);
+
+-- Previous slugs of renamed tasks, so old links keep resolving
+CREATE TABLE IF NOT EXISTS task_slug_redirects (
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  old_slug TEXT NOT NULL,
+  task_id UUID NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, old_slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_slug_redirects_task_id ON task_slug_redirects(task_id);
@@ -0,0 +1,12 @@
+-- `[[...]]` references found in note bodies. `target_ref` is the text inside
+-- the brackets; `target_note_id` stays NULL until a note with that title or
+-- slug exists in the owner's notes.
+CREATE TABLE IF NOT EXISTS note_links (
+  source_note_id UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+  target_ref TEXT NOT NULL,
+  target_note_id UUID REFERENCES notes(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (source_note_id, target_ref)
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_links_target_note_id ON note_links(target_note_id);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS notebooks (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  parent_id UUID REFERENCES notebooks(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notebooks_user_id ON notebooks(user_id);
+CREATE INDEX IF NOT EXISTS idx_notebooks_parent_id ON notebooks(parent_id);
+
+-- Sibling names are unique per owner, top-level notebooks included
+CREATE UNIQUE INDEX IF NOT EXISTS notebooks_sibling_name_unique
+  ON notebooks(user_id, COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'::uuid), lower(name));
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS notebook_id UUID REFERENCES notebooks(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_notes_notebook_id ON notes(notebook_id);
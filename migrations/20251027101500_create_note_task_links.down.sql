@@ -0,0 +1 @@
+DROP TABLE IF EXISTS note_task_links;
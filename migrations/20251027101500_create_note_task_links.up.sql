@@ -0,0 +1,9 @@
+-- Tasks extracted from task-list items of a note. Items are matched by their
+-- text, so each text is linked at most once per note.
+CREATE TABLE IF NOT EXISTS note_task_links (
+  task_id UUID PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+  note_id UUID NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+  item_text TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  CONSTRAINT note_task_links_note_item_unique UNIQUE (note_id, item_text)
+);
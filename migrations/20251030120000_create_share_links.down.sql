@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS share_links;
+DROP TYPE IF EXISTS share_resource_type;
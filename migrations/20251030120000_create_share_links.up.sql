@@ -0,0 +1,25 @@
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'share_resource_type') THEN
+        CREATE TYPE share_resource_type AS ENUM ('task', 'note');
+    END IF;
+END$$;
+
+-- Public read-only links. Only a SHA-256 of the token is stored; the token
+-- itself is shown once, when the link is created.
+CREATE TABLE IF NOT EXISTS share_links (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  resource_type share_resource_type NOT NULL,
+  resource_id UUID NOT NULL,
+  token_hash TEXT NOT NULL UNIQUE,
+  token_hint TEXT NOT NULL,
+  password_hash TEXT,
+  expires_at TIMESTAMPTZ,
+  access_count BIGINT NOT NULL DEFAULT 0,
+  last_accessed_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_links_user_id ON share_links(user_id);
+CREATE INDEX IF NOT EXISTS idx_share_links_resource ON share_links(resource_type, resource_id);
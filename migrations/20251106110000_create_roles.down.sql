@@ -0,0 +1,16 @@
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'user_role') THEN
+        CREATE TYPE user_role AS ENUM ('user', 'admin');
+    END IF;
+END$$;
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role user_role NOT NULL DEFAULT 'user';
+
+-- Anyone on the admin role stays an admin, every other role becomes a plain user
+UPDATE users u SET role = 'admin' FROM roles r WHERE r.id = u.role_id AND r.name = 'admin';
+
+DROP INDEX IF EXISTS idx_users_role_id;
+ALTER TABLE users DROP COLUMN IF EXISTS role_id;
+DROP TABLE IF EXISTS role_permissions;
+DROP TABLE IF EXISTS roles;
@@ -0,0 +1,59 @@
+-- Named roles with a set of permissions, replacing the user_role enum
+CREATE TABLE IF NOT EXISTS roles (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  name TEXT NOT NULL UNIQUE,
+  description TEXT NOT NULL DEFAULT '',
+  -- Built-in roles cannot be edited or deleted
+  built_in BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+  role_id UUID NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+  permission TEXT NOT NULL,
+  PRIMARY KEY (role_id, permission)
+);
+
+INSERT INTO roles (name, description, built_in) VALUES
+  ('admin', 'Full access to every resource', TRUE),
+  ('user', 'Access to own tasks and notes and to what is shared with them', TRUE),
+  ('viewer', 'Read-only access to all tasks and notes', FALSE),
+  ('manager', 'Can read and edit all tasks', FALSE),
+  ('support', 'Can look up users and revoke share links', FALSE)
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role_id, permission)
+SELECT r.id, p.permission
+FROM roles r
+JOIN (VALUES
+  ('admin', 'users:create'),
+  ('admin', 'users:read_any'),
+  ('admin', 'roles:manage'),
+  ('admin', 'tasks:read_any'),
+  ('admin', 'tasks:write_any'),
+  ('admin', 'notes:read_any'),
+  ('admin', 'notes:write_any'),
+  ('admin', 'shares:manage_any'),
+  ('admin', 'system:admin'),
+  ('viewer', 'tasks:read_any'),
+  ('viewer', 'notes:read_any'),
+  ('manager', 'users:read_any'),
+  ('manager', 'tasks:read_any'),
+  ('manager', 'tasks:write_any'),
+  ('manager', 'notes:read_any'),
+  ('support', 'users:read_any'),
+  ('support', 'tasks:read_any'),
+  ('support', 'notes:read_any'),
+  ('support', 'shares:manage_any')
+) AS p(role, permission) ON p.role = r.name
+ON CONFLICT DO NOTHING;
+
+-- Existing enum values map onto the seeded roles of the same name
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role_id UUID REFERENCES roles(id);
+UPDATE users u SET role_id = r.id FROM roles r WHERE r.name = u.role::text AND u.role_id IS NULL;
+ALTER TABLE users ALTER COLUMN role_id SET NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_users_role_id ON users(role_id);
+
+ALTER TABLE users DROP COLUMN IF EXISTS role;
+DROP TYPE IF EXISTS user_role;
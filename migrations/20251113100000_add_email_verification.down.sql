@@ -0,0 +1,4 @@
+ALTER TABLE users DROP COLUMN IF EXISTS email_verified_at;
+
+-- Enum values cannot be dropped; remove the tokens that use it
+DELETE FROM user_tokens WHERE purpose = 'email_verification';
@@ -0,0 +1,6 @@
+ALTER TYPE user_token_purpose ADD VALUE IF NOT EXISTS 'email_verification';
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ;
+
+-- Accounts created before verification existed are treated as verified
+UPDATE users SET email_verified_at = created_at WHERE email_verified_at IS NULL;
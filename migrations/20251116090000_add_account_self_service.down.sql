@@ -0,0 +1,5 @@
+ALTER TABLE users DROP COLUMN IF EXISTS token_version;
+ALTER TABLE users DROP COLUMN IF EXISTS pending_email;
+
+-- Enum values cannot be dropped; remove the tokens that use it
+DELETE FROM user_tokens WHERE purpose = 'email_change';
@@ -0,0 +1,8 @@
+-- Email changes are confirmed through a mailed link to the new address
+ALTER TYPE user_token_purpose ADD VALUE IF NOT EXISTS 'email_change';
+
+-- Address waiting for confirmation; replaces email once the link is followed
+ALTER TABLE users ADD COLUMN IF NOT EXISTS pending_email TEXT;
+
+-- Embedded in every access token; bumping it invalidates all tokens issued before
+ALTER TABLE users ADD COLUMN IF NOT EXISTS token_version INTEGER NOT NULL DEFAULT 0;
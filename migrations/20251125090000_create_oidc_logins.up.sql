@@ -0,0 +1,25 @@
+-- Accounts at external OpenID Connect providers, each linked to a local user
+CREATE TABLE IF NOT EXISTS user_identities (
+  provider TEXT NOT NULL,
+  -- The provider's `sub` claim; stable, unlike the email
+  subject TEXT NOT NULL,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  email TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  last_login_at TIMESTAMPTZ,
+  PRIMARY KEY (provider, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_identities_user_id ON user_identities(user_id);
+
+-- Authorization requests between the redirect to a provider and its callback
+CREATE TABLE IF NOT EXISTS oidc_auth_requests (
+  -- SHA-256 of the `state` parameter
+  state_hash TEXT PRIMARY KEY,
+  provider TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  -- PKCE verifier; only its challenge ever leaves the server
+  code_verifier TEXT NOT NULL,
+  expires_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,14 @@
+-- Passwordless sign-in links; each works once, and only with the device
+-- code handed to whoever asked for it
+CREATE TABLE IF NOT EXISTS magic_links (
+  -- SHA-256 of the token in the emailed link
+  token_hash TEXT PRIMARY KEY,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  -- SHA-256 of the device code returned to the requesting client
+  device_code_hash TEXT NOT NULL,
+  expires_at TIMESTAMPTZ NOT NULL,
+  used_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_magic_links_user_id ON magic_links(user_id);
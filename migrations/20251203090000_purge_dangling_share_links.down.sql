@@ -0,0 +1 @@
+-- Deleted links cannot be restored
@@ -0,0 +1,6 @@
+-- share_links.resource_id has no foreign key (it points at tasks or notes).
+-- Links are now removed with their note or on transfer; drop the ones left
+-- pointing at nothing by earlier deletes.
+DELETE FROM share_links s
+WHERE (s.resource_type = 'task' AND NOT EXISTS (SELECT 1 FROM tasks t WHERE t.id = s.resource_id))
+   OR (s.resource_type = 'note' AND NOT EXISTS (SELECT 1 FROM notes n WHERE n.id = s.resource_id));
@@ -0,0 +1,7 @@
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, 'shares:manage_any'
+FROM role_permissions
+WHERE permission = 'shares:revoke_any'
+ON CONFLICT DO NOTHING;
+
+DELETE FROM role_permissions WHERE permission = 'shares:revoke_any';
@@ -0,0 +1,11 @@
+-- Revoking other users' share links no longer implies creating them:
+-- shares:manage_any stays with admins, support only gets shares:revoke_any
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, 'shares:revoke_any'
+FROM role_permissions
+WHERE permission = 'shares:manage_any'
+ON CONFLICT DO NOTHING;
+
+DELETE FROM role_permissions p
+USING roles r
+WHERE r.id = p.role_id AND r.name = 'support' AND p.permission = 'shares:manage_any';
@@ -0,0 +1,2 @@
+ALTER TABLE notes DROP COLUMN IF EXISTS slug_base;
+ALTER TABLE tasks DROP COLUMN IF EXISTS slug_base;
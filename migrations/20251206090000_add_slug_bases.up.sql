@@ -0,0 +1,13 @@
+-- The slugified title words a slug was built from, before any uniqueness
+-- suffix; empty when the title had none and the slug is a fallback. A rename
+-- keeps the slug while the new title slugifies to the same base.
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS slug_base TEXT;
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS slug_base TEXT;
+
+-- Whether an existing slug carries a suffix can't be told from its shape;
+-- taking the whole slug as its base at worst re-slugs a suffixed row on rename
+UPDATE tasks SET slug_base = slug WHERE slug_base IS NULL;
+UPDATE notes SET slug_base = slug WHERE slug_base IS NULL;
+
+ALTER TABLE tasks ALTER COLUMN slug_base SET NOT NULL;
+ALTER TABLE notes ALTER COLUMN slug_base SET NOT NULL;
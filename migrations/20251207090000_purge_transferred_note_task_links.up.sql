@@ -0,0 +1,5 @@
+-- Links are now dropped when a task changes owner; drop the ones earlier
+-- transfers left between a task and another user's note.
+DELETE FROM note_task_links l
+USING tasks t, notes n
+WHERE t.id = l.task_id AND n.id = l.note_id AND t.user_id <> n.user_id;
@@ -0,0 +1,62 @@
+use std::marker::PhantomData;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+use crate::domain::ApiError;
+use crate::middleware::CurrentUser;
+use super::policy::{authorize, Action, Actor, Resource};
+
+/// An action on a resource that does not depend on the request
+pub trait Permission {
+    const ACTION: Action;
+    const RESOURCE: Resource;
+}
+
+/// Rejects the request with 403 unless the current user holds `P`.
+/// Needs `auth_middleware` to have run.
+pub struct RequirePermission<P> {
+    pub actor: Actor,
+    _permission: PhantomData<P>,
+}
+
+#[async_trait]
+impl<P, S> FromRequestParts<S> for RequirePermission<P>
+where
+    P: Permission,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let current_user = parts
+            .extensions
+            .get::<CurrentUser>()
+            .ok_or_else(|| ApiError::Unauthorized("Authentication required".to_string()))?;
+
+        let actor = Actor::from(current_user);
+        authorize(&actor, P::ACTION, &P::RESOURCE)?;
+
+        Ok(Self { actor, _permission: PhantomData })
+    }
+}
+
+pub struct CreateUsers;
+
+impl Permission for CreateUsers {
+    const ACTION: Action = Action::Create;
+    const RESOURCE: Resource = Resource::Users;
+}
+
+pub struct AdministerSystem;
+
+impl Permission for AdministerSystem {
+    const ACTION: Action = Action::Administer;
+    const RESOURCE: Resource = Resource::System;
+}
+
+pub struct ManageRoles;
+
+impl Permission for ManageRoles {
+    const ACTION: Action = Action::Manage;
+    const RESOURCE: Resource = Resource::Roles;
+}
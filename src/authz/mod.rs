@@ -0,0 +1,6 @@
+// Authorization module - decides who may do what to which resource
+pub mod policy;
+pub mod extractor;
+
+pub use policy::{authorize, Action, Actor, Decision, DefaultPolicy, Policy, Resource};
+pub use extractor::{Permission, RequirePermission, CreateUsers, ManageRoles, AdministerSystem};
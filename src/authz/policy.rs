@@ -0,0 +1,364 @@
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::domain::{ApiError, Permission, Result};
+use crate::domain::task::TaskPermission;
+use crate::middleware::CurrentUser;
+
+/// The authenticated user a decision is made for
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub id: Uuid,
+    pub role: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl Actor {
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+impl From<&CurrentUser> for Actor {
+    fn from(current_user: &CurrentUser) -> Self {
+        Self {
+            id: current_user.id,
+            role: current_user.role.clone(),
+            permissions: current_user.permissions.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Read,
+    List,
+    Update,
+    Delete,
+    Share,
+    Transfer,
+    Duplicate,
+    /// Create a task from a template
+    Instantiate,
+    /// Create, edit and assign roles
+    Manage,
+    Administer,
+}
+
+/// What is acted on, with just the state the rules look at
+#[derive(Debug, Clone, Copy)]
+pub enum Resource {
+    /// Every user account
+    Users,
+    User { id: Uuid },
+    /// The caller's share on the task, if any, has to be looked up beforehand
+    Task { owner_id: Uuid, shared: Option<TaskPermission> },
+    /// Tasks owned by one user
+    UserTasks { owner_id: Uuid },
+    /// Every task in the system
+    AllTasks,
+    Template { owner_id: Uuid },
+    Note { owner_id: Uuid },
+    /// Notes owned by one user
+    UserNotes { owner_id: Uuid },
+    Notebook { owner_id: Uuid },
+    /// A public share link, owned by whoever owns the shared task or note
+    ShareLink { owner_id: Uuid },
+    Roles,
+    /// Admin-only parts of the API
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResourceKind {
+    Users,
+    User,
+    Task,
+    UserTasks,
+    AllTasks,
+    Template,
+    Note,
+    UserNotes,
+    Notebook,
+    ShareLink,
+    Roles,
+    System,
+}
+
+impl Resource {
+    fn kind(&self) -> ResourceKind {
+        match self {
+            Resource::Users => ResourceKind::Users,
+            Resource::User { .. } => ResourceKind::User,
+            Resource::Task { .. } => ResourceKind::Task,
+            Resource::UserTasks { .. } => ResourceKind::UserTasks,
+            Resource::AllTasks => ResourceKind::AllTasks,
+            Resource::Template { .. } => ResourceKind::Template,
+            Resource::Note { .. } => ResourceKind::Note,
+            Resource::UserNotes { .. } => ResourceKind::UserNotes,
+            Resource::Notebook { .. } => ResourceKind::Notebook,
+            Resource::ShareLink { .. } => ResourceKind::ShareLink,
+            Resource::Roles => ResourceKind::Roles,
+            Resource::System => ResourceKind::System,
+        }
+    }
+
+    /// The user the resource belongs to, for resources that have one
+    fn owner_id(&self) -> Option<Uuid> {
+        match self {
+            Resource::User { id } => Some(*id),
+            Resource::Task { owner_id, .. }
+            | Resource::UserTasks { owner_id }
+            | Resource::Template { owner_id }
+            | Resource::Note { owner_id }
+            | Resource::UserNotes { owner_id }
+            | Resource::Notebook { owner_id }
+            | Resource::ShareLink { owner_id } => Some(*owner_id),
+            Resource::Users | Resource::AllTasks | Resource::Roles | Resource::System => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny(&'static str),
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allow)
+    }
+}
+
+pub trait Policy {
+    fn can(&self, actor: &Actor, action: Action, resource: &Resource) -> Decision;
+
+    /// Like `can`, but logs the decision and turns a denial into `Forbidden`
+    fn authorize(&self, actor: &Actor, action: Action, resource: &Resource) -> Result<()> {
+        match self.can(actor, action, resource) {
+            Decision::Allow => {
+                debug!("authz allow: user {} ({}) {:?} {:?}", actor.id, actor.role, action, resource);
+                Ok(())
+            }
+            Decision::Deny(reason) => {
+                warn!("authz deny: user {} ({}) {:?} {:?}: {}", actor.id, actor.role, action, resource, reason);
+                Err(ApiError::forbidden(reason))
+            }
+        }
+    }
+}
+
+/// What an actor needs for a rule to allow the action
+#[derive(Debug, Clone, Copy)]
+enum Requirement {
+    Permission(Permission),
+    /// Only the actor the resource belongs to, whatever they hold
+    Owner,
+    /// The resource belongs to the actor, or the actor holds the permission
+    OwnerOr(Permission),
+    /// As `OwnerOr`, or the task was shared with the actor at this level or above
+    SharedOr(TaskPermission, Permission),
+}
+
+struct Rule {
+    resource: ResourceKind,
+    action: Action,
+    requires: Requirement,
+    denied: &'static str,
+}
+
+const fn rule(resource: ResourceKind, action: Action, requires: Requirement, denied: &'static str) -> Rule {
+    Rule { resource, action, requires, denied }
+}
+
+use Permission as P;
+use Requirement::{OwnerOr, SharedOr};
+
+/// Anything not listed here is denied
+const RULES: &[Rule] = &[
+    rule(ResourceKind::Users, Action::Create, Requirement::Permission(P::UsersCreate), "Only administrators can create user accounts"),
+    rule(ResourceKind::User, Action::Read, OwnerOr(P::UsersReadAny), "You can only view your own profile"),
+    rule(ResourceKind::Task, Action::Read, SharedOr(TaskPermission::View, P::TasksReadAny), "You do not have access to this task"),
+    rule(ResourceKind::Task, Action::Update, SharedOr(TaskPermission::Edit, P::TasksWriteAny), "You do not have permission to edit this task"),
+    rule(ResourceKind::Task, Action::Share, OwnerOr(P::TasksWriteAny), "Only the task owner can share it"),
+    rule(ResourceKind::Task, Action::Transfer, OwnerOr(P::TasksWriteAny), "You can only transfer your own tasks"),
+    rule(ResourceKind::Task, Action::Duplicate, OwnerOr(P::TasksWriteAny), "You can only duplicate your own tasks"),
+    rule(ResourceKind::UserTasks, Action::List, OwnerOr(P::TasksReadAny), "You can only view your own tasks"),
+    rule(ResourceKind::AllTasks, Action::List, Requirement::Permission(P::TasksReadAny), "Only administrators can view all tasks"),
+    rule(ResourceKind::Template, Action::Read, OwnerOr(P::TasksReadAny), "You can only view your own templates"),
+    rule(ResourceKind::Template, Action::Instantiate, Requirement::Owner, "You can only use your own templates"),
+    rule(ResourceKind::Template, Action::Delete, OwnerOr(P::TasksWriteAny), "You can only delete your own templates"),
+    rule(ResourceKind::Note, Action::Read, OwnerOr(P::NotesReadAny), "You can only view your own notes"),
+    rule(ResourceKind::Note, Action::Update, OwnerOr(P::NotesWriteAny), "You can only edit your own notes"),
+    rule(ResourceKind::Note, Action::Delete, OwnerOr(P::NotesWriteAny), "You can only delete your own notes"),
+    rule(ResourceKind::UserNotes, Action::List, OwnerOr(P::NotesReadAny), "You can only view your own notes"),
+    rule(ResourceKind::Notebook, Action::Read, OwnerOr(P::NotesReadAny), "You can only view your own notebooks"),
+    rule(ResourceKind::Notebook, Action::Update, OwnerOr(P::NotesWriteAny), "You can only edit your own notebooks"),
+    rule(ResourceKind::Notebook, Action::Delete, OwnerOr(P::NotesWriteAny), "You can only delete your own notebooks"),
+    rule(ResourceKind::ShareLink, Action::Create, OwnerOr(P::SharesManageAny), "You can only share your own tasks and notes"),
+    rule(ResourceKind::ShareLink, Action::Delete, OwnerOr(P::SharesRevokeAny), "You can only revoke your own share links"),
+    rule(ResourceKind::Roles, Action::Manage, Requirement::Permission(P::RolesManage), "Only administrators can manage roles"),
+    rule(ResourceKind::System, Action::Administer, Requirement::Permission(P::SystemAdmin), "Admin access required"),
+];
+
+/// Role and ownership rules used by the API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultPolicy;
+
+impl Policy for DefaultPolicy {
+    fn can(&self, actor: &Actor, action: Action, resource: &Resource) -> Decision {
+        let Some(rule) = RULES.iter().find(|r| r.resource == resource.kind() && r.action == action) else {
+            return Decision::Deny("Action not permitted");
+        };
+
+        let is_owner = resource.owner_id() == Some(actor.id);
+        let allowed = match rule.requires {
+            Requirement::Permission(permission) => actor.has(permission),
+            Requirement::Owner => is_owner,
+            OwnerOr(permission) => is_owner || actor.has(permission),
+            SharedOr(required, permission) => {
+                let shared = match resource {
+                    Resource::Task { shared, .. } => *shared,
+                    _ => None,
+                };
+                is_owner || actor.has(permission) || shared.is_some_and(|granted| granted >= required)
+            }
+        };
+
+        if allowed {
+            Decision::Allow
+        } else if let (SharedOr(..), Resource::Task { shared: None, .. }) = (rule.requires, resource) {
+            // Without any share the task is simply not visible to this user
+            Decision::Deny("You do not have access to this task")
+        } else {
+            Decision::Deny(rule.denied)
+        }
+    }
+}
+
+/// Check an action against the default policy, logging the decision
+pub fn authorize(actor: &Actor, action: Action, resource: &Resource) -> Result<()> {
+    DefaultPolicy.authorize(actor, action, resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor(permissions: &[Permission]) -> Actor {
+        Actor { id: Uuid::new_v4(), role: "test".to_string(), permissions: permissions.to_vec() }
+    }
+
+    fn allowed(actor: &Actor, action: Action, resource: Resource) -> bool {
+        DefaultPolicy.can(actor, action, &resource).is_allowed()
+    }
+
+    /// Resources owned by `owner_id` with the permission that overrides ownership
+    fn owned(owner_id: Uuid) -> Vec<(Action, Resource, Permission)> {
+        let task = Resource::Task { owner_id, shared: None };
+        vec![
+            (Action::Read, Resource::User { id: owner_id }, P::UsersReadAny),
+            (Action::Read, task, P::TasksReadAny),
+            (Action::Update, task, P::TasksWriteAny),
+            (Action::Share, task, P::TasksWriteAny),
+            (Action::Transfer, task, P::TasksWriteAny),
+            (Action::Duplicate, task, P::TasksWriteAny),
+            (Action::List, Resource::UserTasks { owner_id }, P::TasksReadAny),
+            (Action::Read, Resource::Template { owner_id }, P::TasksReadAny),
+            (Action::Delete, Resource::Template { owner_id }, P::TasksWriteAny),
+            (Action::Read, Resource::Note { owner_id }, P::NotesReadAny),
+            (Action::Update, Resource::Note { owner_id }, P::NotesWriteAny),
+            (Action::Delete, Resource::Note { owner_id }, P::NotesWriteAny),
+            (Action::List, Resource::UserNotes { owner_id }, P::NotesReadAny),
+            (Action::Read, Resource::Notebook { owner_id }, P::NotesReadAny),
+            (Action::Update, Resource::Notebook { owner_id }, P::NotesWriteAny),
+            (Action::Delete, Resource::Notebook { owner_id }, P::NotesWriteAny),
+            (Action::Create, Resource::ShareLink { owner_id }, P::SharesManageAny),
+            (Action::Delete, Resource::ShareLink { owner_id }, P::SharesRevokeAny),
+        ]
+    }
+
+    #[test]
+    fn owners_may_act_on_their_own_resources() {
+        let owner = actor(&[]);
+        for (action, resource, _) in owned(owner.id) {
+            assert!(allowed(&owner, action, resource), "{:?} {:?}", action, resource);
+        }
+        assert!(allowed(&owner, Action::Instantiate, Resource::Template { owner_id: owner.id }));
+    }
+
+    #[test]
+    fn other_users_are_denied() {
+        let stranger = actor(&[]);
+        for (action, resource, _) in owned(Uuid::new_v4()) {
+            assert!(!allowed(&stranger, action, resource), "{:?} {:?}", action, resource);
+        }
+    }
+
+    #[test]
+    fn permissions_override_ownership() {
+        for (action, resource, permission) in owned(Uuid::new_v4()) {
+            assert!(allowed(&actor(&[permission]), action, resource), "{:?} {:?}", action, resource);
+
+            let others: Vec<Permission> = Permission::ALL.into_iter().filter(|p| *p != permission).collect();
+            assert!(!allowed(&actor(&others), action, resource), "{:?} {:?} without {}", action, resource, permission);
+        }
+    }
+
+    #[test]
+    fn templates_are_only_used_by_their_owner() {
+        let admin = actor(&Permission::ALL);
+        assert!(!allowed(&admin, Action::Instantiate, Resource::Template { owner_id: Uuid::new_v4() }));
+    }
+
+    #[test]
+    fn shares_grant_task_access_by_level() {
+        let collaborator = actor(&[]);
+        let task = |shared| Resource::Task { owner_id: Uuid::new_v4(), shared: Some(shared) };
+
+        assert!(allowed(&collaborator, Action::Read, task(TaskPermission::View)));
+        assert!(allowed(&collaborator, Action::Read, task(TaskPermission::Comment)));
+        assert!(!allowed(&collaborator, Action::Update, task(TaskPermission::View)));
+        assert!(!allowed(&collaborator, Action::Update, task(TaskPermission::Comment)));
+        assert!(allowed(&collaborator, Action::Update, task(TaskPermission::Edit)));
+
+        // Sharing never passes on the owner's rights
+        for action in [Action::Share, Action::Transfer, Action::Duplicate, Action::Delete] {
+            assert!(!allowed(&collaborator, action, task(TaskPermission::Edit)), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn unshared_tasks_look_inaccessible() {
+        let owner_id = Uuid::new_v4();
+        let unshared = Resource::Task { owner_id, shared: None };
+        let viewable = Resource::Task { owner_id, shared: Some(TaskPermission::View) };
+
+        assert_eq!(DefaultPolicy.can(&actor(&[]), Action::Update, &unshared), Decision::Deny("You do not have access to this task"));
+        assert_eq!(DefaultPolicy.can(&actor(&[]), Action::Update, &viewable), Decision::Deny("You do not have permission to edit this task"));
+    }
+
+    #[test]
+    fn global_resources_need_the_permission() {
+        let cases = [
+            (Action::Create, Resource::Users, P::UsersCreate),
+            (Action::List, Resource::AllTasks, P::TasksReadAny),
+            (Action::Manage, Resource::Roles, P::RolesManage),
+            (Action::Administer, Resource::System, P::SystemAdmin),
+        ];
+        for (action, resource, permission) in cases {
+            assert!(allowed(&actor(&[permission]), action, resource), "{:?} {:?}", action, resource);
+            assert!(!allowed(&actor(&[]), action, resource), "{:?} {:?}", action, resource);
+        }
+    }
+
+    #[test]
+    fn unlisted_actions_are_denied() {
+        let admin = actor(&Permission::ALL);
+        assert_eq!(DefaultPolicy.can(&admin, Action::Delete, &Resource::Users), Decision::Deny("Action not permitted"));
+        assert!(!allowed(&admin, Action::Update, Resource::User { id: admin.id }));
+        assert!(!allowed(&admin, Action::Create, Resource::Note { owner_id: admin.id }));
+    }
+}
@@ -1,5 +1,9 @@
 pub fn task_key(id: &uuid::Uuid) -> String { format!("task:{}", id) }
+pub fn task_html_key(id: &uuid::Uuid) -> String { format!("task_html:{}", id) }
 pub fn user_tasks_key(user_id: &uuid::Uuid) -> String { format!("user_tasks:{}", user_id) }
 pub fn all_tasks_key() -> String { "tasks:all".to_string() }
-
-
+pub fn login_failures_key(scope: &str) -> String { format!("login_failures:{}", scope) }
+pub fn login_lock_key(scope: &str) -> String { format!("login_lock:{}", scope) }
+pub fn session_key(id: &uuid::Uuid) -> String { format!("session:{}", id) }
+pub fn magic_link_requests_key(email: &str) -> String { format!("magic_link_requests:{}", email) }
+pub fn verification_resends_key(email: &str) -> String { format!("verification_resends:{}", email) }
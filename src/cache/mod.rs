@@ -2,6 +2,6 @@ pub mod redis_cache;
 pub mod keys;
 
 pub use redis_cache::RedisCache;
-pub use keys::{task_key, user_tasks_key, all_tasks_key};
+pub use keys::{task_key, task_html_key, user_tasks_key, all_tasks_key, login_failures_key, login_lock_key, session_key, magic_link_requests_key, verification_resends_key};
 
 
@@ -1,4 +1,4 @@
-use redis::{aio::ConnectionManager, AsyncCommands};
+use redis::{aio::ConnectionManager, AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt;
 
@@ -50,11 +50,56 @@ impl RedisCache {
         Ok(())
     }
 
+    /// Like `set_json_with_ttl`, but leaves an existing value alone. Returns
+    /// whether the value was written.
+    pub async fn set_json_if_absent_with_ttl<T: Serialize>(&self, key: &str, value: &T, ttl_secs: u64) -> redis::RedisResult<bool> {
+        let mut con = self.manager.clone();
+        let payload = serde_json::to_string(value)
+            .map_err(|_| redis::RedisError::from((redis::ErrorKind::TypeError, "serde encode error")))?;
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::EX(ttl_secs as usize));
+        let written: Option<String> = con.set_options(key, payload, options).await?;
+        Ok(written.is_some())
+    }
+
     pub async fn del(&self, key: &str) -> redis::RedisResult<()> {
         let mut con = self.manager.clone();
         let _: () = con.del(key).await?;
         Ok(())
     }
-}
 
+    /// Increment a counter; a new counter expires after `ttl_secs`
+    pub async fn incr_with_ttl(&self, key: &str, ttl_secs: u64) -> redis::RedisResult<i64> {
+        let mut con = self.manager.clone();
+        let count: i64 = con.incr(key, 1).await?;
+        if count == 1 {
+            let _: () = con.expire(key, ttl_secs as i64).await?;
+        }
+        Ok(count)
+    }
+
+    pub async fn expire(&self, key: &str, ttl_secs: u64) -> redis::RedisResult<()> {
+        let mut con = self.manager.clone();
+        let _: () = con.expire(key, ttl_secs as i64).await?;
+        Ok(())
+    }
+
+    /// Seconds until `key` expires; `None` when it is missing or never expires
+    pub async fn ttl(&self, key: &str) -> redis::RedisResult<Option<u64>> {
+        let mut con = self.manager.clone();
+        let ttl: i64 = con.ttl(key).await?;
+        Ok(u64::try_from(ttl).ok().filter(|secs| *secs > 0))
+    }
 
+    /// Every key matching a glob `pattern`, walked with SCAN rather than KEYS
+    pub async fn scan_keys(&self, pattern: &str) -> redis::RedisResult<Vec<String>> {
+        let mut con = self.manager.clone();
+        let mut keys = Vec::new();
+        let mut iter: redis::AsyncIter<String> = con.scan_match(pattern).await?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+}
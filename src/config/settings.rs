@@ -7,6 +7,9 @@ pub struct AppConfig {
     pub logging: LoggingConfig,
     pub auth: AuthConfig,
     pub redis: RedisConfig,
+    pub notes: NotesConfig,
+    pub mail: MailConfig,
+    pub oidc: OidcConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +17,10 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub cors_origins: Vec<String>,
+    /// Proxies in front of the app that append to `X-Forwarded-For`; the
+    /// client address is taken that many entries from the right. 0 ignores
+    /// proxy headers, which is the only safe choice without a proxy.
+    pub trusted_proxy_hops: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,10 +38,56 @@ pub struct LoggingConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
+    /// HS256 secret; unused once `jwt_private_key_path` is set
     pub jwt_secret: String,
+    /// PEM private key (RSA or Ed25519) to sign tokens with instead of the secret
+    pub jwt_private_key_path: Option<String>,
+    /// PEM keys whose tokens are still accepted, e.g. the previous signing key
+    pub jwt_verification_key_paths: Vec<String>,
     pub issuer: String,
     pub audience: String,
     pub expiry_minutes: u64,
+    /// How long a login lasts without being refreshed; each refresh extends it
+    pub session_ttl_days: u64,
+    pub password_reset_ttl_minutes: u64,
+    pub email_verification: EmailVerificationGate,
+    pub email_verification_ttl_minutes: u64,
+    /// Minimum gap between two verification emails to the same address
+    pub email_verification_resend_secs: u64,
+    /// Issuer shown next to the account in authenticator apps
+    pub mfa_issuer: String,
+    pub lockout: LockoutConfig,
+    pub magic_link: MagicLinkConfig,
+}
+
+/// Failed-login throttling. Reaching a threshold locks the account (or IP)
+/// for `base_lock_secs`, doubling with every further failure up to
+/// `max_lock_secs`; failures are forgotten after `failure_window_secs` of quiet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockoutConfig {
+    pub account_threshold: u32,
+    pub ip_threshold: u32,
+    pub base_lock_secs: u64,
+    pub max_lock_secs: u64,
+    pub failure_window_secs: u64,
+}
+
+/// Passwordless sign-in links. An address can ask for at most
+/// `max_requests` links per `request_window_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkConfig {
+    pub ttl_minutes: u64,
+    pub max_requests: u32,
+    pub request_window_secs: u64,
+}
+
+/// What an account with an unverified email address is kept from doing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailVerificationGate {
+    None,
+    Login,
+    TaskCreation,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +96,98 @@ pub struct RedisConfig {
     pub ttl_secs: u64,
 }
 
+/// Note revision retention: the newest `revisions_keep_last` revisions are
+/// always kept, older ones only as the last revision of each day within
+/// `revisions_keep_daily_days` days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotesConfig {
+    pub revisions_keep_last: u32,
+    pub revisions_keep_daily_days: u32,
+}
+
+/// How outgoing email is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MailTransport {
+    Smtp,
+    /// Log messages (and optionally write them to `outbox_dir`) instead of sending
+    Log,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub from: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Use STARTTLS; off for local catch-all servers like MailHog
+    pub smtp_starttls: bool,
+    pub outbox_dir: Option<String>,
+    /// Base URL of the web app, used to build links in emails
+    pub app_base_url: String,
+}
+
+/// OpenID Connect providers users can sign in with
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OidcConfig {
+    pub providers: Vec<OidcProviderConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderConfig {
+    /// Identifies the provider in URLs
+    pub name: String,
+    pub display_name: String,
+    /// Discovery runs against `{issuer}/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    /// Unset for public clients, which rely on PKCE alone
+    pub client_secret: Option<String>,
+    /// Registered with the provider; receives `code` and `state`
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    /// Accept the email as verified without an `email_verified` claim, for
+    /// company IdPs that only hand out addresses they own
+    pub trust_email: bool,
+}
+
+impl OidcConfig {
+    /// `OIDC_PROVIDERS=corp,google` plus `OIDC_CORP_ISSUER`, `OIDC_CORP_CLIENT_ID`, ...
+    fn from_env(app_base_url: &str) -> Self {
+        let names = std::env::var("OIDC_PROVIDERS").unwrap_or_default();
+        let providers = names
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                let var = |key: &str| std::env::var(format!("OIDC_{}_{}", name.to_uppercase(), key)).ok();
+                let required = |key: &str| var(key)
+                    .unwrap_or_else(|| panic!("OIDC_{}_{} must be set", name.to_uppercase(), key));
+
+                OidcProviderConfig {
+                    display_name: var("DISPLAY_NAME").unwrap_or_else(|| name.clone()),
+                    issuer: required("ISSUER").trim_end_matches('/').to_string(),
+                    client_id: required("CLIENT_ID"),
+                    client_secret: var("CLIENT_SECRET"),
+                    redirect_uri: var("REDIRECT_URI")
+                        .unwrap_or_else(|| format!("{}/auth/oidc/{}/callback", app_base_url, name)),
+                    scopes: var("SCOPES")
+                        .unwrap_or_else(|| "openid email profile".to_string())
+                        .split_whitespace()
+                        .map(str::to_string)
+                        .collect(),
+                    trust_email: var("TRUST_EMAIL").is_some_and(|v| v == "true"),
+                    name,
+                }
+            })
+            .collect();
+
+        OidcConfig { providers }
+    }
+}
+
 impl AppConfig {
     pub fn from_env() -> Self {
         let host = std::env::var("APP_HOST")
@@ -65,16 +210,63 @@ impl AppConfig {
 
         let level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
         let format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string());
-        let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+        let jwt_private_key_path = std::env::var("JWT_PRIVATE_KEY_PATH").ok().filter(|v| !v.is_empty());
+        let jwt_secret = match std::env::var("JWT_SECRET") {
+            Ok(secret) => secret,
+            Err(_) if jwt_private_key_path.is_some() => String::new(),
+            Err(_) => panic!("JWT_SECRET must be set unless JWT_PRIVATE_KEY_PATH is"),
+        };
+        let jwt_verification_key_paths: Vec<String> = std::env::var("JWT_VERIFICATION_KEY_PATHS")
+            .map(|v| v.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect())
+            .unwrap_or_default();
         let issuer = std::env::var("JWT_ISSUER").unwrap_or_else(|_| "note-task-api".to_string());
         let audience = std::env::var("JWT_AUDIENCE").unwrap_or_else(|_| "note-clients".to_string());
         let expiry_minutes: u64 = std::env::var("JWT_EXP_MINUTES").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+        let session_ttl_days: u64 = std::env::var("SESSION_TTL_DAYS").ok().and_then(|v| v.parse().ok()).unwrap_or(30);
+        let password_reset_ttl_minutes: u64 = std::env::var("PASSWORD_RESET_TTL_MINUTES").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+
+        let email_verification = match std::env::var("EMAIL_VERIFICATION_REQUIRED").unwrap_or_else(|_| "none".to_string()).as_str() {
+            "none" => EmailVerificationGate::None,
+            "login" => EmailVerificationGate::Login,
+            "task_creation" => EmailVerificationGate::TaskCreation,
+            other => panic!("EMAIL_VERIFICATION_REQUIRED must be none, login or task_creation, got {}", other),
+        };
+        let email_verification_ttl_minutes: u64 = std::env::var("EMAIL_VERIFICATION_TTL_MINUTES").ok().and_then(|v| v.parse().ok()).unwrap_or(24 * 60);
+        let email_verification_resend_secs: u64 = std::env::var("EMAIL_VERIFICATION_RESEND_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(60);
+        let mfa_issuer = std::env::var("MFA_ISSUER").unwrap_or_else(|_| "Note Task API".to_string());
+        let lockout = LockoutConfig {
+            account_threshold: std::env::var("LOGIN_LOCKOUT_THRESHOLD").ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            ip_threshold: std::env::var("LOGIN_LOCKOUT_IP_THRESHOLD").ok().and_then(|v| v.parse().ok()).unwrap_or(20),
+            base_lock_secs: std::env::var("LOGIN_LOCKOUT_BASE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            max_lock_secs: std::env::var("LOGIN_LOCKOUT_MAX_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600),
+            failure_window_secs: std::env::var("LOGIN_FAILURE_WINDOW_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(900),
+        };
+        let magic_link = MagicLinkConfig {
+            ttl_minutes: std::env::var("MAGIC_LINK_TTL_MINUTES").ok().and_then(|v| v.parse().ok()).unwrap_or(15),
+            max_requests: std::env::var("MAGIC_LINK_MAX_REQUESTS").ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            request_window_secs: std::env::var("MAGIC_LINK_WINDOW_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(900),
+        };
+
+        let transport = match std::env::var("MAIL_TRANSPORT").unwrap_or_else(|_| "log".to_string()).as_str() {
+            "smtp" => MailTransport::Smtp,
+            "log" => MailTransport::Log,
+            other => panic!("MAIL_TRANSPORT must be smtp or log, got {}", other),
+        };
+
+        // TRUST_PROXY_HEADERS=true is kept as shorthand for a single proxy
+        let trusted_proxy_hops: usize = std::env::var("TRUSTED_PROXY_HOPS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| usize::from(std::env::var("TRUST_PROXY_HEADERS").is_ok_and(|v| v == "true")));
+        let app_base_url = std::env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let oidc = OidcConfig::from_env(&app_base_url);
 
         AppConfig {
             server: ServerConfig {
                 host,
                 port,
                 cors_origins: vec!["*".to_string()],
+                trusted_proxy_hops,
             },
             database: DatabaseConfig {
                 database_url,
@@ -85,11 +277,42 @@ impl AppConfig {
                 level,
                 format
             },
-            auth: AuthConfig { jwt_secret, issuer, audience, expiry_minutes },
+            auth: AuthConfig {
+                jwt_secret,
+                jwt_private_key_path,
+                jwt_verification_key_paths,
+                issuer,
+                audience,
+                expiry_minutes,
+                session_ttl_days,
+                password_reset_ttl_minutes,
+                email_verification,
+                email_verification_ttl_minutes,
+                email_verification_resend_secs,
+                mfa_issuer,
+                lockout,
+                magic_link,
+            },
             redis: RedisConfig {
                 url: std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
                 ttl_secs: std::env::var("REDIS_TTL_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(300),
             },
+            notes: NotesConfig {
+                revisions_keep_last: std::env::var("NOTE_REVISIONS_KEEP_LAST").ok().and_then(|v| v.parse().ok()).unwrap_or(50),
+                revisions_keep_daily_days: std::env::var("NOTE_REVISIONS_KEEP_DAILY_DAYS").ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            },
+            mail: MailConfig {
+                transport,
+                from: std::env::var("MAIL_FROM").unwrap_or_else(|_| "Note Task API <no-reply@localhost>".to_string()),
+                smtp_host: std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string()),
+                smtp_port: std::env::var("SMTP_PORT").ok().and_then(|v| v.parse().ok()).unwrap_or(1025),
+                smtp_username: std::env::var("SMTP_USERNAME").ok(),
+                smtp_password: std::env::var("SMTP_PASSWORD").ok(),
+                smtp_starttls: std::env::var("SMTP_STARTTLS").map(|v| v == "true").unwrap_or(false),
+                outbox_dir: std::env::var("MAIL_OUTBOX_DIR").ok(),
+                app_base_url,
+            },
+            oidc,
         }
     }
 }
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+
+use super::role::Permission;
+
+/// Every API key starts with this, which is how `auth_middleware` tells them from JWTs
+pub const API_KEY_PREFIX: &str = "ntk_";
+
+/// What an API key may be used for. Write scopes include the matching read scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiScope {
+    #[serde(rename = "tasks:read")]
+    TasksRead,
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "notes:read")]
+    NotesRead,
+    #[serde(rename = "notes:write")]
+    NotesWrite,
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+}
+
+impl ApiScope {
+    pub const ALL: [ApiScope; 5] = [
+        ApiScope::TasksRead,
+        ApiScope::TasksWrite,
+        ApiScope::NotesRead,
+        ApiScope::NotesWrite,
+        ApiScope::ProfileRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::TasksRead => "tasks:read",
+            ApiScope::TasksWrite => "tasks:write",
+            ApiScope::NotesRead => "notes:read",
+            ApiScope::NotesWrite => "notes:write",
+            ApiScope::ProfileRead => "profile:read",
+        }
+    }
+
+    /// Whether holding this scope is enough for `required`
+    pub fn grants(self, required: ApiScope) -> bool {
+        self == required
+            || matches!(
+                (self, required),
+                (ApiScope::TasksWrite, ApiScope::TasksRead) | (ApiScope::NotesWrite, ApiScope::NotesRead)
+            )
+    }
+
+    /// Role permissions an API key with this scope keeps; everything else is dropped
+    fn covers(self, permission: Permission) -> bool {
+        match permission {
+            Permission::TasksReadAny => self.grants(ApiScope::TasksRead),
+            Permission::TasksWriteAny => self.grants(ApiScope::TasksWrite),
+            Permission::NotesReadAny => self.grants(ApiScope::NotesRead),
+            Permission::NotesWriteAny => self.grants(ApiScope::NotesWrite),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiScope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("Invalid scope: {}", s))
+    }
+}
+
+/// The part of a user's role permissions that requests made with an API key get
+pub fn scoped_permissions(permissions: Vec<Permission>, scopes: &[ApiScope]) -> Vec<Permission> {
+    permissions
+        .into_iter()
+        .filter(|permission| scopes.iter().any(|scope| scope.covers(*permission)))
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_hint: String,
+    pub scopes: Vec<ApiScope>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Returned once on creation; the plain key cannot be recovered later
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -41,6 +41,9 @@ pub enum ApiError {
     
     #[error("Not found: {0}")]
     NotFound(String),
+
+    #[error("Too many requests: {message}")]
+    TooManyRequests { message: String, retry_after_secs: u64 },
 }
 
 impl IntoResponse for ApiError {
@@ -57,6 +60,7 @@ impl IntoResponse for ApiError {
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
             ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
             ApiError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            ApiError::TooManyRequests { .. } => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
         };
 
         let body = Json(json!({
@@ -65,6 +69,10 @@ impl IntoResponse for ApiError {
             "status": status.as_u16()
         }));
 
+        if let ApiError::TooManyRequests { retry_after_secs, .. } = self {
+            return (status, [(header::RETRY_AFTER, retry_after_secs.to_string())], body).into_response();
+        }
+
         (status, body).into_response()
     }
 }
@@ -127,4 +135,8 @@ impl ApiError {
     pub fn internal_error(message: impl Into<String>) -> Self {
         Self::InternalError(message.into())
     }
+
+    pub fn too_many_requests(message: impl Into<String>, retry_after_secs: u64) -> Self {
+        Self::TooManyRequests { message: message.into(), retry_after_secs }
+    }
 }
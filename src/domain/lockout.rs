@@ -0,0 +1,12 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// An account that failed too many logins, as listed for admins. The email
+/// is what was typed at login, so it may not belong to any user.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountLockout {
+    pub email: String,
+    pub user_id: Option<Uuid>,
+    pub failed_attempts: i64,
+    pub retry_after_secs: u64,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Body of `POST /auth/magic-link`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+/// Handed to the client that asked for a link, whether or not the address
+/// has an account. The link only works together with `device_code`, so it
+/// is useless on any other device.
+#[derive(Debug, Clone, Serialize)]
+pub struct MagicLinkIssued {
+    pub device_code: String,
+    pub expires_in_secs: u64,
+}
+
+/// Body of `POST /auth/magic-link/consume`: the token from the emailed link
+/// plus the device code from `MagicLinkIssued`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConsumeMagicLinkRequest {
+    pub token: String,
+    pub device_code: String,
+}
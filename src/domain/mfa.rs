@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A user's TOTP authenticator. Never serialized: the secret stays on the server
+/// once enrolment has started.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserMfa {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub confirmed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl UserMfa {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+/// Response to starting enrolment; shown once, for the authenticator app
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Body of `GET /users/me/mfa`
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaStatus {
+    pub enabled: bool,
+    /// Whether the user's role makes MFA mandatory
+    pub required: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+/// A TOTP code, or a recovery code where the endpoint accepts one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+/// Fresh recovery codes; the plain codes are only ever returned here
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
@@ -1,11 +1,40 @@
 // Domain module - contains business models and error types
 pub mod user;
+pub mod role;
+pub mod mfa;
+pub mod api_key;
+pub mod lockout;
+pub mod oidc;
+pub mod magic_link;
+pub mod session;
 pub mod task;
+pub mod template;
+pub mod note;
+pub mod notebook;
+pub mod share;
 pub mod error;
 pub mod pagination;
 
-pub use user::{User, CreateUserRequest};
-pub use task::{Task, CreateTaskRequest};
+pub use user::{User, CreateUserRequest, UpdateProfileRequest};
+pub use role::{Role, Permission, CreateRoleRequest, UpdateRoleRequest, AssignRoleRequest, SetMfaRequirementRequest};
+pub use api_key::{ApiKey, ApiScope, CreateApiKeyRequest, CreatedApiKey};
+pub use lockout::AccountLockout;
+pub use session::{Session, ClientInfo};
+pub use oidc::{OidcProvider, OidcAuthorization, OidcCallbackRequest, OidcAuthRequest};
+pub use magic_link::{MagicLinkRequest, MagicLinkIssued, ConsumeMagicLinkRequest};
+pub use mfa::{UserMfa, MfaEnrollment, MfaStatus, MfaCodeRequest, RecoveryCodes};
+pub use task::{Task, CreateTaskRequest, UpdateTaskRequest, ChecklistItem, TaskTransfer, RenderedTask,
+    TaskPermission, TaskShare, ShareTaskRequest,
+};
+pub use note::{
+    Note, CreateNoteRequest, UpdateNoteRequest, MoveNoteRequest, NoteRevision, NoteRevisionSummary, NoteDiff, DiffFormat,
+    NoteRef, NoteEdge, NoteGraph, NoteTaskLink, ExtractTasksRequest, ExtractTasksResponse,
+};
+pub use notebook::{
+    Notebook, NotebookWithCounts, CreateNotebookRequest, RenameNotebookRequest, MoveNotebookRequest, NotebookDeleteMode,
+};
+pub use share::{ShareLink, ShareResourceType, CreateShareLinkRequest, CreatedShareLink, PublicShareView};
+pub use template::{TaskTemplate, CreateTaskTemplateRequest, InstantiateTemplateRequest};
 pub use error::{ApiError, Result};
 pub use pagination::{
     PaginationParams, TaskFilters, TaskQueryParams, 
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::Task;
+
+/// Prefix of a note slug used when nothing of the title survives slugification
+pub const NOTE_FALLBACK_SLUG_PREFIX: &str = "note-";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Note {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub notebook_id: Option<Uuid>,
+    pub title: String,
+    pub body: String,
+    pub slug: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNoteRequest {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub notebook_id: Option<Uuid>,
+}
+
+/// Body for `POST /notes/:id/move`; a null `notebook_id` moves the note to the top level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveNoteRequest {
+    pub notebook_id: Option<Uuid>,
+}
+
+/// Partial update for `PATCH /notes/:id`; omitted fields are left unchanged
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateNoteRequest {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    /// On rename, also update `[[old title]]` links in notes referencing this one
+    #[serde(default)]
+    pub rewrite_links: bool,
+}
+
+/// Minimal view of a note, used for backlinks and graph nodes
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteRef {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+}
+
+/// A resolved `[[link]]` from `source` to `target`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+}
+
+/// Response of `GET /notes/graph`
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteGraph {
+    pub nodes: Vec<NoteRef>,
+    pub edges: Vec<NoteEdge>,
+}
+
+/// A saved version of a note
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteRevision {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub revision: i32,
+    pub title: String,
+    pub body: String,
+    pub author_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Revision listing entry, without the body
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteRevisionSummary {
+    pub revision: i32,
+    pub title: String,
+    pub author_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffFormat {
+    /// Classic line-based unified diff
+    #[default]
+    Unified,
+    /// Word-level change segments, for inline highlighting
+    Words,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Difference between two revisions of a note
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteDiff {
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub format: DiffFormat,
+    /// Present when the title changed between the two revisions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TitleChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<DiffSegment>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleChange {
+    pub from: String,
+    pub to: String,
+}
+
+impl NoteDiff {
+    pub fn between(from: &NoteRevision, to: &NoteRevision, format: DiffFormat) -> Self {
+        let title = (from.title != to.title).then(|| TitleChange {
+            from: from.title.clone(),
+            to: to.title.clone(),
+        });
+
+        let (unified, segments) = match format {
+            DiffFormat::Unified => {
+                let diff = TextDiff::from_lines(&from.body, &to.body);
+                let unified = diff
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&format!("revision {}", from.revision), &format!("revision {}", to.revision))
+                    .to_string();
+                (Some(unified), None)
+            }
+            DiffFormat::Words => (None, Some(word_diff(&from.body, &to.body))),
+        };
+
+        Self {
+            from_revision: from.revision,
+            to_revision: to.revision,
+            format,
+            title,
+            unified,
+            segments,
+        }
+    }
+}
+
+/// Word-level diff with consecutive changes of the same kind merged
+fn word_diff(from: &str, to: &str) -> Vec<DiffSegment> {
+    let diff = TextDiff::from_words(from, to);
+    let mut segments: Vec<DiffSegment> = Vec::new();
+
+    for change in diff.iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Insert => DiffOp::Insert,
+            ChangeTag::Delete => DiffOp::Delete,
+        };
+        match segments.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => segments.push(DiffSegment { op, text: change.value().to_string() }),
+        }
+    }
+    segments
+}
+
+/// A task created from a task-list item of a note
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteTaskLink {
+    pub task_id: Uuid,
+    pub note_id: Uuid,
+    pub item_text: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Options for `POST /notes/:id/extract-tasks`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractTasksRequest {
+    /// Also create (completed) tasks for items that are already ticked
+    #[serde(default)]
+    pub include_checked: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractTasksResponse {
+    pub created: Vec<Task>,
+    /// Items skipped because a task was extracted from them before
+    pub already_linked: usize,
+}
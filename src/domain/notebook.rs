@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Deepest allowed nesting; a top-level notebook has depth 1
+pub const MAX_NOTEBOOK_DEPTH: i32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notebook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Notebook listing entry with the number of notes it holds
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotebookWithCounts {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    /// Notes filed directly in this notebook
+    pub note_count: i64,
+    /// Notes in this notebook and all of its sub-notebooks
+    pub total_note_count: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNotebookRequest {
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameNotebookRequest {
+    pub name: String,
+}
+
+/// Body for `POST /notebooks/:id/move`; a null `parent_id` makes it top level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveNotebookRequest {
+    pub parent_id: Option<Uuid>,
+}
+
+/// What happens to the contents of a deleted notebook
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotebookDeleteMode {
+    /// Notes and sub-notebooks move up to the deleted notebook's parent
+    #[default]
+    Rehome,
+    /// Notes and sub-notebooks are deleted with it
+    Cascade,
+}
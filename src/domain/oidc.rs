@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// A configured OpenID Connect provider, as listed to clients
+#[derive(Debug, Clone, Serialize)]
+pub struct OidcProvider {
+    pub name: String,
+    pub display_name: String,
+}
+
+/// Where to send the browser to sign in with a provider. The provider
+/// redirects back with `code` and this `state`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OidcAuthorization {
+    pub authorization_url: String,
+    pub state: String,
+}
+
+/// What the provider's redirect carried back
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcCallbackRequest {
+    pub code: String,
+    pub state: String,
+}
+
+/// A sign-in started with `OidcAuthorization`, waiting for its callback
+#[derive(Debug, Clone)]
+pub struct OidcAuthRequest {
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
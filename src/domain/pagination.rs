@@ -24,7 +24,7 @@ pub struct PaginationParams {
 }
 
 /// Task filtering parameters
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TaskFilters {
     /// Filter by task status
     pub status: Option<TaskStatus>,
@@ -40,10 +40,13 @@ pub struct TaskFilters {
     
     /// Search in title and description
     pub search: Option<String>,
+
+    /// Only tasks shared with this user
+    pub shared_with: Option<Uuid>,
 }
 
 /// Combined query parameters for tasks
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TaskQueryParams {
     #[serde(flatten)]
     pub pagination: PaginationParams,
@@ -101,27 +104,6 @@ impl Default for PaginationParams {
     }
 }
 
-impl Default for TaskFilters {
-    fn default() -> Self {
-        Self {
-            status: None,
-            user_id: None,
-            created_after: None,
-            created_before: None,
-            search: None,
-        }
-    }
-}
-
-impl Default for TaskQueryParams {
-    fn default() -> Self {
-        Self {
-            pagination: PaginationParams::default(),
-            filters: TaskFilters::default(),
-        }
-    }
-}
-
 impl PaginationParams {
     /// Calculate offset for database queries
     pub fn offset(&self) -> u32 {
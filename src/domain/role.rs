@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+
+/// Role given to new accounts
+pub const DEFAULT_ROLE: &str = "user";
+
+/// A capability granted by a role, stored as text in `role_permissions`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    #[serde(rename = "users:create")]
+    UsersCreate,
+    #[serde(rename = "users:read_any")]
+    UsersReadAny,
+    #[serde(rename = "roles:manage")]
+    RolesManage,
+    #[serde(rename = "tasks:read_any")]
+    TasksReadAny,
+    #[serde(rename = "tasks:write_any")]
+    TasksWriteAny,
+    #[serde(rename = "notes:read_any")]
+    NotesReadAny,
+    #[serde(rename = "notes:write_any")]
+    NotesWriteAny,
+    #[serde(rename = "shares:manage_any")]
+    SharesManageAny,
+    #[serde(rename = "shares:revoke_any")]
+    SharesRevokeAny,
+    #[serde(rename = "system:admin")]
+    SystemAdmin,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 10] = [
+        Permission::UsersCreate,
+        Permission::UsersReadAny,
+        Permission::RolesManage,
+        Permission::TasksReadAny,
+        Permission::TasksWriteAny,
+        Permission::NotesReadAny,
+        Permission::NotesWriteAny,
+        Permission::SharesManageAny,
+        Permission::SharesRevokeAny,
+        Permission::SystemAdmin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::UsersCreate => "users:create",
+            Permission::UsersReadAny => "users:read_any",
+            Permission::RolesManage => "roles:manage",
+            Permission::TasksReadAny => "tasks:read_any",
+            Permission::TasksWriteAny => "tasks:write_any",
+            Permission::NotesReadAny => "notes:read_any",
+            Permission::NotesWriteAny => "notes:write_any",
+            Permission::SharesManageAny => "shares:manage_any",
+            Permission::SharesRevokeAny => "shares:revoke_any",
+            Permission::SystemAdmin => "system:admin",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| format!("Invalid permission: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    /// Seeded roles the application relies on; they cannot be changed
+    pub built_in: bool,
+    /// Members have to use two-factor authentication to sign in
+    pub require_mfa: bool,
+    pub permissions: Vec<Permission>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+}
+
+/// `None` keeps the current value; `permissions` replaces the whole set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub description: Option<String>,
+    pub permissions: Option<Vec<Permission>>,
+}
+
+/// Body of `PUT /roles/:id/mfa`; allowed on built-in roles too
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMfaRequirementRequest {
+    pub required: bool,
+}
+
+/// Body of `PUT /users/:id/role`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignRoleRequest {
+    pub role: String,
+}
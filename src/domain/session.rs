@@ -0,0 +1,24 @@
+use std::net::IpAddr;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A login on some device, as listed at `GET /users/me/sessions`
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_seen_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// The session the listing was requested from
+    pub current: bool,
+}
+
+/// Who a login came from, recorded on its session
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize, Serializer};
+use sqlx::{types::Json, FromRow, Type};
+use uuid::Uuid;
+
+use crate::domain::task::{ChecklistItem, TaskPriority, TaskStatus};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "share_resource_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ShareResourceType {
+    Task,
+    Note,
+}
+
+/// A public read-only link, as seen by its owner
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShareLink {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub resource_type: ShareResourceType,
+    pub resource_id: Uuid,
+    /// First characters of the token, so owners can tell their links apart
+    pub token_hint: String,
+    #[serde(rename = "password_protected", serialize_with = "serialize_is_some", skip_deserializing)]
+    pub password_hash: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub access_count: i64,
+    pub last_accessed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateShareLinkRequest {
+    pub resource_type: ShareResourceType,
+    pub resource_id: Uuid,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Visitors must send it in the `X-Share-Password` header
+    pub password: Option<String>,
+}
+
+/// Returned once on creation; the plain token cannot be recovered later
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedShareLink {
+    #[serde(flatten)]
+    pub link: ShareLink,
+    pub token: String,
+    pub path: String,
+}
+
+/// What an anonymous visitor of a share link gets to see
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublicShareView {
+    Task {
+        title: String,
+        description: Option<String>,
+        description_html: Option<String>,
+        status: TaskStatus,
+        priority: TaskPriority,
+        due_at: Option<chrono::DateTime<chrono::Utc>>,
+        checklist: Json<Vec<ChecklistItem>>,
+        tags: Vec<String>,
+        updated_at: chrono::DateTime<chrono::Utc>,
+    },
+    Note {
+        title: String,
+        body: String,
+        body_html: String,
+        updated_at: chrono::DateTime<chrono::Utc>,
+    },
+}
+
+fn serialize_is_some<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(value.is_some())
+}
@@ -1,6 +1,7 @@
-use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Type};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::{types::Json, FromRow, Type};
 use uuid::Uuid;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[sqlx(type_name = "task_status", rename_all = "snake_case")]
@@ -10,6 +11,30 @@ pub enum TaskStatus {
     Done,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq)]
+#[sqlx(type_name = "task_priority", rename_all = "snake_case")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Urgent,
+}
+
+impl FromStr for TaskPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(TaskPriority::Low),
+            "medium" => Ok(TaskPriority::Medium),
+            "high" => Ok(TaskPriority::High),
+            "urgent" => Ok(TaskPriority::Urgent),
+            _ => Err(format!("Invalid task priority: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Task {
     pub id: Uuid,
@@ -17,15 +42,82 @@ pub struct Task {
     pub description: Option<String>,
     pub slug: String,
     pub status: TaskStatus,
+    pub priority: TaskPriority,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub assignee_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
     pub user_id: Uuid,
+    pub checklist: Json<Vec<ChecklistItem>>,
+    pub tags: Vec<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Task with its description rendered to sanitised HTML (`?render=html`)
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub description_html: Option<String>,
+}
+
+/// A single checklist entry, stored embedded in the task row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub id: Uuid,
+    pub text: String,
+    pub checked: bool,
+    pub position: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTaskRequest {
     pub title: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub assignee_id: Option<Uuid>,
+    /// Makes the new task a subtask of an existing task of the same owner
+    pub parent_id: Option<Uuid>,
+}
+
+/// Partial update for `PATCH /tasks/:id`; omitted fields are left unchanged.
+/// `due_at` and `assignee_id` can also be sent as `null` to clear them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateTaskRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub priority: Option<TaskPriority>,
+    #[serde(default, deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    #[serde(default, deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub assignee_id: Option<Option<Uuid>>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Tell a field sent as `null` (`Some(None)`) apart from one left out (`None`)
+fn nullable<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddChecklistItemRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderChecklistRequest {
+    /// Every item id of the checklist, in the desired order
+    pub item_ids: Vec<Uuid>,
 }
 
 impl Task {
@@ -35,28 +127,258 @@ impl Task {
             id: Uuid::new_v4(),
             title: title.clone(),
             description,
-            slug: slugify(&title),
+            slug: slugify(&title, TASK_FALLBACK_SLUG_PREFIX),
             status: TaskStatus::Todo,
+            priority: TaskPriority::default(),
+            due_at: None,
+            assignee_id: None,
+            parent_id: None,
             user_id,
+            checklist: Json(Vec::new()),
+            tags: Vec::new(),
             created_at: now,
             updated_at: now,
         }
     }
 }
 
-pub fn slugify(title: &str) -> String {
-    title
-        .trim()
+/// Options for `POST /tasks/:id/duplicate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateTaskRequest {
+    /// Title of the copy, defaults to the original title
+    pub title: Option<String>,
+    #[serde(default = "default_true")]
+    pub include_description: bool,
+    #[serde(default = "default_true")]
+    pub include_checklist: bool,
+    #[serde(default = "default_true")]
+    pub include_tags: bool,
+    #[serde(default)]
+    pub include_subtasks: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferTaskRequest {
+    pub to_user_id: Uuid,
+}
+
+/// A transfer waiting for the recipient to accept it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskTransfer {
+    pub task_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Level of access an owner can grant another user on a task
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "task_permission", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPermission {
+    View,
+    Comment,
+    Edit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskShare {
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub permission: TaskPermission,
+    pub granted_by: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Body of `POST /tasks/:id/shares`; sharing again changes the permission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareTaskRequest {
+    pub user_id: Uuid,
+    pub permission: TaskPermission,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for DuplicateTaskRequest {
+    fn default() -> Self {
+        Self {
+            title: None,
+            include_description: true,
+            include_checklist: true,
+            include_tags: true,
+            include_subtasks: false,
+        }
+    }
+}
+
+impl ChecklistItem {
+    pub fn new(text: String, position: i32) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            text,
+            checked: false,
+            position,
+        }
+    }
+}
+
+/// Build checklist items from plain texts, keeping their order
+pub fn build_checklist(texts: &[String]) -> Vec<ChecklistItem> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| ChecklistItem::new(text.trim().to_string(), i as i32))
+        .collect()
+}
+
+/// Trim, lowercase and de-duplicate tags, dropping empty ones and a leading `#`
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Longest base slug we generate; longer titles are cut at a word boundary
+pub const MAX_SLUG_LENGTH: usize = 80;
+
+/// Prefix of a task slug used when nothing of the title survives slugification
+pub const TASK_FALLBACK_SLUG_PREFIX: &str = "task-";
+
+/// Length of the random `[a-z0-9]` suffix appended to a slug that is taken
+pub const SLUG_SUFFIX_LENGTH: usize = 6;
+
+/// Build a URL slug from a title. Never returns an empty string: titles
+/// that transliterate to nothing get a `<fallback_prefix><short id>` slug
+/// instead, e.g. `task-1a2b3c4d`.
+pub fn slugify(title: &str, fallback_prefix: &str) -> String {
+    let slug = slugify_words(title);
+    if slug.is_empty() {
+        let id = Uuid::new_v4().simple().to_string();
+        format!("{}{}", fallback_prefix, &id[..8])
+    } else {
+        slug
+    }
+}
+
+/// Transliterate a title to ASCII (é→e, ß→ss, Cyrillic and Greek to Latin,
+/// CJK to pinyin-style words, emoji to their names), keep `a-z0-9` words
+/// joined by `-`, and truncate to `MAX_SLUG_LENGTH` on a word boundary.
+/// May return an empty string.
+pub fn slugify_words(title: &str) -> String {
+    let ascii = deunicode::deunicode(title.trim())
         .to_lowercase()
-        .chars()
-        .map(|c| match c {
-            'a'..='z' | '0'..='9' => c,
-            ' ' => '-',
-            _ => '-',
-        })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
+        .replace('\'', "");
+
+    let words = ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty());
+
+    let mut slug = String::new();
+    for word in words {
+        let needed = if slug.is_empty() { word.len() } else { word.len() + 1 };
+        if slug.len() + needed > MAX_SLUG_LENGTH {
+            if slug.is_empty() {
+                // A single word longer than the limit is cut hard
+                slug.push_str(&word[..MAX_SLUG_LENGTH]);
+            }
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    slug
+}
+
+/// Whether a slug built from a title whose words slugified to `slug_base`
+/// still fits `title`, in which case a rename keeps it. Rows store that base
+/// next to the slug, so a uniqueness suffix or a fallback slug never has to
+/// be told apart from a title word. Fallback slugs have an empty base.
+pub fn slug_matches_title(slug_base: &str, title: &str) -> bool {
+    slugify_words(title) == slug_base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_transliterates_non_latin_titles() {
+        assert_eq!(slugify("Straße café", TASK_FALLBACK_SLUG_PREFIX), "strasse-cafe");
+        assert_eq!(slugify("Привет мир", TASK_FALLBACK_SLUG_PREFIX), "privet-mir");
+        assert_eq!(slugify("Ελληνικά", TASK_FALLBACK_SLUG_PREFIX), "ellenika");
+        assert_eq!(slugify("北京欢迎你", TASK_FALLBACK_SLUG_PREFIX), "bei-jing-huan-ying-ni");
+        assert_eq!(slugify("L'été", TASK_FALLBACK_SLUG_PREFIX), "lete");
+    }
+
+    #[test]
+    fn slugify_cuts_long_titles_on_a_word_boundary() {
+        let title = format!("{} tail", "word ".repeat(20));
+        let slug = slugify(&title, TASK_FALLBACK_SLUG_PREFIX);
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert!(slug.starts_with("word-word") && slug.ends_with("word"));
+        assert!(!slug.contains("tail"));
+    }
+
+    #[test]
+    fn slugify_cuts_a_single_overlong_word_hard() {
+        let word = "a".repeat(MAX_SLUG_LENGTH + 20);
+        assert_eq!(slugify(&word, TASK_FALLBACK_SLUG_PREFIX), "a".repeat(MAX_SLUG_LENGTH));
+        assert_eq!(slugify(&format!("{} next", word), TASK_FALLBACK_SLUG_PREFIX), "a".repeat(MAX_SLUG_LENGTH));
+    }
+
+    #[test]
+    fn slugify_names_emoji() {
+        assert_eq!(slugify("🎉🚀", TASK_FALLBACK_SLUG_PREFIX), "tada-rocket");
+    }
+
+    #[test]
+    fn slugify_falls_back_when_nothing_survives() {
+        for title in ["★ — ★", "   ", "..."] {
+            let slug = slugify(title, TASK_FALLBACK_SLUG_PREFIX);
+            let id = slug.strip_prefix(TASK_FALLBACK_SLUG_PREFIX).expect("fallback prefix");
+            assert_eq!(id.len(), 8);
+            assert!(id.bytes().all(|b| b.is_ascii_hexdigit()));
+        }
+        assert_ne!(slugify("★", TASK_FALLBACK_SLUG_PREFIX), slugify("★", TASK_FALLBACK_SLUG_PREFIX));
+        assert!(slugify("★", "note-").starts_with("note-"));
+    }
+
+    #[test]
+    fn rename_to_the_same_words_keeps_the_slug() {
+        assert!(slug_matches_title("meeting-notes", "Meeting notes!"));
+        assert!(slug_matches_title("meeting-notes", "  MEETING   Notes "));
+    }
+
+    #[test]
+    fn rename_dropping_a_six_letter_word_gets_a_new_slug() {
+        // "budget" has the shape of a uniqueness suffix, but it is a title word
+        assert!(!slug_matches_title("review-budget", "Review"));
+        assert!(!slug_matches_title("meeting-notes", "Meeting"));
+    }
+
+    #[test]
+    fn suffixed_slug_is_kept_while_the_base_still_fits() {
+        // A slug stored as `review-x1y2z3` because `review` was taken has base `review`
+        assert!(slug_matches_title("review", "Review"));
+        assert!(!slug_matches_title("review", "Review budget"));
+    }
+
+    #[test]
+    fn rename_to_a_title_without_words_leaves_a_word_slug() {
+        assert!(!slug_matches_title("task-list", "★ — ★"));
+    }
+
+    #[test]
+    fn fallback_slug_is_kept_for_another_title_without_words() {
+        assert!(slug_matches_title("", "★ — ★"));
+        assert!(!slug_matches_title("", "Task list"));
+    }
 }
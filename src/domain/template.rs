@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Datelike, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskTemplate {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub title_pattern: String,
+    pub description: Option<String>,
+    pub checklist: Vec<String>,
+    pub default_tags: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaskTemplateRequest {
+    pub name: String,
+    pub title_pattern: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+}
+
+/// Body for `POST /tasks/from-template/:id` - everything is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    /// Values for custom `{{name}}` placeholders
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
+    /// Tags added on top of the template's default tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+static PLACEHOLDER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*\}\}").unwrap());
+
+/// Expand `{{placeholder}}` markers in a template string.
+///
+/// Built-in placeholders are `date`, `time`, `datetime`, `weekday`, `week`,
+/// `month` and `year`; any other name is looked up in `variables`. Unknown
+/// placeholders are left untouched so mistakes stay visible in the task.
+pub fn expand_placeholders(input: &str, now: DateTime<Utc>, variables: &HashMap<String, String>) -> String {
+    PLACEHOLDER_REGEX
+        .replace_all(input, |caps: &regex::Captures| {
+            let name = &caps[1];
+            if let Some(value) = variables.get(name) {
+                return value.clone();
+            }
+            match name {
+                "date" => now.format("%Y-%m-%d").to_string(),
+                "time" => now.format("%H:%M").to_string(),
+                "datetime" => now.format("%Y-%m-%d %H:%M").to_string(),
+                "weekday" => now.format("%A").to_string(),
+                "week" => now.iso_week().week().to_string(),
+                "month" => now.format("%B").to_string(),
+                "year" => now.year().to_string(),
+                _ => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
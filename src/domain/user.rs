@@ -1,60 +1,58 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Type};
 use uuid::Uuid;
-use std::fmt;
-use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
-#[sqlx(type_name = "user_role", rename_all = "lowercase")]
-pub enum UserRole {
-    User,
-    Admin,
-}
-
-impl fmt::Display for UserRole {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            UserRole::User => write!(f, "user"),
-            UserRole::Admin => write!(f, "admin"),
-        }
-    }
-}
-
-impl FromStr for UserRole {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "user" => Ok(UserRole::User),
-            "admin" => Ok(UserRole::Admin),
-            _ => Err(format!("Invalid user role: {}", s)),
-        }
-    }
-}
+use super::role::DEFAULT_ROLE;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
     pub id: Uuid,
     pub name: String,
     pub email: String,
-    pub role: UserRole,
+    /// Name of the user's role, see `roles`
+    pub role: String,
+    pub timezone: String,
+    /// Unset until the user follows the link in the verification email
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// What a mailed single-use token is good for
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "user_token_purpose", rename_all = "snake_case")]
+pub enum UserTokenPurpose {
+    PasswordReset,
+    EmailVerification,
+    EmailChange,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserRequest {
     pub name: String,
     pub email: String,
 }
 
+/// Body of `PATCH /users/me`; email changes go through `POST /users/me/email`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub name: Option<String>,
+    pub timezone: Option<String>,
+}
+
 impl User {
     pub fn new(name: String, email: String) -> Self {
         Self {
             id: Uuid::new_v4(),
             name,
             email,
-            role: UserRole::User,
+            role: DEFAULT_ROLE.to_string(),
+            timezone: "UTC".to_string(),
+            email_verified_at: None,
             created_at: chrono::Utc::now(),
         }
     }
+
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
 }
@@ -0,0 +1,125 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::USER_AGENT, request::Parts},
+};
+
+use crate::domain::ClientInfo;
+
+/// Longer user agents are cut short before they are stored
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Set as a request extension to tell `ClientIp` how many proxies in front
+/// of the app append to `X-Forwarded-For`; 0 ignores proxy headers
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedProxyHops(pub usize);
+
+/// Address of the client, if it can be told. Taken from the socket, or from
+/// `X-Forwarded-For` when `TrustedProxyHops` is above 0.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let hops = parts.extensions.get::<TrustedProxyHops>().map_or(0, |t| t.0);
+        if hops > 0 && let Some(ip) = forwarded_ip(parts, hops) {
+            return Ok(ClientIp(Some(ip)));
+        }
+
+        let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+        Ok(ClientIp(peer))
+    }
+}
+
+/// Client address plus `User-Agent`, for recording a login
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let user_agent = parts.headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        Ok(ClientInfo { ip, user_agent })
+    }
+}
+
+fn forwarded_ip(parts: &Parts, hops: usize) -> Option<IpAddr> {
+    // A client can send its own X-Forwarded-For, and a proxy may then append
+    // a separate header line instead of extending it, so every line counts.
+    // X-Real-IP is not used: nothing says a proxy overwrote what the client sent.
+    let mut entries = Vec::new();
+    for line in parts.headers.get_all("x-forwarded-for") {
+        entries.extend(line.to_str().ok()?.split(','));
+    }
+
+    // Each trusted proxy appends the address it received the request from,
+    // so the client is `hops` entries from the right. Anything further left
+    // was sent by the client and can be forged.
+    let index = entries.len().checked_sub(hops)?;
+    entries[index].trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn parts(headers: &[(&str, &str)]) -> Parts {
+        let mut request = Request::builder();
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0
+    }
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn client_is_counted_from_the_right() {
+        let forged = parts(&[("x-forwarded-for", "1.1.1.1, 203.0.113.7")]);
+        assert_eq!(forwarded_ip(&forged, 1), ip("203.0.113.7"));
+
+        let two_proxies = parts(&[("x-forwarded-for", "1.1.1.1, 203.0.113.7, 10.0.0.2")]);
+        assert_eq!(forwarded_ip(&two_proxies, 2), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn short_or_garbled_header_is_ignored() {
+        assert_eq!(forwarded_ip(&parts(&[("x-forwarded-for", "203.0.113.7")]), 2), None);
+        assert_eq!(forwarded_ip(&parts(&[("x-forwarded-for", "1.1.1.1, nonsense")]), 1), None);
+    }
+
+    #[test]
+    fn every_forwarded_for_line_counts() {
+        // The client's forged line comes first, the proxy's appended line last
+        let split = parts(&[("x-forwarded-for", "1.1.1.1, 2.2.2.2"), ("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(forwarded_ip(&split, 1), ip("203.0.113.7"));
+        assert_eq!(forwarded_ip(&split, 2), ip("2.2.2.2"));
+    }
+
+    #[test]
+    fn real_ip_is_not_trusted() {
+        assert_eq!(forwarded_ip(&parts(&[("x-real-ip", "203.0.113.7")]), 1), None);
+        assert_eq!(forwarded_ip(&parts(&[]), 1), None);
+    }
+}
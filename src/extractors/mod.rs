@@ -1,6 +1,8 @@
 // Extractors module - custom Axum extractors
+pub mod client_ip;
 pub mod error_parser;
 pub mod validated_json;
 
 // Re-export commonly used extractors
+pub use client_ip::{ClientIp, TrustedProxyHops};
 pub use validated_json::ValidatedJson;
@@ -0,0 +1,63 @@
+use axum::{
+    extract::{Path, State, Extension},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::{CreateApiKeyRequest, Result, ApiError};
+use crate::middleware::CurrentUser;
+use crate::services::ApiKeyService;
+use super::{respond_created, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct ApiKeyIdPath {
+    pub id: String,
+}
+
+/// Keys are managed from a login session; a key cannot see or mint other keys
+fn require_login_session(current_user: &CurrentUser) -> Result<()> {
+    if current_user.is_api_key() {
+        return Err(ApiError::forbidden("API keys cannot manage API keys"));
+    }
+    Ok(())
+}
+
+pub async fn get_api_keys(
+    State(api_key_service): State<ApiKeyService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    require_login_session(&current_user)?;
+
+    let keys = api_key_service.get_api_keys(current_user.id).await?;
+    Ok(respond_ok(keys))
+}
+
+/// The response holds the key itself; it is not shown again
+pub async fn create_api_key(
+    State(api_key_service): State<ApiKeyService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse> {
+    require_login_session(&current_user)?;
+
+    let created = api_key_service.create_api_key(current_user.id, request).await?;
+    Ok(respond_created(created))
+}
+
+pub async fn revoke_api_key(
+    State(api_key_service): State<ApiKeyService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<ApiKeyIdPath>,
+) -> Result<impl IntoResponse> {
+    require_login_session(&current_user)?;
+
+    let key_id = params
+        .id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid API key ID format: {}", params.id)))?;
+
+    api_key_service.revoke_api_key(current_user.id, key_id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": key_id })))
+}
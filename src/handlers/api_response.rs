@@ -35,3 +35,11 @@ pub fn respond_created<T: Serialize>(data: T) -> impl IntoResponse {
 }
 
 
+
+pub fn respond_accepted(message: impl Into<String>) -> impl IntoResponse {
+    (StatusCode::ACCEPTED, Json(ApiResponse::<()>::msg(message)))
+}
+
+pub fn respond_message(message: impl Into<String>) -> impl IntoResponse {
+    Json(ApiResponse::<()>::msg(message))
+}
@@ -1,8 +1,12 @@
 use axum::{extract::State, response::IntoResponse};
-use crate::domain::Result;
-use crate::services::auth_service::{AuthService, RegisterRequest, LoginRequest};
+use crate::domain::{ClientInfo, Result};
+use crate::services::auth_service::{
+    AuthService, RegisterRequest, LoginRequest, ForgotPasswordRequest, ResetPasswordRequest,
+    VerifyEmailRequest, ResendVerificationRequest, ConfirmEmailChangeRequest, MfaVerifyRequest, MfaEnrollRequest,
+    RefreshTokenRequest,
+};
 use crate::extractors::ValidatedJson;
-use super::{respond_created, respond_ok};
+use super::{respond_accepted, respond_created, respond_message, respond_ok};
 use tracing::{info, debug};
 
 pub async fn register(
@@ -18,16 +22,90 @@ pub async fn register(
     Ok(respond_created(user))
 }
 
+/// 429 with `Retry-After` while the account or client address is locked out
 pub async fn login(
     State(auth): State<AuthService>,
+    client: ClientInfo,
     ValidatedJson(req): ValidatedJson<LoginRequest>,
 ) -> Result<impl IntoResponse> {
     let email = req.email.clone();
     info!("Login attempt for email: {}", email);
     debug!("Login request payload: {:?}", req);
     
-    let token = auth.login(req).await?;
+    let response = auth.login(req, &client).await?;
     
     info!("User logged in successfully: {}", email);
-    Ok(respond_ok(token))
-}
\ No newline at end of file
+    Ok(respond_ok(response))
+}
+
+/// Always 202, whether or not the email belongs to an account
+pub async fn forgot_password(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<ForgotPasswordRequest>,
+) -> Result<impl IntoResponse> {
+    info!("Password reset requested");
+
+    auth.request_password_reset(req);
+    Ok(respond_accepted("If the address belongs to an account, a reset link is on its way"))
+}
+
+pub async fn reset_password(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<ResetPasswordRequest>,
+) -> Result<impl IntoResponse> {
+    auth.reset_password(req).await?;
+    Ok(respond_message("Password has been reset"))
+}
+
+pub async fn verify_email(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<VerifyEmailRequest>,
+) -> Result<impl IntoResponse> {
+    auth.verify_email(req).await?;
+    Ok(respond_message("Email address verified"))
+}
+
+/// 202 unless the previous email went out too recently
+pub async fn resend_verification(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<ResendVerificationRequest>,
+) -> Result<impl IntoResponse> {
+    auth.resend_verification(req).await?;
+    Ok(respond_accepted("If the address needs verifying, a new link is on its way"))
+}
+
+pub async fn confirm_email_change(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<ConfirmEmailChangeRequest>,
+) -> Result<impl IntoResponse> {
+    let user = auth.confirm_email_change(req).await?;
+    Ok(respond_ok(user))
+}
+
+/// Second login step for users with MFA
+pub async fn verify_mfa(
+    State(auth): State<AuthService>,
+    client: ClientInfo,
+    ValidatedJson(req): ValidatedJson<MfaVerifyRequest>,
+) -> Result<impl IntoResponse> {
+    let response = auth.verify_mfa(req, &client).await?;
+    Ok(respond_ok(response))
+}
+
+/// New access and refresh tokens for the session of a refresh token
+pub async fn refresh_token(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<RefreshTokenRequest>,
+) -> Result<impl IntoResponse> {
+    let response = auth.refresh(req).await?;
+    Ok(respond_ok(response))
+}
+
+/// MFA setup during login, for roles that require it
+pub async fn enroll_mfa(
+    State(auth): State<AuthService>,
+    ValidatedJson(req): ValidatedJson<MfaEnrollRequest>,
+) -> Result<impl IntoResponse> {
+    let enrollment = auth.enroll_mfa(req).await?;
+    Ok(respond_ok(enrollment))
+}
@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::authz::{AdministerSystem, RequirePermission};
+use crate::domain::{ApiError, Result};
+use crate::services::LockoutService;
+use super::{respond_message, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct LockoutUserPath {
+    pub id: String,
+}
+
+pub async fn get_lockouts(
+    State(lockout_service): State<LockoutService>,
+    _permission: RequirePermission<AdministerSystem>,
+) -> Result<impl IntoResponse> {
+    let lockouts = lockout_service.locked_accounts().await?;
+    Ok(respond_ok(lockouts))
+}
+
+pub async fn unlock_user(
+    State(lockout_service): State<LockoutService>,
+    permission: RequirePermission<AdministerSystem>,
+    Path(params): Path<LockoutUserPath>,
+) -> Result<impl IntoResponse> {
+    let user_id = params.id.parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", params.id)))?;
+    info!("User {} unlocking login for user {}", permission.actor.id, user_id);
+
+    if lockout_service.unlock(user_id).await? {
+        Ok(respond_message("Account unlocked"))
+    } else {
+        Ok(respond_message("Account was not locked"))
+    }
+}
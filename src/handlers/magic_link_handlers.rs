@@ -0,0 +1,26 @@
+use axum::{extract::State, response::IntoResponse};
+
+use crate::domain::{ClientInfo, ConsumeMagicLinkRequest, MagicLinkRequest, Result};
+use crate::extractors::ValidatedJson;
+use crate::services::MagicLinkService;
+use super::respond_ok;
+
+/// Returns the device code the link will need, whether or not the address
+/// belongs to an account; 429 once it has asked too often
+pub async fn request_magic_link(
+    State(magic_link_service): State<MagicLinkService>,
+    ValidatedJson(req): ValidatedJson<MagicLinkRequest>,
+) -> Result<impl IntoResponse> {
+    let issued = magic_link_service.request(req).await?;
+    Ok(respond_ok(issued))
+}
+
+/// Same response as a password login: a token, or an MFA challenge
+pub async fn consume_magic_link(
+    State(magic_link_service): State<MagicLinkService>,
+    client: ClientInfo,
+    ValidatedJson(req): ValidatedJson<ConsumeMagicLinkRequest>,
+) -> Result<impl IntoResponse> {
+    let response = magic_link_service.consume(req, &client).await?;
+    Ok(respond_ok(response))
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{State, Extension},
+    response::IntoResponse,
+};
+
+use crate::domain::{MfaCodeRequest, Result};
+use crate::extractors::ValidatedJson;
+use crate::middleware::CurrentUser;
+use crate::services::MfaService;
+use super::{respond_message, respond_ok};
+
+pub async fn get_mfa_status(
+    State(mfa_service): State<MfaService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let status = mfa_service.status(current_user.id).await?;
+    Ok(respond_ok(status))
+}
+
+/// Returns the secret and `otpauth://` URI; MFA is enabled by `/me/mfa/confirm`
+pub async fn begin_mfa_enrollment(
+    State(mfa_service): State<MfaService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let enrollment = mfa_service.begin_enrollment(current_user.id).await?;
+    Ok(respond_ok(enrollment))
+}
+
+/// Returns the recovery codes; this is the only time they are shown
+pub async fn confirm_mfa_enrollment(
+    State(mfa_service): State<MfaService>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(request): ValidatedJson<MfaCodeRequest>,
+) -> Result<impl IntoResponse> {
+    let codes = mfa_service.confirm_enrollment(current_user.id, &request.code).await?;
+    Ok(respond_ok(codes))
+}
+
+pub async fn disable_mfa(
+    State(mfa_service): State<MfaService>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(request): ValidatedJson<MfaCodeRequest>,
+) -> Result<impl IntoResponse> {
+    mfa_service.disable(current_user.id, &request.code).await?;
+    Ok(respond_message("Two-factor authentication disabled"))
+}
+
+pub async fn regenerate_recovery_codes(
+    State(mfa_service): State<MfaService>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(request): ValidatedJson<MfaCodeRequest>,
+) -> Result<impl IntoResponse> {
+    let codes = mfa_service.regenerate_recovery_codes(current_user.id, &request.code).await?;
+    Ok(respond_ok(codes))
+}
@@ -1,12 +1,36 @@
 // Handlers module - HTTP request handlers
 pub mod user_handlers;
+pub mod role_handlers;
 pub mod task_handlers;
+pub mod template_handlers;
+pub mod note_handlers;
+pub mod notebook_handlers;
+pub mod share_handlers;
 pub mod health_handlers;
+pub mod well_known_handlers;
 pub mod api_response;
 pub mod auth_handlers;
+pub mod mfa_handlers;
+pub mod api_key_handlers;
+pub mod session_handlers;
+pub mod lockout_handlers;
+pub mod oidc_handlers;
+pub mod magic_link_handlers;
 
 pub use user_handlers::*;
+pub use role_handlers::*;
 pub use task_handlers::*;
+pub use template_handlers::*;
+pub use note_handlers::*;
+pub use notebook_handlers::*;
+pub use share_handlers::*;
 pub use health_handlers::*;
+pub use well_known_handlers::*;
 pub use api_response::*;
 pub use auth_handlers::*;
+pub use mfa_handlers::*;
+pub use api_key_handlers::*;
+pub use session_handlers::*;
+pub use lockout_handlers::*;
+pub use oidc_handlers::*;
+pub use magic_link_handlers::*;
@@ -0,0 +1,241 @@
+use axum::{
+    extract::{Path, Query, State, Extension},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use tracing::{info, debug};
+
+use crate::domain::{CreateNoteRequest, UpdateNoteRequest, MoveNoteRequest, ExtractTasksRequest, DiffFormat, Note, Result, ApiError};
+use crate::authz::{authorize, Action, Actor, Resource};
+use crate::services::NoteService;
+use crate::middleware::CurrentUser;
+use super::{respond_created, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct NoteIdPath {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteRevisionPath {
+    pub id: String,
+    pub rev: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotesQuery {
+    /// Owner whose notes are listed (admins only), defaults to the caller
+    pub user_id: Option<String>,
+    /// Only notes filed in this notebook
+    pub notebook_id: Option<String>,
+    /// With `notebook_id`, also include notes of its sub-notebooks
+    #[serde(default)]
+    pub include_descendants: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// `unified` (default) or `words`
+    pub format: Option<String>,
+}
+
+pub async fn create_note(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateNoteRequest>,
+) -> Result<impl IntoResponse> {
+    info!("Creating note for user {}: {}", current_user.id, request.title);
+
+    let note = note_service.create_note(request, current_user.id).await?;
+    Ok(respond_created(note))
+}
+
+pub async fn get_notes(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<NotesQuery>,
+) -> Result<impl IntoResponse> {
+    let user_id = match &query.user_id {
+        Some(user_id_str) => user_id_str
+            .parse::<Uuid>()
+            .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", user_id_str)))?,
+        None => current_user.id,
+    };
+
+    authorize(&Actor::from(&current_user), Action::List, &Resource::UserNotes { owner_id: user_id })?;
+
+    let notes = match &query.notebook_id {
+        Some(notebook_id_str) => {
+            let notebook_id = notebook_id_str
+                .parse::<Uuid>()
+                .map_err(|_| ApiError::bad_request(format!("Invalid notebook ID format: {}", notebook_id_str)))?;
+            note_service
+                .get_notes_in_notebook(user_id, notebook_id, query.include_descendants)
+                .await?
+        }
+        None => note_service.get_notes_by_user(user_id).await?,
+    };
+    Ok(respond_ok(notes))
+}
+
+pub async fn get_note(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Read).await?;
+    Ok(respond_ok(note))
+}
+
+pub async fn update_note(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+    Json(request): Json<UpdateNoteRequest>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    debug!("Note update payload: {:?}", request);
+    let note = note_service.update_note(&note, request, current_user.id).await?;
+    Ok(respond_ok(note))
+}
+
+pub async fn move_note(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+    Json(request): Json<MoveNoteRequest>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    let note = note_service.move_note(&note, request.notebook_id).await?;
+    Ok(respond_ok(note))
+}
+
+pub async fn extract_note_tasks(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+    request: Option<Json<ExtractTasksRequest>>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let result = note_service.extract_tasks(&note, request).await?;
+    Ok(respond_created(result))
+}
+
+pub async fn delete_note(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Delete).await?;
+
+    note_service.delete_note(note.id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": note.id })))
+}
+
+pub async fn get_note_backlinks(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Read).await?;
+
+    let backlinks = note_service.get_backlinks(note.id).await?;
+    Ok(respond_ok(backlinks))
+}
+
+pub async fn get_note_graph(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let graph = note_service.get_graph(current_user.id).await?;
+    Ok(respond_ok(graph))
+}
+
+pub async fn get_note_revisions(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+) -> Result<impl IntoResponse> {
+    let note = load_note(&note_service, &current_user, &params.id, Action::Read).await?;
+
+    let revisions = note_service.get_revisions(note.id).await?;
+    Ok(respond_ok(revisions))
+}
+
+pub async fn get_note_revision(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteRevisionPath>,
+) -> Result<impl IntoResponse> {
+    let revision = parse_revision(&params.rev)?;
+    let note = load_note(&note_service, &current_user, &params.id, Action::Read).await?;
+
+    let revision = note_service.get_revision(note.id, revision).await?;
+    Ok(respond_ok(revision))
+}
+
+pub async fn diff_note_revisions(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteIdPath>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<impl IntoResponse> {
+    let from = query
+        .from
+        .as_deref()
+        .ok_or_else(|| ApiError::bad_request("Missing `from` revision"))
+        .and_then(parse_revision)?;
+    let to = query
+        .to
+        .as_deref()
+        .ok_or_else(|| ApiError::bad_request("Missing `to` revision"))
+        .and_then(parse_revision)?;
+    let format = match query.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("unified") => DiffFormat::Unified,
+        Some("words") => DiffFormat::Words,
+        Some(other) => return Err(ApiError::bad_request(format!("Invalid diff format: {}. Must be: unified, words", other))),
+    };
+
+    let note = load_note(&note_service, &current_user, &params.id, Action::Read).await?;
+
+    let diff = note_service.diff_revisions(note.id, from, to, format).await?;
+    Ok(respond_ok(diff))
+}
+
+pub async fn restore_note_revision(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NoteRevisionPath>,
+) -> Result<impl IntoResponse> {
+    let revision = parse_revision(&params.rev)?;
+    let note = load_note(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    let note = note_service.restore_revision(&note, revision, current_user.id).await?;
+    Ok(respond_ok(note))
+}
+
+/// Fetch a note the caller is allowed to perform `action` on
+async fn load_note(note_service: &NoteService, current_user: &CurrentUser, id: &str, action: Action) -> Result<Note> {
+    let note_id = id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid note ID format: {}", id)))?;
+    let note = note_service.get_note(note_id).await?;
+
+    authorize(&Actor::from(current_user), action, &Resource::Note { owner_id: note.user_id })?;
+    Ok(note)
+}
+
+fn parse_revision(rev: &str) -> Result<i32> {
+    rev.parse::<i32>()
+        .ok()
+        .filter(|rev| *rev > 0)
+        .ok_or_else(|| ApiError::bad_request(format!("Invalid revision number: {}", rev)))
+}
@@ -0,0 +1,110 @@
+use axum::{
+    extract::{Path, Query, State, Extension},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::domain::{
+    Notebook, CreateNotebookRequest, RenameNotebookRequest, MoveNotebookRequest, NotebookDeleteMode, Result, ApiError,
+};
+use crate::authz::{authorize, Action, Actor, Resource};
+use crate::services::NoteService;
+use crate::middleware::CurrentUser;
+use super::{respond_created, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct NotebookIdPath {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteNotebookQuery {
+    /// `rehome` (default) moves the contents up a level, `cascade` deletes them
+    pub notes: Option<String>,
+}
+
+pub async fn create_notebook(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateNotebookRequest>,
+) -> Result<impl IntoResponse> {
+    info!("Creating notebook for user {}: {}", current_user.id, request.name);
+
+    let notebook = note_service.create_notebook(request, current_user.id).await?;
+    Ok(respond_created(notebook))
+}
+
+pub async fn get_notebooks(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let notebooks = note_service.get_notebooks(current_user.id).await?;
+    Ok(respond_ok(notebooks))
+}
+
+pub async fn get_notebook(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NotebookIdPath>,
+) -> Result<impl IntoResponse> {
+    let notebook = load_notebook(&note_service, &current_user, &params.id, Action::Read).await?;
+
+    let notebook = note_service.get_notebook_with_counts(&notebook).await?;
+    Ok(respond_ok(notebook))
+}
+
+pub async fn rename_notebook(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NotebookIdPath>,
+    Json(request): Json<RenameNotebookRequest>,
+) -> Result<impl IntoResponse> {
+    let notebook = load_notebook(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    let notebook = note_service.rename_notebook(notebook.id, &request.name).await?;
+    Ok(respond_ok(notebook))
+}
+
+pub async fn move_notebook(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NotebookIdPath>,
+    Json(request): Json<MoveNotebookRequest>,
+) -> Result<impl IntoResponse> {
+    let notebook = load_notebook(&note_service, &current_user, &params.id, Action::Update).await?;
+
+    let notebook = note_service.move_notebook(&notebook, request.parent_id).await?;
+    Ok(respond_ok(notebook))
+}
+
+pub async fn delete_notebook(
+    State(note_service): State<NoteService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<NotebookIdPath>,
+    Query(query): Query<DeleteNotebookQuery>,
+) -> Result<impl IntoResponse> {
+    let mode = match query.notes.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("rehome") => NotebookDeleteMode::Rehome,
+        Some("cascade") => NotebookDeleteMode::Cascade,
+        Some(other) => return Err(ApiError::bad_request(format!("Invalid notes mode: {}. Must be: rehome, cascade", other))),
+    };
+
+    let notebook = load_notebook(&note_service, &current_user, &params.id, Action::Delete).await?;
+
+    note_service.delete_notebook(&notebook, mode).await?;
+    Ok(respond_ok(serde_json::json!({ "id": notebook.id, "notes": mode })))
+}
+
+/// Fetch a notebook the caller is allowed to perform `action` on
+async fn load_notebook(note_service: &NoteService, current_user: &CurrentUser, id: &str, action: Action) -> Result<Notebook> {
+    let notebook_id = id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid notebook ID format: {}", id)))?;
+    let notebook = note_service.get_notebook(notebook_id).await?;
+
+    authorize(&Actor::from(current_user), action, &Resource::Notebook { owner_id: notebook.user_id })?;
+    Ok(notebook)
+}
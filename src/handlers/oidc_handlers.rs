@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::domain::{ClientInfo, OidcCallbackRequest, Result};
+use crate::extractors::ValidatedJson;
+use crate::services::OidcService;
+use super::respond_ok;
+
+#[derive(Debug, Deserialize)]
+pub struct OidcProviderPath {
+    pub provider: String,
+}
+
+pub async fn get_oidc_providers(
+    State(oidc_service): State<OidcService>,
+) -> Result<impl IntoResponse> {
+    Ok(respond_ok(oidc_service.providers()))
+}
+
+/// Returns the provider URL to send the browser to
+pub async fn start_oidc_login(
+    State(oidc_service): State<OidcService>,
+    Path(params): Path<OidcProviderPath>,
+) -> Result<impl IntoResponse> {
+    let authorization = oidc_service.authorize(&params.provider).await?;
+    Ok(respond_ok(authorization))
+}
+
+/// Same response as a password login: a token, or an MFA challenge
+pub async fn finish_oidc_login(
+    State(oidc_service): State<OidcService>,
+    Path(params): Path<OidcProviderPath>,
+    client: ClientInfo,
+    ValidatedJson(req): ValidatedJson<OidcCallbackRequest>,
+) -> Result<impl IntoResponse> {
+    let response = oidc_service.callback(&params.provider, req, &client).await?;
+    Ok(respond_ok(response))
+}
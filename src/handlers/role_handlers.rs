@@ -0,0 +1,115 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::authz::{RequirePermission, ManageRoles};
+use crate::domain::{Permission, CreateRoleRequest, UpdateRoleRequest, AssignRoleRequest, SetMfaRequirementRequest, Result, ApiError};
+use crate::services::RoleService;
+use super::{respond_created, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct RoleIdPath {
+    pub id: String,
+}
+
+pub async fn get_roles(
+    State(role_service): State<RoleService>,
+    _permission: RequirePermission<ManageRoles>,
+) -> Result<impl IntoResponse> {
+    let roles = role_service.get_roles().await?;
+    Ok(respond_ok(roles))
+}
+
+/// Every permission a role can be given
+pub async fn get_permissions(
+    _permission: RequirePermission<ManageRoles>,
+) -> Result<impl IntoResponse> {
+    Ok(respond_ok(Permission::ALL))
+}
+
+pub async fn create_role(
+    State(role_service): State<RoleService>,
+    permission: RequirePermission<ManageRoles>,
+    Json(request): Json<CreateRoleRequest>,
+) -> Result<impl IntoResponse> {
+    info!("User {} creating role {}", permission.actor.id, request.name);
+
+    let role = role_service.create_role(request).await?;
+    Ok(respond_created(role))
+}
+
+pub async fn get_role(
+    State(role_service): State<RoleService>,
+    _permission: RequirePermission<ManageRoles>,
+    Path(params): Path<RoleIdPath>,
+) -> Result<impl IntoResponse> {
+    let role_id = parse_role_id(&params.id)?;
+
+    let role = role_service.get_role(role_id).await?;
+    Ok(respond_ok(role))
+}
+
+pub async fn update_role(
+    State(role_service): State<RoleService>,
+    permission: RequirePermission<ManageRoles>,
+    Path(params): Path<RoleIdPath>,
+    Json(request): Json<UpdateRoleRequest>,
+) -> Result<impl IntoResponse> {
+    let role_id = parse_role_id(&params.id)?;
+    info!("User {} updating role {}", permission.actor.id, role_id);
+
+    let role = role_service.update_role(role_id, request).await?;
+    Ok(respond_ok(role))
+}
+
+pub async fn set_role_mfa(
+    State(role_service): State<RoleService>,
+    permission: RequirePermission<ManageRoles>,
+    Path(params): Path<RoleIdPath>,
+    Json(request): Json<SetMfaRequirementRequest>,
+) -> Result<impl IntoResponse> {
+    let role_id = parse_role_id(&params.id)?;
+    info!("User {} setting MFA requirement of role {} to {}", permission.actor.id, role_id, request.required);
+
+    let role = role_service.set_mfa_requirement(role_id, request).await?;
+    Ok(respond_ok(role))
+}
+
+pub async fn delete_role(
+    State(role_service): State<RoleService>,
+    permission: RequirePermission<ManageRoles>,
+    Path(params): Path<RoleIdPath>,
+) -> Result<impl IntoResponse> {
+    let role_id = parse_role_id(&params.id)?;
+    info!("User {} deleting role {}", permission.actor.id, role_id);
+
+    role_service.delete_role(role_id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": role_id })))
+}
+
+/// `PUT /users/:id/role`
+pub async fn assign_user_role(
+    State(role_service): State<RoleService>,
+    permission: RequirePermission<ManageRoles>,
+    Path(params): Path<RoleIdPath>,
+    Json(request): Json<AssignRoleRequest>,
+) -> Result<impl IntoResponse> {
+    let user_id = params
+        .id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", params.id)))?;
+    info!("User {} assigning role {} to {}", permission.actor.id, request.role, user_id);
+
+    let user = role_service.assign_role(user_id, request).await?;
+    Ok(respond_ok(user))
+}
+
+fn parse_role_id(id: &str) -> Result<Uuid> {
+    id.parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid role ID format: {}", id)))
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, State, Extension},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::domain::{Result, ApiError};
+use crate::middleware::CurrentUser;
+use crate::services::SessionService;
+use super::respond_ok;
+
+#[derive(Debug, Deserialize)]
+pub struct SessionIdPath {
+    pub id: String,
+}
+
+/// Sessions are logins; an API key is not one and cannot end them
+fn require_login_session(current_user: &CurrentUser) -> Result<()> {
+    if current_user.is_api_key() {
+        return Err(ApiError::forbidden("API keys cannot manage sessions"));
+    }
+    Ok(())
+}
+
+/// The caller's own session is flagged `current`
+pub async fn get_sessions(
+    State(session_service): State<SessionService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    require_login_session(&current_user)?;
+
+    let sessions = session_service.list(current_user.id, current_user.session_id).await?;
+    Ok(respond_ok(sessions))
+}
+
+/// Revoking the current session logs the caller out
+pub async fn revoke_session(
+    State(session_service): State<SessionService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<SessionIdPath>,
+) -> Result<impl IntoResponse> {
+    require_login_session(&current_user)?;
+
+    let session_id = params
+        .id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid session ID format: {}", params.id)))?;
+
+    session_service.revoke(current_user.id, session_id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": session_id })))
+}
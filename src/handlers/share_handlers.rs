@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Path, State, Extension},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use tracing::{info, debug};
+
+use crate::domain::{ClientInfo, CreateShareLinkRequest, Result, ApiError};
+use crate::authz::{authorize, Action, Actor, Resource};
+use crate::services::ShareService;
+use crate::middleware::CurrentUser;
+use super::{respond_created, respond_ok};
+
+/// Header carrying the password of a protected share link
+pub const SHARE_PASSWORD_HEADER: &str = "x-share-password";
+
+#[derive(Debug, Deserialize)]
+pub struct ShareIdPath {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareTokenPath {
+    pub token: String,
+}
+
+pub async fn create_share_link(
+    State(share_service): State<ShareService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateShareLinkRequest>,
+) -> Result<impl IntoResponse> {
+    let owner_id = share_service.resource_owner(request.resource_type, request.resource_id).await?;
+
+    authorize(&Actor::from(&current_user), Action::Create, &Resource::ShareLink { owner_id })?;
+
+    info!("User {} sharing {:?} {}", current_user.id, request.resource_type, request.resource_id);
+    let created = share_service.create_link(request, owner_id).await?;
+    Ok(respond_created(created))
+}
+
+pub async fn get_share_links(
+    State(share_service): State<ShareService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let links = share_service.get_links_by_user(current_user.id).await?;
+    Ok(respond_ok(links))
+}
+
+pub async fn revoke_share_link(
+    State(share_service): State<ShareService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<ShareIdPath>,
+) -> Result<impl IntoResponse> {
+    let link_id = params
+        .id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid share link ID format: {}", params.id)))?;
+    let link = share_service.get_link(link_id).await?;
+
+    authorize(&Actor::from(&current_user), Action::Delete, &Resource::ShareLink { owner_id: link.user_id })?;
+
+    share_service.revoke_link(link_id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": link_id })))
+}
+
+/// Unauthenticated: anyone holding the token (and password, if set) can read
+pub async fn open_public_share(
+    State(share_service): State<ShareService>,
+    Path(params): Path<ShareTokenPath>,
+    client: ClientInfo,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse> {
+    let password = headers
+        .get(SHARE_PASSWORD_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    debug!("Opening public share {}...", params.token.chars().take(6).collect::<String>());
+    let view = share_service.open_link(&params.token, password, client.ip).await?;
+    Ok(respond_ok(view))
+}
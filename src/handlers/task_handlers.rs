@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Path, Query, State, Extension},
-    response::IntoResponse,
+    extract::{NestedPath, Path, Query, State, Extension},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -15,9 +15,10 @@ pub enum TaskQueryResponse {
 use uuid::Uuid;
 use tracing::{info, debug};
 
-use crate::domain::{CreateTaskRequest, Result, ApiError, TaskQueryParams, PaginatedResponse};
-use crate::domain::user::UserRole;
-use crate::services::TaskService;
+use crate::domain::{Task, CreateTaskRequest, UpdateTaskRequest, Result, ApiError, TaskQueryParams, PaginatedResponse, ShareTaskRequest, Permission};
+use crate::domain::task::{AddChecklistItemRequest, ReorderChecklistRequest, DuplicateTaskRequest, TransferTaskRequest};
+use crate::authz::{authorize, Action, Actor, DefaultPolicy, Policy, Resource};
+use crate::services::{TaskService, QuickAddRequest, TransferOutcome, SlugLookup};
 use crate::middleware::CurrentUser;
 use super::{respond_created, respond_ok};
 
@@ -26,6 +27,36 @@ pub struct TaskIdPath {
     pub id: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ChecklistItemPath {
+    pub id: String,
+    pub item_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskSharePath {
+    pub id: String,
+    pub user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskSlugPath {
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlugLookupQuery {
+    /// Owner whose namespace is searched (admins only), defaults to the caller
+    pub user_id: Option<String>,
+    pub render: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenderQuery {
+    /// `html` adds a sanitised `description_html` field to the response
+    pub render: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TasksQuery {
     pub user_id: Option<String>,
@@ -48,6 +79,9 @@ pub struct DynamicTaskQuery {
     pub created_after: Option<String>,
     pub created_before: Option<String>,
     pub search: Option<String>,
+
+    /// Only tasks other users shared with the caller
+    pub shared_with_me: Option<bool>,
 }
 
 pub async fn create_task(
@@ -64,23 +98,107 @@ pub async fn create_task(
     Ok(respond_created(task))
 }
 
+pub async fn quick_add_task(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<QuickAddRequest>,
+) -> Result<Response> {
+    info!("Quick-add for user {} (dry_run: {}): {}", current_user.id, request.dry_run, request.text);
+
+    let result = task_service.quick_add(request, current_user.id).await?;
+
+    match &result.task {
+        Some(task) => {
+            info!("Task created successfully: {} (slug: {})", task.id, task.slug);
+            Ok(respond_created(result).into_response())
+        }
+        None => Ok(respond_ok(result).into_response()),
+    }
+}
+
 pub async fn get_task(
     State(task_service): State<TaskService>,
     Extension(current_user): Extension<CurrentUser>,
     Path(params): Path<TaskIdPath>,
-) -> Result<impl IntoResponse> {
+    Query(query): Query<RenderQuery>,
+) -> Result<Response> {
     let task_id = params
         .id
         .parse::<Uuid>()
         .map_err(|_| ApiError::bad_request(format!("Invalid task ID format: {}", params.id)))?;
+    let render_html = wants_html(query.render.as_deref())?;
 
     let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Read).await?;
+
+    respond_task(&task_service, task, render_html).await
+}
+
+pub async fn get_task_by_slug(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskSlugPath>,
+    Query(query): Query<SlugLookupQuery>,
+    nested_path: NestedPath,
+) -> Result<Response> {
+    let owner_id = match &query.user_id {
+        Some(user_id_str) => user_id_str
+            .parse::<Uuid>()
+            .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", user_id_str)))?,
+        None => current_user.id,
+    };
+    let render_html = wants_html(query.render.as_deref())?;
+    let actor = Actor::from(&current_user);
+    let own_namespace = DefaultPolicy
+        .can(&actor, Action::List, &Resource::UserTasks { owner_id })
+        .is_allowed();
+
+    let lookup = match task_service.get_task_by_slug(owner_id, &params.slug).await {
+        // Do not reveal which slugs exist in another user's namespace
+        Err(ApiError::NotFound(_)) if !own_namespace => {
+            return Err(ApiError::forbidden("You do not have access to this task"));
+        }
+        result => result?,
+    };
+    let (SlugLookup::Found(task) | SlugLookup::Moved(task)) = &lookup;
+    task_service.authorize(task, &actor, Action::Read).await?;
 
-    // Users can only view their own tasks, admins can view any task
-    if current_user.role != UserRole::Admin && current_user.id != task.user_id {
-        return Err(ApiError::forbidden("You can only view your own tasks"));
+    match lookup {
+        SlugLookup::Found(task) => respond_task(&task_service, task, render_html).await,
+        SlugLookup::Moved(task) => {
+            // Relative to wherever the task routes are mounted
+            let mut location = format!("{}/by-slug/{}", nested_path.as_str(), task.slug);
+            let mut query_parts = Vec::new();
+            if let Some(user_id) = &query.user_id {
+                query_parts.push(format!("user_id={}", user_id));
+            }
+            if render_html {
+                query_parts.push("render=html".to_string());
+            }
+            if !query_parts.is_empty() {
+                location.push('?');
+                location.push_str(&query_parts.join("&"));
+            }
+            debug!("Slug {} redirected to {}", params.slug, location);
+            Ok(Redirect::permanent(&location).into_response())
+        }
     }
+}
 
+pub async fn update_task(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    Json(request): Json<UpdateTaskRequest>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Update).await?;
+
+    debug!("Task update payload: {:?}", request);
+    let task = task_service.update_task(&task, request, current_user.id).await?;
+
+    info!("Task updated successfully: {} (slug: {})", task.id, task.slug);
     Ok(respond_ok(task))
 }
 
@@ -112,23 +230,18 @@ pub async fn get_tasks(
     } else {
         // Use simple query (backward compatibility)
         let tasks = match params.user_id {
+            _ if params.shared_with_me == Some(true) => task_service.get_tasks_shared_with(current_user.id).await?,
             Some(user_id_str) => {
                 let user_id = user_id_str
                     .parse::<Uuid>()
                     .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", user_id_str)))?;
 
-                // Users can only view their own tasks, admins can view any user's tasks
-                if current_user.role != UserRole::Admin && current_user.id != user_id {
-                    return Err(ApiError::forbidden("You can only view your own tasks"));
-                }
+                authorize(&Actor::from(&current_user), Action::List, &Resource::UserTasks { owner_id: user_id })?;
 
                 task_service.get_tasks_by_user(user_id).await?
             }
             None => {
-                // Only admins can view all tasks
-                if current_user.role != UserRole::Admin {
-                    return Err(ApiError::forbidden("Only administrators can view all tasks"));
-                }
+                authorize(&Actor::from(&current_user), Action::List, &Resource::AllTasks)?;
 
                 task_service.get_all_tasks().await?
             }
@@ -140,6 +253,218 @@ pub async fn get_tasks(
     Ok(respond_ok(response))
 }
 
+pub async fn add_checklist_item(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    Json(request): Json<AddChecklistItemRequest>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Update).await?;
+
+    let task = task_service.add_checklist_item(task_id, request.text).await?;
+    Ok(respond_created(task))
+}
+
+pub async fn toggle_checklist_item(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<ChecklistItemPath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let item_id = parse_checklist_item_id(&params.item_id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Update).await?;
+
+    let task = task_service.toggle_checklist_item(task_id, item_id).await?;
+    Ok(respond_ok(task))
+}
+
+pub async fn remove_checklist_item(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<ChecklistItemPath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let item_id = parse_checklist_item_id(&params.item_id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Update).await?;
+
+    let task = task_service.remove_checklist_item(task_id, item_id).await?;
+    Ok(respond_ok(task))
+}
+
+pub async fn reorder_checklist(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    Json(request): Json<ReorderChecklistRequest>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Update).await?;
+
+    let task = task_service.reorder_checklist(task_id, request.item_ids).await?;
+    Ok(respond_ok(task))
+}
+
+pub async fn duplicate_task(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    request: Option<Json<DuplicateTaskRequest>>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Duplicate).await?;
+
+    let options = request.map(|Json(options)| options).unwrap_or_default();
+    debug!("Duplicate options for task {}: {:?}", task_id, options);
+
+    let copy = task_service.duplicate_task(&task, options).await?;
+    Ok(respond_created(copy))
+}
+
+/// Users holding `tasks:write_any` move the task immediately; owners create a pending transfer that
+/// the recipient has to accept
+pub async fn transfer_task(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    Json(request): Json<TransferTaskRequest>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+
+    let actor = Actor::from(&current_user);
+    task_service.authorize(&task, &actor, Action::Transfer).await?;
+
+    let outcome = if actor.has(Permission::TasksWriteAny) {
+        info!("User {} transferring task {} to {}", current_user.id, task_id, request.to_user_id);
+        TransferOutcome::Completed {
+            task: task_service.transfer_task(&task, request.to_user_id).await?,
+        }
+    } else {
+        TransferOutcome::Pending {
+            transfer: task_service.request_transfer(&task, request.to_user_id).await?,
+        }
+    };
+
+    Ok(respond_ok(outcome))
+}
+
+pub async fn accept_task_transfer(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+
+    let task = task_service.accept_transfer(task_id, current_user.id).await?;
+    info!("User {} accepted transfer of task {}", current_user.id, task_id);
+    Ok(respond_ok(TransferOutcome::Completed { task }))
+}
+
+/// Cancelled by the owner or declined by the recipient
+pub async fn cancel_task_transfer(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let transfer = task_service
+        .get_transfer_request(task_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No pending transfer for task {}", task_id)))?;
+
+    if !Actor::from(&current_user).has(Permission::TasksWriteAny)
+        && current_user.id != transfer.from_user_id
+        && current_user.id != transfer.to_user_id
+    {
+        return Err(ApiError::forbidden("You are not part of this transfer"));
+    }
+
+    task_service.cancel_transfer(task_id).await?;
+    Ok(respond_ok(transfer))
+}
+
+/// Owners (and admins) grant other users view, comment or edit access
+pub async fn share_task(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+    Json(request): Json<ShareTaskRequest>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Share).await?;
+
+    let share = task_service.share_task(&task, request, current_user.id).await?;
+    Ok(respond_created(share))
+}
+
+pub async fn get_task_shares(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskIdPath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let task = task_service.get_task(task_id).await?;
+    task_service.authorize(&task, &Actor::from(&current_user), Action::Share).await?;
+
+    let shares = task_service.get_task_shares(task_id).await?;
+    Ok(respond_ok(shares))
+}
+
+/// Revoked by the owner, or by the user giving up their own access
+pub async fn revoke_task_share(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TaskSharePath>,
+) -> Result<impl IntoResponse> {
+    let task_id = parse_task_id(&params.id)?;
+    let user_id = params
+        .user_id
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", params.user_id)))?;
+    let task = task_service.get_task(task_id).await?;
+
+    if current_user.id != user_id {
+        task_service.authorize(&task, &Actor::from(&current_user), Action::Share).await?;
+    }
+
+    task_service.revoke_task_share(task_id, user_id).await?;
+    Ok(respond_ok(serde_json::json!({ "task_id": task_id, "user_id": user_id })))
+}
+
+/// Parse the `render` query parameter; only `html` is supported
+fn wants_html(render: Option<&str>) -> Result<bool> {
+    match render {
+        None => Ok(false),
+        Some(value) if value.eq_ignore_ascii_case("html") => Ok(true),
+        Some(value) => Err(ApiError::bad_request(format!("Unsupported render format: {}. Must be: html", value))),
+    }
+}
+
+async fn respond_task(task_service: &TaskService, task: Task, render_html: bool) -> Result<Response> {
+    if render_html {
+        let rendered = task_service.render_task(task).await;
+        return Ok(respond_ok(rendered).into_response());
+    }
+    Ok(respond_ok(task).into_response())
+}
+
+fn parse_task_id(id: &str) -> Result<Uuid> {
+    id.parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid task ID format: {}", id)))
+}
+
+fn parse_checklist_item_id(id: &str) -> Result<Uuid> {
+    id.parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid checklist item ID format: {}", id)))
+}
+
 /// Convert DynamicTaskQuery to TaskQueryParams
 fn convert_to_task_query_params(
     params: DynamicTaskQuery, 
@@ -172,16 +497,26 @@ fn convert_to_task_query_params(
     }
 
     // Parse user_id if provided
+    let actor = Actor::from(current_user);
+    let shared_with_me = params.shared_with_me == Some(true);
     if let Some(user_id_str) = params.user_id {
         let user_id = user_id_str
             .parse::<Uuid>()
             .map_err(|_| ApiError::bad_request("Invalid user ID format"))?;
+        // The shared_with filter already limits results to what the caller may see
+        if !shared_with_me {
+            authorize(&actor, Action::List, &Resource::UserTasks { owner_id: user_id })?;
+        }
         filters.user_id = Some(user_id);
-    } else if current_user.role != crate::domain::user::UserRole::Admin {
-        // Non-admin users can only see their own tasks
+    } else if !shared_with_me && !DefaultPolicy.can(&actor, Action::List, &Resource::AllTasks).is_allowed() {
+        // Users who may not list every task only see their own
         filters.user_id = Some(current_user.id);
     }
 
+    if shared_with_me {
+        filters.shared_with = Some(current_user.id);
+    }
+
     // Parse date filters if provided
     if let Some(created_after_str) = params.created_after {
         let created_after = created_after_str
@@ -198,10 +533,10 @@ fn convert_to_task_query_params(
     }
 
     // Add search filter if provided
-    if let Some(search) = params.search {
-        if !search.trim().is_empty() {
-            filters.search = Some(search.trim().to_string());
-        }
+    if let Some(search) = params.search
+        && !search.trim().is_empty()
+    {
+        filters.search = Some(search.trim().to_string());
     }
 
     Ok(TaskQueryParams {
@@ -0,0 +1,89 @@
+use axum::{
+    extract::{Path, State, Extension},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+use tracing::{info, debug};
+
+use crate::domain::{CreateTaskTemplateRequest, InstantiateTemplateRequest, Result, ApiError};
+use crate::authz::{authorize, Action, Actor, Resource};
+use crate::services::TaskService;
+use crate::middleware::CurrentUser;
+use super::{respond_created, respond_ok};
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateIdPath {
+    pub id: String,
+}
+
+pub async fn create_template(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateTaskTemplateRequest>,
+) -> Result<impl IntoResponse> {
+    info!("Creating task template for user {}: {}", current_user.id, request.name);
+    debug!("Template request payload: {:?}", request);
+
+    let template = task_service.create_template(request, current_user.id).await?;
+    Ok(respond_created(template))
+}
+
+pub async fn get_templates(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let templates = task_service.get_templates_by_user(current_user.id).await?;
+    Ok(respond_ok(templates))
+}
+
+pub async fn get_template(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TemplateIdPath>,
+) -> Result<impl IntoResponse> {
+    let template_id = parse_template_id(&params.id)?;
+    let template = task_service.get_template(template_id).await?;
+
+    authorize(&Actor::from(&current_user), Action::Read, &Resource::Template { owner_id: template.user_id })?;
+
+    Ok(respond_ok(template))
+}
+
+pub async fn delete_template(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TemplateIdPath>,
+) -> Result<impl IntoResponse> {
+    let template_id = parse_template_id(&params.id)?;
+    let template = task_service.get_template(template_id).await?;
+
+    authorize(&Actor::from(&current_user), Action::Delete, &Resource::Template { owner_id: template.user_id })?;
+
+    task_service.delete_template(template_id).await?;
+    Ok(respond_ok(serde_json::json!({ "id": template_id })))
+}
+
+pub async fn create_task_from_template(
+    State(task_service): State<TaskService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(params): Path<TemplateIdPath>,
+    request: Option<Json<InstantiateTemplateRequest>>,
+) -> Result<impl IntoResponse> {
+    let template_id = parse_template_id(&params.id)?;
+    let template = task_service.get_template(template_id).await?;
+
+    authorize(&Actor::from(&current_user), Action::Instantiate, &Resource::Template { owner_id: template.user_id })?;
+
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let task = task_service.create_task_from_template(&template, request, current_user.id).await?;
+
+    info!("Task {} created from template {}", task.id, template.id);
+    Ok(respond_created(task))
+}
+
+fn parse_template_id(id: &str) -> Result<Uuid> {
+    id.parse::<Uuid>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid template ID format: {}", id)))
+}
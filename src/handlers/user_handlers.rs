@@ -6,11 +6,13 @@ use axum::{
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::domain::{CreateUserRequest, Result, ApiError};
-use crate::domain::user::UserRole;
+use crate::domain::{ClientInfo, CreateUserRequest, UpdateProfileRequest, Result, ApiError};
+use crate::authz::{authorize, Action, Actor, Resource, RequirePermission, CreateUsers};
 use crate::services::UserService;
+use crate::services::auth_service::{AuthService, ChangePasswordRequest, ChangeEmailRequest};
+use crate::extractors::ValidatedJson;
 use crate::middleware::CurrentUser;
-use super::{respond_created, respond_ok};
+use super::{respond_accepted, respond_created, respond_ok};
 
 #[derive(Debug, Deserialize)]
 pub struct UserIdPath {
@@ -19,14 +21,9 @@ pub struct UserIdPath {
 
 pub async fn create_user(
     State(user_service): State<UserService>,
-    Extension(current_user): Extension<CurrentUser>,
+    _permission: RequirePermission<CreateUsers>,
     Json(request): Json<CreateUserRequest>,
 ) -> Result<impl IntoResponse> {
-    // Only admins can create users via this endpoint
-    if current_user.role != UserRole::Admin {
-        return Err(ApiError::forbidden("Only administrators can create user accounts"));
-    }
-
     let user = user_service.create_user(request).await?;
     Ok(respond_created(user))
 }
@@ -41,11 +38,46 @@ pub async fn get_user(
         .parse::<Uuid>()
         .map_err(|_| ApiError::bad_request(format!("Invalid user ID format: {}", params.id)))?;
 
-    // Users can only view their own profile, admins can view any profile
-    if current_user.role != UserRole::Admin && current_user.id != user_id {
-        return Err(ApiError::forbidden("You can only view your own profile"));
-    }
+    authorize(&Actor::from(&current_user), Action::Read, &Resource::User { id: user_id })?;
 
     let user = user_service.get_user(user_id).await?;
     Ok(respond_ok(user))
 }
+
+pub async fn get_me(
+    State(user_service): State<UserService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse> {
+    let user = user_service.get_user(current_user.id).await?;
+    Ok(respond_ok(user))
+}
+
+pub async fn update_me(
+    State(user_service): State<UserService>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(request): ValidatedJson<UpdateProfileRequest>,
+) -> Result<impl IntoResponse> {
+    let user = user_service.update_profile(current_user.id, request).await?;
+    Ok(respond_ok(user))
+}
+
+/// Responds with a new token and session; every other one of the user is revoked
+pub async fn change_password(
+    State(auth): State<AuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+    client: ClientInfo,
+    ValidatedJson(request): ValidatedJson<ChangePasswordRequest>,
+) -> Result<impl IntoResponse> {
+    let token = auth.change_password(current_user.id, request, &client).await?;
+    Ok(respond_ok(token))
+}
+
+/// 202; the address changes once the link mailed to it is followed
+pub async fn change_email(
+    State(auth): State<AuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(request): ValidatedJson<ChangeEmailRequest>,
+) -> Result<impl IntoResponse> {
+    auth.request_email_change(current_user.id, request).await?;
+    Ok(respond_accepted("Follow the link sent to the new address to confirm the change"))
+}
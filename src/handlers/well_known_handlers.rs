@@ -0,0 +1,17 @@
+use axum::{
+    extract::State,
+    http::header::CACHE_CONTROL,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::security::JwtKeys;
+
+/// Public keys for verifying our tokens, as a bare JWK set (RFC 7517) rather
+/// than the usual response envelope. Empty while tokens are HS256.
+pub async fn jwks(State(jwt_keys): State<JwtKeys>) -> impl IntoResponse {
+    (
+        [(CACHE_CONTROL, "public, max-age=300")],
+        Json(jwt_keys.jwks().clone()),
+    )
+}
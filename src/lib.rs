@@ -10,6 +10,11 @@ pub mod db;
 pub mod validation;
 pub mod extractors;
 pub mod cache;
+pub mod parser;
+pub mod markdown;
+pub mod authz;
+pub mod security;
+pub mod mail;
 
 // Re-export commonly used types for convenience
 pub use domain::error::{ApiError, Result};
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::{ApiError, Result};
+use super::mailer::{EmailMessage, Mailer};
+
+/// Development mailer: logs every message and, when `outbox_dir` is set,
+/// writes it there as a `.eml` file
+pub struct LogMailer {
+    outbox_dir: Option<PathBuf>,
+}
+
+impl LogMailer {
+    pub fn new(outbox_dir: Option<PathBuf>) -> Self {
+        Self { outbox_dir }
+    }
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, message: EmailMessage) -> Result<()> {
+        info!("Email to {}: {}\n{}", message.to, message.subject, message.body);
+
+        if let Some(dir) = &self.outbox_dir {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Creating mail outbox failed: {}", e)))?;
+
+            let path = dir.join(format!("{}-{}.eml", chrono::Utc::now().format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+            let contents = format!("To: {}\r\nSubject: {}\r\n\r\n{}\r\n", message.to, message.subject, message.body);
+            tokio::fs::write(&path, contents)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Writing {} failed: {}", path.display(), e)))?;
+        }
+
+        Ok(())
+    }
+}
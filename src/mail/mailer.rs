@@ -0,0 +1,16 @@
+use axum::async_trait;
+
+use crate::domain::Result;
+
+/// A plain-text email
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: EmailMessage) -> Result<()>;
+}
@@ -0,0 +1,21 @@
+// Mail module - outgoing email behind a pluggable transport
+pub mod mailer;
+pub mod smtp;
+pub mod log_mailer;
+
+use std::sync::Arc;
+
+use crate::config::settings::{MailConfig, MailTransport};
+use crate::domain::Result;
+
+pub use mailer::{EmailMessage, Mailer};
+pub use smtp::SmtpMailer;
+pub use log_mailer::LogMailer;
+
+/// Mailer for the configured transport
+pub fn build_mailer(config: &MailConfig) -> Result<Arc<dyn Mailer>> {
+    match config.transport {
+        MailTransport::Smtp => Ok(Arc::new(SmtpMailer::new(config)?)),
+        MailTransport::Log => Ok(Arc::new(LogMailer::new(config.outbox_dir.clone().map(Into::into)))),
+    }
+}
@@ -0,0 +1,64 @@
+use axum::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tracing::debug;
+
+use crate::config::settings::MailConfig;
+use crate::domain::{ApiError, Result};
+use super::mailer::{EmailMessage, Mailer};
+
+/// Delivers through an SMTP relay
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailConfig) -> Result<Self> {
+        let from = config.from
+            .parse::<Mailbox>()
+            .map_err(|e| ApiError::internal_error(format!("Invalid MAIL_FROM address: {}", e)))?;
+
+        let mut builder = if config.smtp_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                .map_err(|e| ApiError::internal_error(format!("SMTP relay setup failed: {}", e)))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        };
+        builder = builder.port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self { transport: builder.build(), from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: EmailMessage) -> Result<()> {
+        let to = message.to
+            .parse::<Mailbox>()
+            .map_err(|e| ApiError::bad_request(format!("Invalid recipient address: {}", e)))?;
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|e| ApiError::internal_error(format!("Building email failed: {}", e)))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("SMTP send failed: {}", e)))?;
+
+        debug!("Email sent to {}", message.to);
+        Ok(())
+    }
+}
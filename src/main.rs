@@ -1,13 +1,16 @@
 use note_task_api::{
-    config::AppConfig,
-    repositories::{UserRepository, TaskRepository},
-    services::{UserService, TaskService, AuthService},
-    routes::{api_v1_routes, health_routes},
-    middleware::{logging_middleware, request_logging_middleware, json_404_middleware},
+    config::{AppConfig, settings::EmailVerificationGate},
+    repositories::{UserRepository, RoleRepository, MfaRepository, ApiKeyRepository, OidcRepository, SessionRepository, MagicLinkRepository, TaskRepository, TemplateRepository, NoteRepository, NotebookRepository, ShareRepository, UserTokenRepository},
+    services::{UserService, RoleService, TaskService, AuthService, AuthRepositories, AuthServices, MfaService, LockoutService, SessionService, OidcService, MagicLinkService, ApiKeyService, NoteService, ShareService},
+    routes::{api_v1_routes, health_routes, well_known_routes, ApiServices},
+    middleware::{AuthState, logging_middleware, request_logging_middleware, json_404_middleware},
+    mail::build_mailer,
+    extractors::TrustedProxyHops,
+    security::JwtKeys,
     init_pg_pool,
 };
 
-use axum::Router;
+use axum::{Extension, Router};
 use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -37,26 +40,114 @@ async fn main() {
     
     // Initialize repositories (Postgres-backed)
     let user_repository = UserRepository::new(pool.clone());
+    let role_repository = RoleRepository::new(pool.clone());
     let task_repository = TaskRepository::new(pool.clone());
+    let template_repository = TemplateRepository::new(pool.clone());
+    let note_repository = NoteRepository::new(pool.clone());
+    let notebook_repository = NotebookRepository::new(pool.clone());
+    let share_repository = ShareRepository::new(pool.clone());
+    let user_token_repository = UserTokenRepository::new(pool.clone());
+    let mfa_repository = MfaRepository::new(pool.clone());
+    let api_key_repository = ApiKeyRepository::new(pool.clone());
+    let oidc_repository = OidcRepository::new(pool.clone());
+    let session_repository = SessionRepository::new(pool.clone());
+    let magic_link_repository = MagicLinkRepository::new(pool.clone());
     
     // Initialize Redis and cache
     let redis_client = RedisClient::open(config.redis.url.clone()).expect("Invalid REDIS_URL");
     let redis_manager = RedisConnectionManager::new(redis_client).await.expect("Failed to connect to Redis");
     let cache = RedisCache::new(redis_manager, config.redis.ttl_secs);
 
+    // Outgoing email
+    let mailer = build_mailer(&config.mail).expect("Invalid mail configuration");
+
+    // Token signing and verification keys
+    let jwt_keys = JwtKeys::from_config(&config.auth).expect("Invalid JWT key configuration");
+
     // Initialize services
     let user_service = UserService::new(user_repository.clone());
-    let task_service = TaskService::new(task_repository, user_repository.clone(), Some(cache.clone()));
-    let auth_service = AuthService::new(user_repository, config.auth.clone());
+    let lockout_service = LockoutService::new(Some(cache.clone()), user_repository.clone(), config.auth.lockout.clone());
+    let share_service = ShareService::new(share_repository, task_repository.clone(), note_repository.clone(), lockout_service.clone());
+    let task_service = TaskService::new(
+        task_repository,
+        user_repository.clone(),
+        template_repository,
+        note_repository.clone(),
+        Some(cache.clone()),
+        config.auth.email_verification == EmailVerificationGate::TaskCreation,
+    );
+    let note_service = NoteService::new(note_repository, notebook_repository, task_service.clone(), config.notes.clone());
+    let api_key_service = ApiKeyService::new(api_key_repository.clone());
+    let session_service = SessionService::new(session_repository, Some(cache.clone()), &config.auth);
+    let role_service = RoleService::new(role_repository.clone(), user_repository.clone(), session_service.clone());
+    let auth_state = AuthState {
+        config: config.auth.clone(),
+        jwt_keys: jwt_keys.clone(),
+        user_repository: user_repository.clone(),
+        role_repository: role_repository.clone(),
+        api_key_repository,
+        session_service: session_service.clone(),
+    };
+    let mfa_service = MfaService::new(
+        mfa_repository,
+        user_repository.clone(),
+        role_repository.clone(),
+        config.auth.mfa_issuer.clone(),
+    );
+    let auth_service = AuthService::new(
+        AuthRepositories {
+            users: user_repository.clone(),
+            roles: role_repository,
+            tokens: user_token_repository,
+        },
+        AuthServices {
+            mfa: mfa_service.clone(),
+            lockout: lockout_service.clone(),
+            sessions: session_service.clone(),
+        },
+        Some(cache.clone()),
+        mailer.clone(),
+        jwt_keys.clone(),
+        config.auth.clone(),
+        config.mail.app_base_url.clone(),
+    );
+    let oidc_service = OidcService::new(config.oidc.clone(), oidc_repository, user_repository.clone(), auth_service.clone());
+    let magic_link_service = MagicLinkService::new(
+        magic_link_repository,
+        user_repository,
+        auth_service.clone(),
+        Some(cache.clone()),
+        mailer,
+        config.auth.magic_link.clone(),
+        config.mail.app_base_url.clone(),
+    );
 
     // Build our application with modular routes
     let app = Router::new()
         .merge(health_routes())
-        .merge(api_v1_routes(user_service, task_service, auth_service, config.auth.clone()))
+        .merge(well_known_routes().with_state(jwt_keys))
+        .merge(api_v1_routes(
+            ApiServices {
+                user_service,
+                role_service,
+                task_service,
+                note_service,
+                share_service,
+                auth_service,
+                mfa_service,
+                api_key_service,
+                session_service,
+                lockout_service,
+                oidc_service,
+                magic_link_service,
+            },
+            auth_state,
+        ))
         // Add middleware
         .layer(axum::middleware::from_fn(request_logging_middleware))
         .layer(logging_middleware())
         .layer(axum::middleware::from_fn(json_404_middleware))
+        .layer(Extension(TrustedProxyHops(config.server.trusted_proxy_hops)))
         .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any));
 
     // Run the server using config-resolved host/port
@@ -64,5 +155,5 @@ async fn main() {
     tracing::info!("Server running on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 }
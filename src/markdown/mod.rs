@@ -0,0 +1,4 @@
+// Markdown module - CommonMark/GFM rendering with HTML sanitisation
+pub mod renderer;
+
+pub use renderer::render_markdown;
@@ -0,0 +1,55 @@
+use std::collections::{HashMap, HashSet};
+
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+
+/// Tags allowed to survive sanitisation
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "blockquote", "br", "code", "del", "em", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "input",
+    "li", "ol", "p", "pre", "strong", "table", "tbody", "td", "th", "thead", "tr", "ul",
+];
+
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+const LINK_REL: &str = "noopener noreferrer nofollow";
+
+/// Render a CommonMark + GFM document (tables, strikethrough, task lists)
+/// into HTML that is safe to embed in a page.
+///
+/// Raw HTML in the input goes through the same allowlist as the rendered
+/// output, so `<script>`, event handlers and `javascript:` links are dropped.
+pub fn render_markdown(input: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(input, options);
+
+    let mut unsafe_html = String::with_capacity(input.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, parser);
+
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut tag_attributes = HashMap::new();
+    tag_attributes.insert("a", HashSet::from(["href", "title"]));
+    tag_attributes.insert("code", HashSet::from(["class"]));
+    tag_attributes.insert("input", HashSet::from(["checked"]));
+    tag_attributes.insert("th", HashSet::from(["align"]));
+    tag_attributes.insert("td", HashSet::from(["align"]));
+
+    let mut builder = Builder::empty();
+    builder
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .tag_attributes(tag_attributes)
+        .url_schemes(ALLOWED_URL_SCHEMES.iter().copied().collect())
+        .link_rel(Some(LINK_REL))
+        // Task list markers are the only inputs we emit; force any other input to a read-only checkbox
+        .set_tag_attribute_value("input", "type", "checkbox")
+        .set_tag_attribute_value("input", "disabled", "")
+        .strip_comments(true)
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            // Only keep fenced-code language hints
+            ("code", "class") if !value.starts_with("language-") => None,
+            _ => Some(value.into()),
+        });
+    builder
+}
@@ -1,33 +1,98 @@
 use axum::{
     extract::{Request, State},
-    http::{header::AUTHORIZATION, StatusCode},
+    http::{header::AUTHORIZATION, Method, StatusCode},
     middleware::Next,
     response::{Response, IntoResponse},
     Json,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use jsonwebtoken::Validation;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tracing::debug;
 use uuid::Uuid;
 
+use crate::authz::{authorize, Action, Actor, Resource};
 use crate::config::settings::AuthConfig;
-use crate::domain::user::UserRole;
+use crate::domain::{ApiScope, Permission};
+use crate::domain::api_key::{scoped_permissions, API_KEY_PREFIX};
+use crate::repositories::{ApiKeyRepository, RoleRepository, UserRepository};
+use crate::security::{hash_token, JwtKeys};
+use crate::services::SessionService;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,        // user id
     pub email: String,
     pub role: String,       // user role
+    #[serde(default)]
+    pub permissions: Vec<String>, // granted by the role when the token was issued
     pub iss: String,
     pub aud: String,
     pub exp: usize,
+    #[serde(default)]
+    pub ver: i32,           // users.token_version when the token was issued
+    #[serde(default)]
+    pub sid: Option<String>, // session the token belongs to
+}
+
+/// State for `auth_middleware`
+#[derive(Debug, Clone)]
+pub struct AuthState {
+    pub config: AuthConfig,
+    pub jwt_keys: JwtKeys,
+    /// Checks tokens against the user's current token version
+    pub user_repository: UserRepository,
+    pub role_repository: RoleRepository,
+    pub api_key_repository: ApiKeyRepository,
+    /// Rejects tokens of revoked sessions
+    pub session_service: SessionService,
 }
 
 #[derive(Debug, Clone)]
 pub struct CurrentUser {
     pub id: Uuid,
     pub email: String,
-    pub role: UserRole,
+    pub role: String,
+    pub permissions: Vec<Permission>,
+    /// Set when the request came with an API key rather than a login token
+    pub scopes: Option<Vec<ApiScope>>,
+    /// Login session of the token; unset for API keys
+    pub session_id: Option<Uuid>,
+}
+
+impl CurrentUser {
+    pub fn is_api_key(&self) -> bool {
+        self.scopes.is_some()
+    }
+}
+
+/// Header scripts can send an API key in instead of `Authorization`
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Which API key scopes a group of routes accepts. Reads (GET, HEAD) need the
+/// read scope, anything else the write scope.
+#[derive(Debug, Clone, Copy)]
+pub enum ApiKeyAccess {
+    Tasks,
+    Notes,
+    /// Read-only access to users, with `profile:read`
+    Profile,
+    /// Login sessions only
+    Denied,
+}
+
+impl ApiKeyAccess {
+    fn required_scope(self, method: &Method) -> Option<ApiScope> {
+        let read = method == Method::GET || method == Method::HEAD;
+        match (self, read) {
+            (ApiKeyAccess::Tasks, true) => Some(ApiScope::TasksRead),
+            (ApiKeyAccess::Tasks, false) => Some(ApiScope::TasksWrite),
+            (ApiKeyAccess::Notes, true) => Some(ApiScope::NotesRead),
+            (ApiKeyAccess::Notes, false) => Some(ApiScope::NotesWrite),
+            (ApiKeyAccess::Profile, true) => Some(ApiScope::ProfileRead),
+            (ApiKeyAccess::Profile, false) | (ApiKeyAccess::Denied, _) => None,
+        }
+    }
 }
 
 fn create_error_response(status: StatusCode, message: &str) -> Response {
@@ -40,51 +105,153 @@ fn create_error_response(status: StatusCode, message: &str) -> Response {
 }
 
 pub async fn auth_middleware(
-    State(auth_config): State<AuthConfig>,
+    State(auth_state): State<AuthState>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    // Extract Authorization header
-    let auth_header = request
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|header| header.to_str().ok())
-        .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Missing or invalid Authorization header"))?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err(create_error_response(StatusCode::UNAUTHORIZED, "Invalid token format. Expected 'Bearer <token>'"));
-    }
+    let current_user = match request.headers().get(API_KEY_HEADER) {
+        Some(api_key) => {
+            let api_key = api_key
+                .to_str()
+                .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid API key"))?;
+            authenticate_api_key(&auth_state, api_key.trim()).await?
+        }
+        None => {
+            // Extract Authorization header
+            let auth_header = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|header| header.to_str().ok())
+                .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Missing or invalid Authorization header"))?;
+
+            // Check if it's a Bearer token
+            let token = auth_header
+                .strip_prefix("Bearer ")
+                .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Invalid token format. Expected 'Bearer <token>'"))?;
+
+            if token.starts_with(API_KEY_PREFIX) {
+                authenticate_api_key(&auth_state, token).await?
+            } else {
+                authenticate_jwt(&auth_state, token).await?
+            }
+        }
+    };
 
-    // Extract the token
-    let token = &auth_header[7..]; // Remove "Bearer " prefix
+    // Insert CurrentUser into request extensions
+    request.extensions_mut().insert(current_user);
+
+    Ok(next.run(request).await)
+}
 
+async fn authenticate_jwt(auth_state: &AuthState, token: &str) -> Result<CurrentUser, Response> {
     // Decode and validate the JWT
-    let decoding_key = DecodingKey::from_secret(auth_config.jwt_secret.as_bytes());
-    let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
+    let auth_config = &auth_state.config;
+    let mut validation = Validation::default();
     validation.set_issuer(&[&auth_config.issuer]);
     validation.set_audience(&[&auth_config.audience]);
 
-    let token_data = decode::<Claims>(token, &decoding_key, &validation)
+    let claims = auth_state.jwt_keys
+        .decode::<Claims>(token, &validation)
         .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
 
     // Parse user ID
-    let user_id = Uuid::parse_str(&token_data.claims.sub)
+    let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid user ID in token"))?;
 
-    // Parse user role
-    let user_role = token_data.claims.role.parse::<UserRole>()
-        .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid user role in token"))?;
+    let session_id = claims.sid
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid session ID in token"))?;
+    match session_id {
+        // A password change revokes every session, so the cached session
+        // state also covers tokens issued before it
+        Some(session_id) => {
+            let active = auth_state.session_service
+                .is_active(session_id)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            if !active {
+                return Err(create_error_response(StatusCode::UNAUTHORIZED, "Session has been revoked"));
+            }
+        }
+        // Tokens from before sessions existed: those issued before the last
+        // password change are revoked
+        None => {
+            let current_version = auth_state.user_repository
+                .find_token_version(user_id)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            if current_version != Some(claims.ver) {
+                return Err(create_error_response(StatusCode::UNAUTHORIZED, "Token has been revoked"));
+            }
+        }
+    }
+
+    // Permissions unknown to this build are dropped
+    let permissions = claims.permissions
+        .iter()
+        .filter_map(|p| p.parse::<Permission>().ok())
+        .collect();
 
-    // Create CurrentUser and attach to request
-    let current_user = CurrentUser {
+    Ok(CurrentUser {
         id: user_id,
-        email: token_data.claims.email,
-        role: user_role,
-    };
+        email: claims.email,
+        role: claims.role,
+        permissions,
+        scopes: None,
+        session_id,
+    })
+}
 
-    // Insert CurrentUser into request extensions
-    request.extensions_mut().insert(current_user);
+/// API keys act as their user, with the role's permissions cut down to the key's scopes
+async fn authenticate_api_key(auth_state: &AuthState, key: &str) -> Result<CurrentUser, Response> {
+    let api_key = auth_state.api_key_repository
+        .find_active_and_touch(&hash_token(key))
+        .await
+        .map_err(IntoResponse::into_response)?
+        .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Invalid or expired API key"))?;
+
+    let user = auth_state.user_repository
+        .find_by_id(api_key.user_id)
+        .await
+        .map_err(|_| create_error_response(StatusCode::UNAUTHORIZED, "Invalid or expired API key"))?;
+    let permissions = auth_state.role_repository
+        .find_permissions_for_user(user.id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    debug!("Request authenticated with API key {} of user {}", api_key.id, user.id);
+    Ok(CurrentUser {
+        id: user.id,
+        email: user.email,
+        role: user.role,
+        permissions: scoped_permissions(permissions, &api_key.scopes),
+        scopes: Some(api_key.scopes),
+        session_id: None,
+    })
+}
+
+/// Refuses API keys that lack the scope a group of routes needs. Runs after
+/// `auth_middleware`; login sessions pass straight through.
+pub async fn api_key_scope_middleware(
+    State(access): State<ApiKeyAccess>,
+    request: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    let current_user = request
+        .extensions()
+        .get::<CurrentUser>()
+        .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Authentication required"))?;
+
+    if let Some(scopes) = &current_user.scopes {
+        let allowed = access
+            .required_scope(request.method())
+            .is_some_and(|required| scopes.iter().any(|scope| scope.grants(required)));
+        if !allowed {
+            return Err(create_error_response(StatusCode::FORBIDDEN, "API key does not have the scope for this endpoint"));
+        }
+    }
 
     Ok(next.run(request).await)
 }
@@ -99,10 +266,8 @@ pub async fn admin_only_middleware(
         .get::<CurrentUser>()
         .ok_or_else(|| create_error_response(StatusCode::UNAUTHORIZED, "Authentication required"))?;
 
-    // Check if user has admin role
-    if current_user.role != UserRole::Admin {
-        return Err(create_error_response(StatusCode::FORBIDDEN, "Admin access required"));
-    }
+    authorize(&Actor::from(current_user), Action::Administer, &Resource::System)
+        .map_err(IntoResponse::into_response)?;
 
     Ok(next.run(request).await)
 }
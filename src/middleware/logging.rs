@@ -1,5 +1,6 @@
 use axum::{
     extract::Request,
+    http::Uri,
     middleware::Next,
     response::Response,
 };
@@ -7,23 +8,42 @@ use tower_http::trace::{
     TraceLayer, 
     DefaultOnRequest, 
     DefaultOnResponse, 
-    DefaultMakeSpan
+    MakeSpan
 };
-use tracing::{info, debug, Level};
+use tracing::{info, debug, Level, Span};
 
-pub fn logging_middleware() -> TraceLayer<tower_http::classify::SharedClassifier<tower_http::classify::ServerErrorsAsFailures>> {
+/// Path segment after which the next segment is a share link token
+const PUBLIC_SHARES_SEGMENT: &str = "/public/shares/";
+
+pub fn logging_middleware() -> TraceLayer<tower_http::classify::SharedClassifier<tower_http::classify::ServerErrorsAsFailures>, RedactedMakeSpan> {
     TraceLayer::new_for_http()
-        .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+        .make_span_with(RedactedMakeSpan)
         .on_request(DefaultOnRequest::new().level(Level::INFO))
         .on_response(DefaultOnResponse::new().level(Level::INFO))
 }
 
+/// Like tower-http's `DefaultMakeSpan` at info level, but with secrets in the
+/// path redacted
+#[derive(Debug, Clone, Copy)]
+pub struct RedactedMakeSpan;
+
+impl<B> MakeSpan<B> for RedactedMakeSpan {
+    fn make_span(&mut self, request: &axum::http::Request<B>) -> Span {
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %redact_uri(request.uri()),
+            version = ?request.version(),
+        )
+    }
+}
+
 pub async fn request_logging_middleware(
     request: Request,
     next: Next,
 ) -> Response {
     let method = request.method().clone();
-    let uri = request.uri().clone();
+    let uri = redact_uri(request.uri());
     let headers = request.headers().clone();
 
     // Log request details
@@ -51,7 +71,7 @@ pub async fn request_logging_middleware(
 }
 
 fn filter_sensitive_headers(headers: axum::http::HeaderMap) -> Vec<(String, String)> {
-    let sensitive_headers = ["authorization", "cookie", "x-api-key"];
+    let sensitive_headers = ["authorization", "cookie", "x-api-key", "x-share-password"];
     
     headers
         .iter()
@@ -66,4 +86,41 @@ fn filter_sensitive_headers(headers: axum::http::HeaderMap) -> Vec<(String, Stri
             )
         })
         .collect()
-}
\ No newline at end of file
+}
+
+/// The URI as it may be logged: the token of a public share link is a
+/// credential, so it is replaced
+fn redact_uri(uri: &Uri) -> String {
+    let uri = uri.to_string();
+    let Some(start) = uri.find(PUBLIC_SHARES_SEGMENT).map(|i| i + PUBLIC_SHARES_SEGMENT.len()) else {
+        return uri;
+    };
+    let end = uri[start..].find(['/', '?']).map_or(uri.len(), |i| start + i);
+    format!("{}[redacted]{}", &uri[..start], &uri[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_token_is_redacted() {
+        let uri: Uri = "/api/v1/public/shares/abc123secret?x=1".parse().unwrap();
+        assert_eq!(redact_uri(&uri), "/api/v1/public/shares/[redacted]?x=1");
+    }
+
+    #[test]
+    fn other_uris_are_left_alone() {
+        let uri: Uri = "/api/v1/shares/6f1c?page=2".parse().unwrap();
+        assert_eq!(redact_uri(&uri), "/api/v1/shares/6f1c?page=2");
+    }
+
+    #[test]
+    fn share_password_header_is_filtered() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("x-share-password", "hunter2".parse().unwrap());
+        headers.insert("accept", "application/json".parse().unwrap());
+        let logged = filter_sensitive_headers(headers);
+        assert_eq!(logged, vec![("accept".to_string(), "application/json".to_string())]);
+    }
+}
@@ -3,6 +3,6 @@ pub mod auth;
 pub mod logging;
 pub mod not_found;
 
-pub use auth::{auth_middleware, admin_only_middleware, CurrentUser};
+pub use auth::{auth_middleware, admin_only_middleware, api_key_scope_middleware, ApiKeyAccess, AuthState, CurrentUser};
 pub use logging::{logging_middleware, request_logging_middleware};
 pub use not_found::json_404_middleware;
@@ -0,0 +1,8 @@
+// Parser module - free-text input parsing
+pub mod quick_add;
+pub mod wiki_links;
+pub mod task_items;
+
+pub use quick_add::{parse_quick_add, QuickAddParse};
+pub use wiki_links::{parse_wiki_links, rewrite_wiki_links};
+pub use task_items::{parse_task_items, set_task_item_checked, TaskListItem};
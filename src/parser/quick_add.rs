@@ -0,0 +1,502 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::task::{normalize_tags, TaskPriority};
+
+/// Fields extracted from a quick-add line such as "Pay rent tomorrow 9am #finance !high"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickAddParse {
+    /// What is left of the input once dates, tags, priority and assignee are removed
+    pub title: String,
+    pub due_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub priority: Option<TaskPriority>,
+    /// Assignee handle without the leading `@`, resolved to a user by the service
+    pub assignee: Option<String>,
+}
+
+/// Words that only introduce a date or time ("on friday", "at 9am", "due tomorrow")
+const CONNECTORS: [&str; 4] = ["on", "at", "by", "due"];
+
+/// A date without a time is due at the end of that day
+fn default_due_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 0).unwrap()
+}
+
+fn tonight_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(20, 0, 0).unwrap()
+}
+
+/// Parse a quick-add line. Relative dates ("tomorrow", "friday", "in 2 days")
+/// are resolved against `now` as seen in the user's timezone `tz`.
+pub fn parse_quick_add(input: &str, now: DateTime<Utc>, tz: Tz) -> QuickAddParse {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let words: Vec<String> = tokens.iter().map(|t| normalize_word(t)).collect();
+    let mut consumed = vec![false; tokens.len()];
+
+    let today = now.with_timezone(&tz).date_naive();
+    let mut date: Option<NaiveDate> = None;
+    let mut time: Option<NaiveTime> = None;
+    let mut instant: Option<DateTime<Utc>> = None;
+    let mut tonight = false;
+
+    let mut tags = Vec::new();
+    let mut priority = None;
+    let mut assignee = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let word = &words[i];
+
+        if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            tags.push(tag.to_string());
+            consumed[i] = true;
+            i += 1;
+            continue;
+        }
+
+        if let Some(level) = word.strip_prefix('!')
+            && priority.is_none()
+            && let Some(parsed) = parse_priority(level)
+        {
+            priority = Some(parsed);
+            consumed[i] = true;
+            i += 1;
+            continue;
+        }
+
+        if let Some(handle) = word.strip_prefix('@').filter(|h| !h.is_empty())
+            && assignee.is_none()
+        {
+            assignee = Some(handle.to_string());
+            consumed[i] = true;
+            i += 1;
+            continue;
+        }
+
+        let mut matched = None;
+        if instant.is_none() && date.is_none() && time.is_none()
+            && let Some((relative, used)) = match_relative(&words, i, now, today)
+        {
+            match relative {
+                Relative::Instant(at) => instant = Some(at),
+                Relative::Date(d) => date = Some(d),
+            }
+            matched = Some(used);
+        }
+        if matched.is_none() && instant.is_none() && date.is_none()
+            && let Some((d, used, is_tonight)) = match_date(&words, i, today)
+        {
+            date = Some(d);
+            tonight = is_tonight;
+            matched = Some(used);
+        }
+        if matched.is_none() && instant.is_none() && time.is_none()
+            && let Some((t, used)) = match_time(&words, i, i > 0 && words[i - 1] == "at")
+        {
+            time = Some(t);
+            matched = Some(used);
+        }
+
+        match matched {
+            Some(used) => {
+                for flag in consumed.iter_mut().skip(i).take(used) {
+                    *flag = true;
+                }
+                if i > 0 && !consumed[i - 1] && CONNECTORS.contains(&words[i - 1].as_str()) {
+                    consumed[i - 1] = true;
+                }
+                i += used;
+            }
+            None => i += 1,
+        }
+    }
+
+    let due_at = instant.or_else(|| match (date, time) {
+        (None, None) => None,
+        (Some(d), t) => {
+            let fallback = if tonight { tonight_time() } else { default_due_time() };
+            Some(to_utc(tz, d, t.unwrap_or(fallback)))
+        }
+        (None, Some(t)) => {
+            // A bare time means the next time the clock shows it
+            let candidate = to_utc(tz, today, t);
+            if candidate > now {
+                Some(candidate)
+            } else {
+                Some(to_utc(tz, today + Duration::days(1), t))
+            }
+        }
+    });
+
+    let title = tokens
+        .iter()
+        .zip(consumed.iter())
+        .filter(|(_, used)| !**used)
+        .map(|(token, _)| *token)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    QuickAddParse {
+        title,
+        due_at,
+        tags: normalize_tags(&tags),
+        priority,
+        assignee,
+    }
+}
+
+enum Relative {
+    Instant(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+fn normalize_word(token: &str) -> String {
+    token
+        .trim_end_matches([',', '.', ';', ':', ')'])
+        .trim_start_matches('(')
+        .to_lowercase()
+}
+
+fn parse_priority(level: &str) -> Option<TaskPriority> {
+    match level {
+        "1" | "p1" => Some(TaskPriority::Urgent),
+        "2" | "p2" => Some(TaskPriority::High),
+        "3" | "p3" | "med" | "normal" => Some(TaskPriority::Medium),
+        "4" | "p4" => Some(TaskPriority::Low),
+        other => other.parse().ok(),
+    }
+}
+
+/// "in 3 days", "in 2 weeks", "in an hour", "in 30 minutes"
+fn match_relative(words: &[String], i: usize, now: DateTime<Utc>, today: NaiveDate) -> Option<(Relative, usize)> {
+    if words[i] != "in" {
+        return None;
+    }
+    let amount = match words.get(i + 1)?.as_str() {
+        "a" | "an" | "one" => 1,
+        n => n.parse::<i64>().ok().filter(|n| (1..=1000).contains(n))?,
+    };
+    let relative = match words.get(i + 2)?.trim_end_matches('s') {
+        "minute" | "min" => Relative::Instant(now + Duration::minutes(amount)),
+        "hour" | "hr" => Relative::Instant(now + Duration::hours(amount)),
+        "day" => Relative::Date(today + Duration::days(amount)),
+        "week" | "wk" => Relative::Date(today + Duration::weeks(amount)),
+        "month" => Relative::Date(today.checked_add_months(Months::new(amount as u32))?),
+        _ => return None,
+    };
+    Some((relative, 3))
+}
+
+/// Returns the date, the number of tokens used and whether it was "tonight"
+fn match_date(words: &[String], i: usize, today: NaiveDate) -> Option<(NaiveDate, usize, bool)> {
+    let word = words[i].as_str();
+    match word {
+        "today" => return Some((today, 1, false)),
+        "tonight" => return Some((today, 1, true)),
+        "tomorrow" | "tmr" | "tmrw" => return Some((today + Duration::days(1), 1, false)),
+        _ => {}
+    }
+
+    if let Some(weekday) = parse_weekday(word) {
+        let ahead = days_until(today.weekday(), weekday);
+        return Some((today + Duration::days(ahead), 1, false));
+    }
+
+    if word == "next" {
+        let next = words.get(i + 1)?.as_str();
+        if let Some(weekday) = parse_weekday(next) {
+            let ahead = match days_until(today.weekday(), weekday) {
+                0 => 7,
+                n => n,
+            };
+            return Some((today + Duration::days(ahead), 2, false));
+        }
+        return match next {
+            "week" => {
+                let ahead = 7 - today.weekday().num_days_from_monday() as i64;
+                Some((today + Duration::days(ahead), 2, false))
+            }
+            "month" => {
+                let first = today.with_day(1)?.checked_add_months(Months::new(1))?;
+                Some((first, 2, false))
+            }
+            _ => None,
+        };
+    }
+
+    if let Ok(iso) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some((iso, 1, false));
+    }
+
+    // "oct 14", "october 14th 2026", "14 oct", "14th of october"
+    let (month, day, used) = if let Some(month) = parse_month(word) {
+        let day = parse_day(words.get(i + 1)?)?;
+        (month, day, 2)
+    } else {
+        let day = parse_day(word)?;
+        let next = words.get(i + 1)?;
+        if next == "of" {
+            (parse_month(words.get(i + 2)?)?, day, 3)
+        } else {
+            (parse_month(next)?, day, 2)
+        }
+    };
+
+    let explicit_year = words
+        .get(i + used)
+        .and_then(|w| w.parse::<i32>().ok())
+        .filter(|y| (1970..=9999).contains(y));
+    match explicit_year {
+        Some(year) => Some((NaiveDate::from_ymd_opt(year, month, day)?, used + 1, false)),
+        None => {
+            let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+            if this_year >= today {
+                Some((this_year, used, false))
+            } else {
+                Some((NaiveDate::from_ymd_opt(today.year() + 1, month, day)?, used, false))
+            }
+        }
+    }
+}
+
+/// "9am", "9:30pm", "9 am", "21:00", "noon", "midnight", and a bare hour after "at"
+fn match_time(words: &[String], i: usize, after_at: bool) -> Option<(NaiveTime, usize)> {
+    let word = words[i].as_str();
+    match word {
+        "noon" | "midday" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 1)),
+        _ => {}
+    }
+
+    for (suffix, pm) in [("am", false), ("pm", true)] {
+        if let Some(clock) = word.strip_suffix(suffix)
+            && !clock.is_empty()
+        {
+            return twelve_hour(clock, pm).map(|t| (t, 1));
+        }
+    }
+
+    if let Some(next) = words.get(i + 1) {
+        let pm = match next.as_str() {
+            "am" => Some(false),
+            "pm" => Some(true),
+            _ => None,
+        };
+        if let Some(pm) = pm
+            && let Some(t) = twelve_hour(word, pm)
+        {
+            return Some((t, 2));
+        }
+    }
+
+    if word.contains(':') {
+        return NaiveTime::parse_from_str(word, "%H:%M").ok().map(|t| (t, 1));
+    }
+
+    if after_at {
+        let hour = word.parse::<u32>().ok()?;
+        return NaiveTime::from_hms_opt(hour, 0, 0).map(|t| (t, 1));
+    }
+
+    None
+}
+
+fn twelve_hour(clock: &str, pm: bool) -> Option<NaiveTime> {
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    let hour = match (hour, pm) {
+        (12, false) => 0,
+        (12, true) => 12,
+        (h, true) => h + 12,
+        (h, false) => h,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    let month = match word {
+        "jan" | "january" => 1,
+        "feb" | "february" => 2,
+        "mar" | "march" => 3,
+        "apr" | "april" => 4,
+        "may" => 5,
+        "jun" | "june" => 6,
+        "jul" | "july" => 7,
+        "aug" | "august" => 8,
+        "sep" | "sept" | "september" => 9,
+        "oct" | "october" => 10,
+        "nov" | "november" => 11,
+        "dec" | "december" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// "14", "14th", "1st", "22nd", "3rd"
+fn parse_day(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if !["", "st", "nd", "rd", "th"].contains(&suffix) {
+        return None;
+    }
+    digits.parse::<u32>().ok().filter(|d| (1..=31).contains(d))
+}
+
+/// Days from `from` until the next `to`, 0 when they are the same day
+fn days_until(from: Weekday, to: Weekday) -> i64 {
+    let from = from.num_days_from_monday() as i64;
+    let to = to.num_days_from_monday() as i64;
+    (to - from).rem_euclid(7)
+}
+
+fn to_utc(tz: Tz, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let naive = date.and_time(time);
+    // Local times skipped by a DST jump are moved forward by an hour
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&naive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2026-10-14, 10:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 14, 10, 0, 0).unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> Option<DateTime<Utc>> {
+        Some(Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap())
+    }
+
+    fn parse(input: &str) -> QuickAddParse {
+        parse_quick_add(input, now(), chrono_tz::UTC)
+    }
+
+    #[test]
+    fn extracts_tags_priority_and_assignee() {
+        let parsed = parse("Pay rent tomorrow 9am #Finance #home #finance !high @Alex");
+        assert_eq!(parsed.title, "Pay rent");
+        assert_eq!(parsed.due_at, at(2026, 10, 15, 9, 0));
+        assert_eq!(parsed.tags, vec!["finance", "home"]);
+        assert_eq!(parsed.priority, Some(TaskPriority::High));
+        assert_eq!(parsed.assignee.as_deref(), Some("alex"));
+    }
+
+    #[test]
+    fn numbered_priorities() {
+        assert_eq!(parse("Fix prod !p1").priority, Some(TaskPriority::Urgent));
+        assert_eq!(parse("Tidy up !4").priority, Some(TaskPriority::Low));
+        let unknown = parse("Wow !!");
+        assert_eq!(unknown.priority, None);
+        assert_eq!(unknown.title, "Wow !!");
+    }
+
+    #[test]
+    fn plain_text_has_no_due_date() {
+        let parsed = parse("Just a title");
+        assert_eq!(parsed.title, "Just a title");
+        assert_eq!(parsed.due_at, None);
+        assert!(parsed.tags.is_empty());
+        assert_eq!(parsed.priority, None);
+        assert_eq!(parsed.assignee, None);
+    }
+
+    #[test]
+    fn date_without_time_is_due_at_end_of_day() {
+        assert_eq!(parse("Call mom friday").due_at, at(2026, 10, 16, 23, 59));
+        assert_eq!(parse("Call mom today").due_at, at(2026, 10, 14, 23, 59));
+        assert_eq!(parse("Movie tonight").due_at, at(2026, 10, 14, 20, 0));
+    }
+
+    #[test]
+    fn connectors_are_dropped_with_the_date() {
+        let parsed = parse("Submit report by friday");
+        assert_eq!(parsed.title, "Submit report");
+        assert_eq!(parsed.due_at, at(2026, 10, 16, 23, 59));
+
+        let parsed = parse("Dentist on oct 20 at 3pm");
+        assert_eq!(parsed.title, "Dentist");
+        assert_eq!(parsed.due_at, at(2026, 10, 20, 15, 0));
+    }
+
+    #[test]
+    fn next_weekday_skips_today() {
+        assert_eq!(parse("Review wednesday").due_at, at(2026, 10, 14, 23, 59));
+        assert_eq!(parse("Review next wednesday").due_at, at(2026, 10, 21, 23, 59));
+        assert_eq!(parse("Plan next week").due_at, at(2026, 10, 19, 23, 59));
+        assert_eq!(parse("Invoice next month").due_at, at(2026, 11, 1, 23, 59));
+    }
+
+    #[test]
+    fn relative_offsets() {
+        assert_eq!(parse("Check oven in 30 minutes").due_at, at(2026, 10, 14, 10, 30));
+        assert_eq!(parse("Ping back in an hour").due_at, at(2026, 10, 14, 11, 0));
+        assert_eq!(parse("Follow up in 3 days").due_at, at(2026, 10, 17, 23, 59));
+        assert_eq!(parse("Renew in 2 weeks").due_at, at(2026, 10, 28, 23, 59));
+    }
+
+    #[test]
+    fn bare_time_means_the_next_occurrence() {
+        assert_eq!(parse("Standup 9:30am").due_at, at(2026, 10, 15, 9, 30));
+        assert_eq!(parse("Gym at 17").due_at, at(2026, 10, 14, 17, 0));
+        assert_eq!(parse("Lunch noon").due_at, at(2026, 10, 14, 12, 0));
+        assert_eq!(parse("Call 21:15").due_at, at(2026, 10, 14, 21, 15));
+    }
+
+    #[test]
+    fn month_day_dates() {
+        // Already past this year, so next year
+        assert_eq!(parse("Taxes oct 1").due_at, at(2027, 10, 1, 23, 59));
+        assert_eq!(parse("Party 14th of november").due_at, at(2026, 11, 14, 23, 59));
+        assert_eq!(parse("Trip 3 jan 2028").due_at, at(2028, 1, 3, 23, 59));
+        assert_eq!(parse("Launch 2026-12-01").due_at, at(2026, 12, 1, 23, 59));
+    }
+
+    #[test]
+    fn times_are_read_in_the_users_timezone() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // 9am EDT is 13:00 UTC
+        let parsed = parse_quick_add("Call bank tomorrow 9am", now(), tz);
+        assert_eq!(parsed.due_at, at(2026, 10, 15, 13, 0));
+    }
+
+    #[test]
+    fn parse_day_accepts_ordinals_only() {
+        assert_eq!(parse_day("1st"), Some(1));
+        assert_eq!(parse_day("22nd"), Some(22));
+        assert_eq!(parse_day("32"), None);
+        assert_eq!(parse_day("5x"), None);
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        assert_eq!(twelve_hour("12", false), NaiveTime::from_hms_opt(0, 0, 0));
+        assert_eq!(twelve_hour("12", true), NaiveTime::from_hms_opt(12, 0, 0));
+        assert_eq!(twelve_hour("7:45", true), NaiveTime::from_hms_opt(19, 45, 0));
+        assert_eq!(twelve_hour("13", true), None);
+    }
+}
@@ -0,0 +1,85 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A GFM task-list item such as `- [ ] follow up with vendor`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskListItem {
+    /// Zero-based line number in the body
+    pub line: usize,
+    pub text: String,
+    pub checked: bool,
+}
+
+fn task_item_regex() -> Regex {
+    Regex::new(r"^(\s*(?:[-*+]|\d+[.)])\s+\[)([ xX])(\]\s+)(.*\S)\s*$").unwrap()
+}
+
+/// Lines that are inside fenced code blocks, which never hold task items
+fn fenced_lines(body: &str) -> Vec<bool> {
+    let mut in_fence = false;
+    body.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return true;
+            }
+            in_fence
+        })
+        .collect()
+}
+
+/// Collect the task-list items of a markdown body, skipping fenced code
+pub fn parse_task_items(body: &str) -> Vec<TaskListItem> {
+    let regex = task_item_regex();
+    let fenced = fenced_lines(body);
+
+    body.lines()
+        .enumerate()
+        .filter(|(line, _)| !fenced[*line])
+        .filter_map(|(line, content)| {
+            let caps = regex.captures(content)?;
+            Some(TaskListItem {
+                line,
+                text: caps[4].trim().to_string(),
+                checked: &caps[2] != " ",
+            })
+        })
+        .collect()
+}
+
+/// Tick or untick every item whose text is `text`. Returns `None` when no
+/// such item exists.
+pub fn set_task_item_checked(body: &str, text: &str, checked: bool) -> Option<String> {
+    let regex = task_item_regex();
+    let fenced = fenced_lines(body);
+    let mark = if checked { "x" } else { " " };
+    let mut found = false;
+
+    let lines: Vec<String> = body
+        .lines()
+        .enumerate()
+        .map(|(line, content)| {
+            if fenced[line] {
+                return content.to_string();
+            }
+            match regex.captures(content) {
+                Some(caps) if caps[4].trim() == text => {
+                    found = true;
+                    format!("{}{}{}{}", &caps[1], mark, &caps[3], &caps[4])
+                }
+                _ => content.to_string(),
+            }
+        })
+        .collect();
+
+    if !found {
+        return None;
+    }
+
+    let mut updated = lines.join("\n");
+    if body.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
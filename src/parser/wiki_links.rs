@@ -0,0 +1,34 @@
+use regex::{Captures, Regex};
+
+/// `[[target]]` or `[[target|label]]`
+fn wiki_link_regex() -> Regex {
+    Regex::new(r"\[\[([^\[\]|\n]+)(\|[^\[\]\n]*)?\]\]").unwrap()
+}
+
+/// Collect the distinct targets of `[[Note Title]]`, `[[slug]]` and
+/// `[[target|label]]` references in a note body, in order of appearance
+pub fn parse_wiki_links(body: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for caps in wiki_link_regex().captures_iter(body) {
+        let target = caps[1].trim();
+        if !target.is_empty() && !targets.iter().any(|t| t == target) {
+            targets.push(target.to_string());
+        }
+    }
+    targets
+}
+
+/// Point every link whose target satisfies `matches` at `new_target`,
+/// keeping any `|label`
+pub fn rewrite_wiki_links(body: &str, matches: impl Fn(&str) -> bool, new_target: impl Fn(&str) -> String) -> String {
+    wiki_link_regex()
+        .replace_all(body, |caps: &Captures| {
+            let target = caps[1].trim();
+            if !matches(target) {
+                return caps[0].to_string();
+            }
+            let label = caps.get(2).map_or("", |m| m.as_str());
+            format!("[[{}{}]]", new_target(target), label)
+        })
+        .into_owned()
+}
@@ -0,0 +1,142 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{ApiKey, ApiScope, Result, ApiError};
+
+/// Row shape of an API key, scopes still as stored
+struct ApiKeyRow {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    key_hint: String,
+    scopes: Vec<String>,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ApiKeyRow> for ApiKey {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            key_hint: row.key_hint,
+            // Scopes this build does not know about grant nothing
+            scopes: row.scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyRepository {
+    pool: PgPool,
+}
+
+impl ApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        key_hint: &str,
+        key_hash: &str,
+        scopes: &[ApiScope],
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<ApiKey> {
+        let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        let rec = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            INSERT INTO api_keys (user_id, name, key_hint, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, key_hint, scopes, expires_at, last_used_at, created_at
+            "#,
+            user_id,
+            name,
+            key_hint,
+            key_hash,
+            &scopes,
+            expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert API key error: {}", e)))?;
+
+        Ok(rec.into())
+    }
+
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        let recs = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT id, user_id, name, key_hint, scopes, expires_at, last_used_at, created_at
+            FROM api_keys
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select API keys error: {}", e)))?;
+
+        Ok(recs.into_iter().map(ApiKey::from).collect())
+    }
+
+    pub async fn count_by_user(&self, user_id: Uuid) -> Result<i64> {
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM api_keys WHERE user_id = $1"#, user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB count API keys error: {}", e)))
+    }
+
+    /// Look up a key that has not expired, recording that it was used.
+    /// `last_used_at` is only written once a minute to keep busy keys cheap.
+    pub async fn find_active_and_touch(&self, key_hash: &str) -> Result<Option<ApiKey>> {
+        let rec = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            SELECT id, user_id, name, key_hint, scopes, expires_at, last_used_at, created_at
+            FROM api_keys
+            WHERE key_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select API key error: {}", e)))?;
+
+        let Some(rec) = rec else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE api_keys SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+            "#,
+            rec.id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB touch API key error: {}", e)))?;
+
+        Ok(Some(rec.into()))
+    }
+
+    /// Delete one of the user's keys; `false` if they have no such key
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM api_keys WHERE id = $1 AND user_id = $2", id, user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete API key error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,74 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{Result, ApiError};
+
+#[derive(Debug, Clone)]
+pub struct MagicLinkRepository {
+    pool: PgPool,
+}
+
+impl MagicLinkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new link, replacing any unused link of the user; stale links
+    /// are swept on the way
+    pub async fn replace(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        device_code_hash: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        sqlx::query!(
+            "DELETE FROM magic_links WHERE (user_id = $1 AND used_at IS NULL) OR expires_at <= NOW()",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete magic links error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO magic_links (user_id, token_hash, device_code_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user_id,
+            token_hash,
+            device_code_hash,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert magic link error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit magic link error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Mark a live link as used and return its user. The device code must be
+    /// the one the link was issued with; a link can only be consumed once.
+    pub async fn consume(&self, token_hash: &str, device_code_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE magic_links
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND device_code_hash = $2 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING user_id
+            "#,
+            token_hash,
+            device_code_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB consume magic link error: {}", e)))?;
+
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,171 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{UserMfa, Result, ApiError};
+
+#[derive(Debug, Clone)]
+pub struct MfaRepository {
+    pool: PgPool,
+}
+
+impl MfaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(&self, user_id: Uuid) -> Result<Option<UserMfa>> {
+        sqlx::query_as!(
+            UserMfa,
+            "SELECT user_id, secret, confirmed_at, last_used_step, created_at FROM user_mfa WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select user MFA error: {}", e)))
+    }
+
+    /// Start (or restart) enrolment with a new secret. An authenticator that is
+    /// already confirmed is left alone and `false` is returned.
+    pub async fn upsert_pending(&self, user_id: Uuid, secret: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_mfa (user_id, secret) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+            WHERE user_mfa.confirmed_at IS NULL
+            "#,
+            user_id,
+            secret
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB upsert user MFA error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Finish enrolment and store the first set of recovery codes
+    pub async fn confirm(&self, user_id: Uuid, step: i64, code_hashes: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let result = sqlx::query!(
+            "UPDATE user_mfa SET confirmed_at = NOW(), last_used_step = $2 WHERE user_id = $1 AND confirmed_at IS NULL",
+            user_id,
+            step
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB confirm user MFA error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::bad_request("No MFA enrolment is pending"));
+        }
+
+        Self::insert_recovery_codes(&mut tx, user_id, code_hashes).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit user MFA error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Remember the step of an accepted code. Returns `false` if that step (or
+    /// a later one) was already used, i.e. the code is being replayed.
+    pub async fn record_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE user_mfa SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+            user_id,
+            step
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB record MFA step error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Use up a recovery code; `false` if it is unknown or already used
+    pub async fn consume_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE mfa_recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+            user_id,
+            code_hash
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB consume recovery code error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn count_unused_recovery_codes(&self, user_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB count recovery codes error: {}", e)))?;
+
+        Ok(count)
+    }
+
+    /// Throw away all recovery codes and store a new set
+    pub async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete recovery codes error: {}", e)))?;
+
+        Self::insert_recovery_codes(&mut tx, user_id, code_hashes).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit recovery codes error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Remove the authenticator and its recovery codes
+    pub async fn delete(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete recovery codes error: {}", e)))?;
+
+        sqlx::query!("DELETE FROM user_mfa WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete user MFA error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit user MFA error: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn insert_recovery_codes(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO mfa_recovery_codes (user_id, code_hash) SELECT $1, unnest($2::text[])",
+            user_id,
+            code_hashes
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert recovery codes error: {}", e)))?;
+
+        Ok(())
+    }
+}
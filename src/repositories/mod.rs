@@ -1,6 +1,29 @@
 // Repository module - data access layer
 pub mod user_repository;
+pub mod role_repository;
+pub mod user_token_repository;
+pub mod mfa_repository;
+pub mod api_key_repository;
+pub mod oidc_repository;
+pub mod session_repository;
+pub mod magic_link_repository;
 pub mod task_repository;
+pub mod template_repository;
+pub mod note_repository;
+pub mod notebook_repository;
+pub mod share_repository;
+mod slug;
 
 pub use user_repository::UserRepository;
-pub use task_repository::{TaskRepository, CreateTaskRequestInternal};
+pub use role_repository::RoleRepository;
+pub use user_token_repository::UserTokenRepository;
+pub use mfa_repository::MfaRepository;
+pub use api_key_repository::ApiKeyRepository;
+pub use oidc_repository::OidcRepository;
+pub use session_repository::SessionRepository;
+pub use magic_link_repository::MagicLinkRepository;
+pub use task_repository::{TaskRepository, CreateTaskRequestInternal, UpdateTaskRequestInternal};
+pub use template_repository::TemplateRepository;
+pub use note_repository::{NoteRepository, CreateNoteRequestInternal, UpdateNoteRequestInternal};
+pub use notebook_repository::NotebookRepository;
+pub use share_repository::{ShareRepository, CreateShareLinkInternal};
@@ -0,0 +1,549 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::config::settings::NotesConfig;
+use crate::domain::{Note, NoteRevision, NoteRevisionSummary, NoteRef, NoteEdge, NoteTaskLink, Result, ApiError};
+use crate::domain::task::{slugify, slugify_words, slug_matches_title};
+use crate::domain::note::NOTE_FALLBACK_SLUG_PREFIX;
+use super::slug::with_unique_slug;
+
+/// Unique constraint on `(user_id, slug)`
+const SLUG_UNIQUE_CONSTRAINT: &str = "notes_user_slug_unique";
+
+#[derive(Debug, Clone)]
+pub struct CreateNoteRequestInternal {
+    pub user_id: Uuid,
+    pub notebook_id: Option<Uuid>,
+    pub title: String,
+    pub body: String,
+    /// `[[link]]` targets found in the body
+    pub links: Vec<String>,
+}
+
+/// Column changes for an update; `None` keeps the current value
+#[derive(Debug, Clone, Default)]
+pub struct UpdateNoteRequestInternal {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    /// `[[link]]` targets of the new body; `None` keeps the stored links
+    pub links: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteRepository {
+    pool: PgPool,
+}
+
+impl NoteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a note under a slug that is unique for its owner, together with
+    /// its first revision and outgoing links
+    pub async fn create(&self, request: CreateNoteRequestInternal) -> Result<Note> {
+        let base_slug = slugify(&request.title, NOTE_FALLBACK_SLUG_PREFIX);
+        let slug_base = slugify_words(&request.title);
+
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let note = with_unique_slug(&mut tx, &base_slug, SLUG_UNIQUE_CONSTRAINT, "insert note", |conn, slug| {
+            let request = request.clone();
+            let slug_base = slug_base.clone();
+            Box::pin(async move {
+                sqlx::query_as!(
+                    Note,
+                    r#"
+                    INSERT INTO notes (user_id, notebook_id, title, body, slug, slug_base)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id, user_id, notebook_id, title, body, slug, created_at, updated_at
+                    "#,
+                    request.user_id,
+                    request.notebook_id,
+                    request.title,
+                    request.body,
+                    slug,
+                    slug_base
+                )
+                .fetch_one(conn)
+                .await
+            })
+        })
+        .await?;
+
+        Self::append_revision(&mut tx, &note, request.user_id).await?;
+        Self::replace_links(&mut tx, &note, &request.links).await?;
+        Self::resolve_dangling_links(&mut tx, &note).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit note insert error: {}", e)))?;
+
+        Ok(note)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Note> {
+        let rec = sqlx::query_as!(
+            Note,
+            r#"
+            SELECT id, user_id, notebook_id, title, body, slug, created_at, updated_at
+            FROM notes
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Note not found: {}", id)))
+    }
+
+    /// Notes of a user filed in any of `notebook_ids`
+    pub async fn find_by_notebooks(&self, user_id: Uuid, notebook_ids: &[Uuid]) -> Result<Vec<Note>> {
+        let recs = sqlx::query_as!(
+            Note,
+            r#"
+            SELECT id, user_id, notebook_id, title, body, slug, created_at, updated_at
+            FROM notes
+            WHERE user_id = $1 AND notebook_id = ANY($2)
+            ORDER BY updated_at DESC
+            "#,
+            user_id,
+            notebook_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select notes by notebook error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// File a note in a notebook (`None` for top level); not a content change, so no revision
+    pub async fn set_notebook(&self, id: Uuid, notebook_id: Option<Uuid>) -> Result<Note> {
+        let rec = sqlx::query_as!(
+            Note,
+            r#"
+            UPDATE notes
+            SET notebook_id = $2
+            WHERE id = $1
+            RETURNING id, user_id, notebook_id, title, body, slug, created_at, updated_at
+            "#,
+            id,
+            notebook_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB move note error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Note not found: {}", id)))
+    }
+
+    pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<Note>> {
+        let recs = sqlx::query_as!(
+            Note,
+            r#"
+            SELECT id, user_id, notebook_id, title, body, slug, created_at, updated_at
+            FROM notes
+            WHERE user_id = $1
+            ORDER BY updated_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select notes by user error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// Apply an update and record it as a new revision, pruning old
+    /// revisions according to `retention`
+    pub async fn update(
+        &self,
+        note: &Note,
+        changes: UpdateNoteRequestInternal,
+        author_id: Uuid,
+        retention: &NotesConfig,
+    ) -> Result<Note> {
+        self.apply_update(note, changes, author_id, Some(retention)).await
+    }
+
+    /// Replace the body after a linked task's checkbox was toggled. Records a
+    /// revision but leaves wiki links alone, and pruning waits for the next
+    /// regular save.
+    pub async fn set_body(&self, note: &Note, body: &str, author_id: Uuid) -> Result<Note> {
+        let changes = UpdateNoteRequestInternal { body: Some(body.to_string()), ..Default::default() };
+        self.apply_update(note, changes, author_id, None).await
+    }
+
+    async fn apply_update(
+        &self,
+        note: &Note,
+        changes: UpdateNoteRequestInternal,
+        author_id: Uuid,
+        retention: Option<&NotesConfig>,
+    ) -> Result<Note> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        if let Some(title) = &changes.title {
+            Self::reslug_for_title(&mut tx, note.id, title).await?;
+        }
+
+        let rec = sqlx::query_as!(
+            Note,
+            r#"
+            UPDATE notes
+            SET title = COALESCE($2, title),
+                body = COALESCE($3, body),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, user_id, notebook_id, title, body, slug, created_at, updated_at
+            "#,
+            note.id,
+            changes.title,
+            changes.body
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update note error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found(format!("Note not found: {}", note.id)))?;
+
+        Self::append_revision(&mut tx, &rec, author_id).await?;
+        if let Some(retention) = retention {
+            Self::prune_revisions(&mut tx, rec.id, retention).await?;
+        }
+
+        if let Some(links) = &changes.links {
+            Self::replace_links(&mut tx, &rec, links).await?;
+        }
+        if rec.title != note.title || rec.slug != note.slug {
+            Self::resolve_dangling_links(&mut tx, &rec).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit note update error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    /// Delete a note together with the public share links pointing at it
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let result = sqlx::query!("DELETE FROM notes WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete note error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Note not found: {}", id)));
+        }
+
+        sqlx::query!("DELETE FROM share_links WHERE resource_type = 'note' AND resource_id = $1", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete note share links error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit note delete error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Revisions of a note, newest first
+    pub async fn find_revisions(&self, note_id: Uuid) -> Result<Vec<NoteRevisionSummary>> {
+        let recs = sqlx::query_as!(
+            NoteRevisionSummary,
+            r#"
+            SELECT revision, title, author_id, created_at
+            FROM note_revisions
+            WHERE note_id = $1
+            ORDER BY revision DESC
+            "#,
+            note_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note revisions error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    pub async fn find_revision(&self, note_id: Uuid, revision: i32) -> Result<NoteRevision> {
+        let rec = sqlx::query_as!(
+            NoteRevision,
+            r#"
+            SELECT id, note_id, revision, title, body, author_id, created_at
+            FROM note_revisions
+            WHERE note_id = $1 AND revision = $2
+            "#,
+            note_id,
+            revision
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note revision error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Revision {} not found for note {}", revision, note_id)))
+    }
+
+    /// Notes of the same owner that link to `note_id`
+    pub async fn find_backlinks(&self, note_id: Uuid) -> Result<Vec<NoteRef>> {
+        let recs = sqlx::query_as!(
+            NoteRef,
+            r#"
+            SELECT DISTINCT n.id, n.title, n.slug
+            FROM note_links l
+            JOIN notes n ON n.id = l.source_note_id
+            WHERE l.target_note_id = $1 AND l.source_note_id <> $1
+            ORDER BY n.title ASC
+            "#,
+            note_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note backlinks error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// Full notes linking to `note_id`, used to rewrite links on rename
+    pub async fn find_linking_notes(&self, note_id: Uuid) -> Result<Vec<Note>> {
+        let recs = sqlx::query_as!(
+            Note,
+            r#"
+            SELECT n.id, n.user_id, n.notebook_id, n.title, n.body, n.slug, n.created_at, n.updated_at
+            FROM notes n
+            WHERE n.id <> $1
+              AND EXISTS (SELECT 1 FROM note_links l WHERE l.source_note_id = n.id AND l.target_note_id = $1)
+            "#,
+            note_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select linking notes error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// All notes of a user and the resolved links between them
+    pub async fn find_graph(&self, user_id: Uuid) -> Result<(Vec<NoteRef>, Vec<NoteEdge>)> {
+        let nodes = sqlx::query_as!(
+            NoteRef,
+            "SELECT id, title, slug FROM notes WHERE user_id = $1 ORDER BY title ASC",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note graph nodes error: {}", e)))?;
+
+        let edges = sqlx::query_as!(
+            NoteEdge,
+            r#"
+            SELECT DISTINCT l.source_note_id AS source, l.target_note_id AS "target!"
+            FROM note_links l
+            JOIN notes n ON n.id = l.source_note_id
+            WHERE n.user_id = $1 AND l.target_note_id IS NOT NULL
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note graph edges error: {}", e)))?;
+
+        Ok((nodes, edges))
+    }
+
+    /// Store the outgoing links of `note`, resolving each target against the
+    /// owner's notes by slug first, then by case-insensitive title
+    async fn replace_links(tx: &mut Transaction<'_, Postgres>, note: &Note, links: &[String]) -> Result<()> {
+        sqlx::query!("DELETE FROM note_links WHERE source_note_id = $1", note.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete note links error: {}", e)))?;
+
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO note_links (source_note_id, target_ref, target_note_id)
+            SELECT $1, r.target_ref, (
+                SELECT n.id FROM notes n
+                WHERE n.user_id = $2 AND (n.slug = r.target_ref OR lower(n.title) = lower(r.target_ref))
+                ORDER BY (n.slug = r.target_ref) DESC, n.created_at ASC
+                LIMIT 1
+            )
+            FROM UNNEST($3::text[]) AS r(target_ref)
+            ON CONFLICT (source_note_id, target_ref) DO NOTHING
+            "#,
+            note.id,
+            note.user_id,
+            links
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert note links error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Point links that were waiting for a note with this title or slug at `note`
+    async fn resolve_dangling_links(tx: &mut Transaction<'_, Postgres>, note: &Note) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE note_links l
+            SET target_note_id = $1
+            FROM notes s
+            WHERE s.id = l.source_note_id
+              AND s.user_id = $2
+              AND l.target_note_id IS NULL
+              AND (l.target_ref = $3 OR lower(l.target_ref) = lower($4))
+            "#,
+            note.id,
+            note.user_id,
+            note.slug,
+            note.title
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB resolve note links error: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_task_links(&self, note_id: Uuid) -> Result<Vec<NoteTaskLink>> {
+        let recs = sqlx::query_as!(
+            NoteTaskLink,
+            r#"
+            SELECT task_id, note_id, item_text, created_at
+            FROM note_task_links
+            WHERE note_id = $1
+            "#,
+            note_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note task links error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// The note item a task was extracted from, if any
+    pub async fn find_task_link_by_task(&self, task_id: Uuid) -> Result<Option<NoteTaskLink>> {
+        let rec = sqlx::query_as!(
+            NoteTaskLink,
+            r#"
+            SELECT task_id, note_id, item_text, created_at
+            FROM note_task_links
+            WHERE task_id = $1
+            "#,
+            task_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select note task link error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    /// Snapshot the current state of `note` as its next revision
+    async fn append_revision(tx: &mut Transaction<'_, Postgres>, note: &Note, author_id: Uuid) -> Result<()> {
+        // The next number is read from MAX(revision); hold the note row until
+        // commit so concurrent saves can't both claim it
+        sqlx::query!("SELECT id FROM notes WHERE id = $1 FOR UPDATE", note.id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB lock note error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO note_revisions (note_id, revision, title, body, author_id)
+            SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4
+            FROM note_revisions
+            WHERE note_id = $1
+            "#,
+            note.id,
+            note.title,
+            note.body,
+            author_id
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert note revision error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Drop revisions that are neither among the newest `revisions_keep_last`
+    /// nor the last revision of a day inside the daily snapshot window
+    async fn prune_revisions(tx: &mut Transaction<'_, Postgres>, note_id: Uuid, retention: &NotesConfig) -> Result<()> {
+        let keep_last = retention.revisions_keep_last.max(1) as i64;
+        let keep_daily_days = retention.revisions_keep_daily_days.min(i32::MAX as u32) as i32;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM note_revisions
+            WHERE note_id = $1
+              AND revision NOT IN (
+                SELECT revision FROM note_revisions
+                WHERE note_id = $1
+                ORDER BY revision DESC
+                LIMIT $2
+              )
+              AND revision NOT IN (
+                SELECT DISTINCT ON ((created_at AT TIME ZONE 'UTC')::date) revision
+                FROM note_revisions
+                WHERE note_id = $1 AND created_at >= NOW() - make_interval(days => $3)
+                ORDER BY (created_at AT TIME ZONE 'UTC')::date, revision DESC
+              )
+            "#,
+            note_id,
+            keep_last,
+            keep_daily_days
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB prune note revisions error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Give a renamed note a slug for `title`, unless the slug's stored base
+    /// still fits the new title
+    async fn reslug_for_title(tx: &mut Transaction<'_, Postgres>, note_id: Uuid, title: &str) -> Result<()> {
+        let slug_base = sqlx::query_scalar!("SELECT slug_base FROM notes WHERE id = $1 FOR UPDATE", note_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select slug base error: {}", e)))?
+            .ok_or_else(|| ApiError::not_found(format!("Note not found: {}", note_id)))?;
+
+        if slug_matches_title(&slug_base, title) {
+            return Ok(());
+        }
+
+        Self::assign_unique_slug(tx, note_id, &slugify(title, NOTE_FALLBACK_SLUG_PREFIX)).await?;
+        sqlx::query!("UPDATE notes SET slug_base = $2 WHERE id = $1", note_id, slugify_words(title))
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB update slug base error: {}", e)))?;
+        Ok(())
+    }
+
+    /// Give an existing note a slug derived from `base_slug`, retrying with a
+    /// random suffix (under a savepoint) while it collides with the owner's notes
+    async fn assign_unique_slug(tx: &mut Transaction<'_, Postgres>, note_id: Uuid, base_slug: &str) -> Result<String> {
+        with_unique_slug(tx, base_slug, SLUG_UNIQUE_CONSTRAINT, "update slug", |conn, slug| {
+            Box::pin(async move {
+                sqlx::query!("UPDATE notes SET slug = $2 WHERE id = $1", note_id, slug)
+                    .execute(conn)
+                    .await
+                    .map(|_| slug)
+            })
+        })
+        .await
+    }
+}
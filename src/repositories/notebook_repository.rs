@@ -0,0 +1,336 @@
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::domain::{Notebook, NotebookWithCounts, Result, ApiError};
+use crate::domain::notebook::MAX_NOTEBOOK_DEPTH;
+
+/// Unique index on owner, parent and lowercased name
+const SIBLING_NAME_UNIQUE_INDEX: &str = "notebooks_sibling_name_unique";
+
+#[derive(Debug, Clone)]
+pub struct NotebookRepository {
+    pool: PgPool,
+}
+
+impl NotebookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a notebook; a parent must leave room below it within
+    /// `MAX_NOTEBOOK_DEPTH`, checked under the owner's tree lock
+    pub async fn create(&self, user_id: Uuid, name: &str, parent_id: Option<Uuid>) -> Result<Notebook> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        if let Some(parent_id) = parent_id {
+            Self::lock_tree(&mut tx, user_id).await?;
+            if Self::select_depth(&mut *tx, parent_id).await? >= MAX_NOTEBOOK_DEPTH {
+                return Err(too_deep());
+            }
+        }
+
+        let rec = sqlx::query_as!(
+            Notebook,
+            r#"
+            INSERT INTO notebooks (user_id, parent_id, name)
+            VALUES ($1, $2, $3)
+            RETURNING id, user_id, parent_id, name, created_at, updated_at
+            "#,
+            user_id,
+            parent_id,
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_write_error(e, "DB insert notebook error"))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit notebook insert error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Notebook> {
+        let rec = sqlx::query_as!(
+            Notebook,
+            r#"
+            SELECT id, user_id, parent_id, name, created_at, updated_at
+            FROM notebooks
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select notebook error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", id)))
+    }
+
+    /// A user's notebooks with direct and subtree note counts; `only` narrows
+    /// the result to a single notebook
+    pub async fn find_with_counts(&self, user_id: Uuid, only: Option<Uuid>) -> Result<Vec<NotebookWithCounts>> {
+        let recs = sqlx::query_as!(
+            NotebookWithCounts,
+            r#"
+            WITH RECURSIVE subtree (root_id, id) AS (
+                SELECT id, id FROM notebooks WHERE user_id = $1
+                UNION ALL
+                SELECT s.root_id, c.id
+                FROM notebooks c
+                JOIN subtree s ON c.parent_id = s.id
+            ),
+            counts AS (
+                SELECT notebook_id, COUNT(*) AS note_count
+                FROM notes
+                WHERE user_id = $1 AND notebook_id IS NOT NULL
+                GROUP BY notebook_id
+            )
+            SELECT
+              nb.id, nb.user_id, nb.parent_id, nb.name,
+              COALESCE((SELECT c.note_count FROM counts c WHERE c.notebook_id = nb.id), 0) AS "note_count!",
+              COALESCE((
+                SELECT SUM(c.note_count) FROM subtree s JOIN counts c ON c.notebook_id = s.id
+                WHERE s.root_id = nb.id
+              ), 0)::BIGINT AS "total_note_count!",
+              nb.created_at, nb.updated_at
+            FROM notebooks nb
+            WHERE nb.user_id = $1 AND ($2::uuid IS NULL OR nb.id = $2)
+            ORDER BY nb.name ASC
+            "#,
+            user_id,
+            only
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select notebooks error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    pub async fn rename(&self, id: Uuid, name: &str) -> Result<Notebook> {
+        let rec = sqlx::query_as!(
+            Notebook,
+            r#"
+            UPDATE notebooks
+            SET name = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, user_id, parent_id, name, created_at, updated_at
+            "#,
+            id,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| map_write_error(e, "DB rename notebook error"))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", id)))
+    }
+
+    /// Re-parent a notebook, keeping the owner's tree acyclic and within
+    /// `MAX_NOTEBOOK_DEPTH`. The checks and the update run in one transaction
+    /// under the owner's tree lock, so concurrent moves can't combine into a
+    /// cycle or an overly deep tree.
+    pub async fn set_parent(&self, notebook: &Notebook, parent_id: Option<Uuid>) -> Result<Notebook> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        if let Some(parent_id) = parent_id {
+            Self::lock_tree(&mut tx, notebook.user_id).await?;
+
+            let subtree = Self::select_subtree(&mut *tx, notebook.id).await?;
+            if subtree.iter().any(|(id, _)| *id == parent_id) {
+                return Err(ApiError::bad_request("A notebook cannot be moved into itself or one of its sub-notebooks"));
+            }
+
+            let height = subtree.iter().map(|(_, level)| *level).max().unwrap_or(1);
+            if Self::select_depth(&mut *tx, parent_id).await? + height > MAX_NOTEBOOK_DEPTH {
+                return Err(too_deep());
+            }
+        }
+
+        let rec = sqlx::query_as!(
+            Notebook,
+            r#"
+            UPDATE notebooks
+            SET parent_id = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, user_id, parent_id, name, created_at, updated_at
+            "#,
+            notebook.id,
+            parent_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| map_write_error(e, "DB move notebook error"))?
+        .ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", notebook.id)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit notebook move error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    /// The notebook and all notebooks below it, with their depth relative to it (itself = 1)
+    pub async fn find_subtree(&self, id: Uuid) -> Result<Vec<(Uuid, i32)>> {
+        Self::select_subtree(&self.pool, id).await
+    }
+
+    /// Serialize changes to one owner's notebook tree until the transaction ends
+    async fn lock_tree(tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtextextended('notebooks:' || $1::text, 0))",
+            user_id.to_string()
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB lock notebook tree error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Depth of a notebook counted from the top level (top level = 1)
+    async fn select_depth<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<i32> {
+        let depth = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE ancestors (id, parent_id) AS (
+                SELECT id, parent_id FROM notebooks WHERE id = $1
+                UNION ALL
+                SELECT nb.id, nb.parent_id
+                FROM notebooks nb
+                JOIN ancestors a ON nb.id = a.parent_id
+            )
+            SELECT COUNT(*)::INT AS "depth!" FROM ancestors
+            "#,
+            id
+        )
+        .fetch_one(executor)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB notebook depth error: {}", e)))?;
+
+        Ok(depth)
+    }
+
+    async fn select_subtree<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> Result<Vec<(Uuid, i32)>> {
+        let recs = sqlx::query!(
+            r#"
+            WITH RECURSIVE subtree (id, level) AS (
+                SELECT id, 1 FROM notebooks WHERE id = $1
+                UNION ALL
+                SELECT c.id, s.level + 1
+                FROM notebooks c
+                JOIN subtree s ON c.parent_id = s.id
+            )
+            SELECT id AS "id!", level AS "level!" FROM subtree
+            "#,
+            id
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select notebook subtree error: {}", e)))?;
+
+        Ok(recs.into_iter().map(|r| (r.id, r.level)).collect())
+    }
+
+    /// Delete a notebook, moving its notes and sub-notebooks up to its parent.
+    /// Runs under the owner's tree lock, and the parent is read again under
+    /// it, so a concurrent move of the notebook can't leave children behind
+    /// under a stale parent.
+    pub async fn delete_rehoming(&self, notebook: &Notebook) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        Self::lock_tree(&mut tx, notebook.user_id).await?;
+
+        let parent_id = sqlx::query_scalar!("SELECT parent_id FROM notebooks WHERE id = $1", notebook.id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select notebook error: {}", e)))?
+            .ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", notebook.id)))?;
+
+        sqlx::query!(
+            "UPDATE notes SET notebook_id = $2 WHERE notebook_id = $1",
+            notebook.id,
+            parent_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB rehome notes error: {}", e)))?;
+
+        sqlx::query!(
+            "UPDATE notebooks SET parent_id = $2, updated_at = NOW() WHERE parent_id = $1",
+            notebook.id,
+            parent_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| map_write_error(e, "DB rehome notebooks error"))?;
+
+        sqlx::query!("DELETE FROM notebooks WHERE id = $1", notebook.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete notebook error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit notebook delete error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Delete a notebook together with every sub-notebook and note inside it,
+    /// and the share links of those notes. Returns the number of deleted notes.
+    pub async fn delete_cascading(&self, id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let deleted_notes = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE subtree (id) AS (
+                SELECT id FROM notebooks WHERE id = $1
+                UNION ALL
+                SELECT c.id FROM notebooks c JOIN subtree s ON c.parent_id = s.id
+            )
+            DELETE FROM notes WHERE notebook_id IN (SELECT id FROM subtree)
+            RETURNING id
+            "#,
+            id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete notebook notes error: {}", e)))?;
+
+        sqlx::query!(
+            "DELETE FROM share_links WHERE resource_type = 'note' AND resource_id = ANY($1)",
+            &deleted_notes
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete note share links error: {}", e)))?;
+
+        // Sub-notebooks go with it through the parent_id foreign key
+        sqlx::query!("DELETE FROM notebooks WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete notebook error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit notebook delete error: {}", e)))?;
+
+        Ok(deleted_notes.len() as u64)
+    }
+}
+
+/// Validation error for a tree that would exceed `MAX_NOTEBOOK_DEPTH`
+fn too_deep() -> ApiError {
+    ApiError::ValidationError(format!("Notebooks cannot be nested more than {} levels deep", MAX_NOTEBOOK_DEPTH))
+}
+
+/// Sibling name clashes become a 409, anything else an internal error
+fn map_write_error(error: sqlx::Error, context: &str) -> ApiError {
+    if let sqlx::Error::Database(db_error) = &error
+        && db_error.constraint() == Some(SIBLING_NAME_UNIQUE_INDEX)
+    {
+        return ApiError::Conflict("A notebook with the same name already exists in that location".to_string());
+    }
+    ApiError::InternalError(format!("{}: {}", context, error))
+}
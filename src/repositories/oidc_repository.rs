@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{OidcAuthRequest, Result, ApiError};
+
+#[derive(Debug, Clone)]
+pub struct OidcRepository {
+    pool: PgPool,
+}
+
+impl OidcRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Remember a sign-in until its callback; stale requests are swept on the way
+    pub async fn create_auth_request(
+        &self,
+        state_hash: &str,
+        request: &OidcAuthRequest,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query!("DELETE FROM oidc_auth_requests WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete OIDC requests error: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO oidc_auth_requests (state_hash, provider, nonce, code_verifier, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            state_hash,
+            request.provider,
+            request.nonce,
+            request.code_verifier,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert OIDC request error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Remove and return a live request for `provider`; each state works once
+    pub async fn take_auth_request(&self, state_hash: &str, provider: &str) -> Result<Option<OidcAuthRequest>> {
+        let rec = sqlx::query_as!(
+            OidcAuthRequest,
+            r#"
+            DELETE FROM oidc_auth_requests
+            WHERE state_hash = $1 AND provider = $2 AND expires_at > NOW()
+            RETURNING provider, nonce, code_verifier
+            "#,
+            state_hash,
+            provider
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB take OIDC request error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    /// The user linked to a provider account, recording the login
+    pub async fn touch_identity(&self, provider: &str, subject: &str, email: Option<&str>) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE user_identities
+            SET last_login_at = NOW(), email = COALESCE($3, email)
+            WHERE provider = $1 AND subject = $2
+            RETURNING user_id
+            "#,
+            provider,
+            subject,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update identity error: {}", e)))?;
+
+        Ok(user_id)
+    }
+
+    pub async fn link_identity(&self, provider: &str, subject: &str, user_id: Uuid, email: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_identities (provider, subject, user_id, email, last_login_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (provider, subject) DO NOTHING
+            "#,
+            provider,
+            subject,
+            user_id,
+            email
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert identity error: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::domain::{Role, Permission, Result, ApiError};
+
+/// Row shape of a role with its permissions aggregated into an array
+struct RoleRow {
+    id: Uuid,
+    name: String,
+    description: String,
+    built_in: bool,
+    require_mfa: bool,
+    permissions: Vec<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<RoleRow> for Role {
+    fn from(row: RoleRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            built_in: row.built_in,
+            require_mfa: row.require_mfa,
+            // Permissions this build does not know about are ignored
+            permissions: row.permissions.iter().filter_map(|p| p.parse().ok()).collect(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoleRepository {
+    pool: PgPool,
+}
+
+impl RoleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<Role>> {
+        let recs = sqlx::query_as!(
+            RoleRow,
+            r#"
+            SELECT r.id, r.name, r.description, r.built_in, r.require_mfa,
+              COALESCE(array_agg(p.permission ORDER BY p.permission) FILTER (WHERE p.permission IS NOT NULL), '{}') AS "permissions!",
+              r.created_at, r.updated_at
+            FROM roles r
+            LEFT JOIN role_permissions p ON p.role_id = r.id
+            GROUP BY r.id
+            ORDER BY r.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select roles error: {}", e)))?;
+
+        Ok(recs.into_iter().map(Role::from).collect())
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Role> {
+        let rec = sqlx::query_as!(
+            RoleRow,
+            r#"
+            SELECT r.id, r.name, r.description, r.built_in, r.require_mfa,
+              COALESCE(array_agg(p.permission ORDER BY p.permission) FILTER (WHERE p.permission IS NOT NULL), '{}') AS "permissions!",
+              r.created_at, r.updated_at
+            FROM roles r
+            LEFT JOIN role_permissions p ON p.role_id = r.id
+            WHERE r.id = $1
+            GROUP BY r.id
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select role error: {}", e)))?;
+
+        rec.map(Role::from).ok_or_else(|| ApiError::not_found(format!("Role not found: {}", id)))
+    }
+
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<Role>> {
+        let rec = sqlx::query_as!(
+            RoleRow,
+            r#"
+            SELECT r.id, r.name, r.description, r.built_in, r.require_mfa,
+              COALESCE(array_agg(p.permission ORDER BY p.permission) FILTER (WHERE p.permission IS NOT NULL), '{}') AS "permissions!",
+              r.created_at, r.updated_at
+            FROM roles r
+            LEFT JOIN role_permissions p ON p.role_id = r.id
+            WHERE r.name = $1
+            GROUP BY r.id
+            "#,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select role by name error: {}", e)))?;
+
+        Ok(rec.map(Role::from))
+    }
+
+    /// Permissions granted by the role a user currently holds
+    pub async fn find_permissions_for_user(&self, user_id: Uuid) -> Result<Vec<Permission>> {
+        let recs = sqlx::query_scalar!(
+            r#"
+            SELECT p.permission
+            FROM users u
+            JOIN role_permissions p ON p.role_id = u.role_id
+            WHERE u.id = $1
+            ORDER BY p.permission
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select user permissions error: {}", e)))?;
+
+        Ok(recs.iter().filter_map(|p| p.parse().ok()).collect())
+    }
+
+    /// Whether the role a user currently holds makes MFA mandatory
+    pub async fn requires_mfa_for_user(&self, user_id: Uuid) -> Result<bool> {
+        let required = sqlx::query_scalar!(
+            "SELECT r.require_mfa FROM users u JOIN roles r ON r.id = u.role_id WHERE u.id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select role MFA requirement error: {}", e)))?;
+
+        Ok(required.unwrap_or(false))
+    }
+
+    pub async fn set_require_mfa(&self, id: Uuid, required: bool) -> Result<Role> {
+        let result = sqlx::query!(
+            "UPDATE roles SET require_mfa = $2, updated_at = NOW() WHERE id = $1",
+            id,
+            required
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update role MFA requirement error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Role not found: {}", id)));
+        }
+        self.find_by_id(id).await
+    }
+
+    pub async fn create(&self, name: &str, description: &str, permissions: &[Permission]) -> Result<Role> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let role_id = sqlx::query_scalar!(
+            "INSERT INTO roles (name, description) VALUES ($1, $2) RETURNING id",
+            name,
+            description
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert role error: {}", e)))?;
+
+        Self::replace_permissions(&mut tx, role_id, permissions).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit role error: {}", e)))?;
+
+        self.find_by_id(role_id).await
+    }
+
+    /// Fails with a conflict, leaving the role unchanged, when the new
+    /// permissions would leave no user able to administer the system
+    pub async fn update(&self, id: Uuid, description: Option<&str>, permissions: Option<&[Permission]>) -> Result<Role> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+        let administrators = Self::lock_administrators(&mut tx).await?;
+
+        let updated = sqlx::query!(
+            "UPDATE roles SET description = COALESCE($2, description), updated_at = NOW() WHERE id = $1",
+            id,
+            description
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update role error: {}", e)))?;
+
+        if updated.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Role not found: {}", id)));
+        }
+
+        if let Some(permissions) = permissions {
+            Self::replace_permissions(&mut tx, id, permissions).await?;
+            Self::ensure_administrator_remains(&mut tx, administrators).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit role error: {}", e)))?;
+
+        self.find_by_id(id).await
+    }
+
+    /// Fails with a conflict when the role is the last one left that lets
+    /// its users administer the system
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+        let administrators = Self::lock_administrators(&mut tx).await?;
+
+        let result = sqlx::query!("DELETE FROM roles WHERE id = $1", id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete role error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Role not found: {}", id)));
+        }
+        Self::ensure_administrator_remains(&mut tx, administrators).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit role error: {}", e)))?;
+        Ok(())
+    }
+
+    /// Number of users currently holding the role
+    pub async fn count_users(&self, id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM users WHERE role_id = $1"#, id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB count role users error: {}", e)))?;
+
+        Ok(count)
+    }
+
+    /// Ids of the users currently holding the role
+    pub async fn find_user_ids(&self, id: Uuid) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar!("SELECT id FROM users WHERE role_id = $1", id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select role users error: {}", e)))
+    }
+
+    /// Serialise changes that could take `system:admin` away from users and
+    /// return how many hold it. Pass the count to `ensure_administrator_remains`
+    /// after making the change.
+    pub(crate) async fn lock_administrators(tx: &mut Transaction<'_, Postgres>) -> Result<i64> {
+        sqlx::query!("SELECT pg_advisory_xact_lock(hashtextextended('roles:system_admin', 0))")
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB lock administrators error: {}", e)))?;
+
+        Self::count_administrators(tx).await
+    }
+
+    /// Conflict when the change took `system:admin` from its last holders, so
+    /// the caller drops the transaction instead of committing it
+    pub(crate) async fn ensure_administrator_remains(tx: &mut Transaction<'_, Postgres>, before: i64) -> Result<()> {
+        if before > 0 && Self::count_administrators(tx).await? == 0 {
+            return Err(ApiError::conflict(format!("At least one user must keep the {} permission", Permission::SystemAdmin)));
+        }
+        Ok(())
+    }
+
+    async fn count_administrators(tx: &mut Transaction<'_, Postgres>) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM users u
+            JOIN role_permissions p ON p.role_id = u.role_id
+            WHERE p.permission = $1
+            "#,
+            Permission::SystemAdmin.as_str()
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB count administrators error: {}", e)))?;
+
+        Ok(count)
+    }
+
+    async fn replace_permissions(tx: &mut Transaction<'_, Postgres>, role_id: Uuid, permissions: &[Permission]) -> Result<()> {
+        sqlx::query!("DELETE FROM role_permissions WHERE role_id = $1", role_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete role permissions error: {}", e)))?;
+
+        let names: Vec<String> = permissions.iter().map(|p| p.as_str().to_string()).collect();
+        sqlx::query!(
+            "INSERT INTO role_permissions (role_id, permission) SELECT $1, unnest($2::text[]) ON CONFLICT DO NOTHING",
+            role_id,
+            &names
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert role permissions error: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{Session, Result, ApiError};
+
+struct SessionRow {
+    id: Uuid,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    last_seen_at: chrono::DateTime<chrono::Utc>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<SessionRow> for Session {
+    fn from(row: SessionRow) -> Self {
+        Self {
+            id: row.id,
+            user_agent: row.user_agent,
+            ip_address: row.ip_address,
+            created_at: row.created_at,
+            last_seen_at: row.last_seen_at,
+            expires_at: row.expires_at,
+            current: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        refresh_token_hash: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Uuid> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO sessions (user_id, user_agent, ip_address, refresh_token_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            user_id,
+            user_agent,
+            ip_address,
+            refresh_token_hash,
+            expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert session error: {}", e)))?;
+
+        Ok(id)
+    }
+
+    /// Swap a live session's refresh token for a new one and extend it.
+    /// Returns the session and its user; unknown, revoked or expired tokens yield `None`.
+    pub async fn rotate_refresh_token(
+        &self,
+        refresh_token_hash: &str,
+        new_refresh_token_hash: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<(Uuid, Uuid)>> {
+        let rec = sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET refresh_token_hash = $2, expires_at = $3, last_seen_at = NOW()
+            WHERE refresh_token_hash = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING id, user_id
+            "#,
+            refresh_token_hash,
+            new_refresh_token_hash,
+            expires_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB rotate refresh token error: {}", e)))?;
+
+        Ok(rec.map(|r| (r.id, r.user_id)))
+    }
+
+    /// Record activity on a session; false once it is revoked, expired or gone
+    pub async fn touch_if_active(&self, id: Uuid) -> Result<bool> {
+        let rec = sqlx::query_scalar!(
+            r#"
+            UPDATE sessions
+            SET last_seen_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING id
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB touch session error: {}", e)))?;
+
+        Ok(rec.is_some())
+    }
+
+    pub async fn find_active_by_user(&self, user_id: Uuid) -> Result<Vec<Session>> {
+        let recs = sqlx::query_as!(
+            SessionRow,
+            r#"
+            SELECT id, user_agent, ip_address, created_at, last_seen_at, expires_at
+            FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY last_seen_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB list sessions error: {}", e)))?;
+
+        Ok(recs.into_iter().map(Session::from).collect())
+    }
+
+    /// Returns false when the user has no such live session
+    pub async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB revoke session error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke every live session of a user, returning their ids
+    pub async fn revoke_all(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            UPDATE sessions SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            RETURNING id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB revoke sessions error: {}", e)))?;
+
+        Ok(ids)
+    }
+}
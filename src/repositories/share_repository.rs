@@ -0,0 +1,132 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{ShareLink, ShareResourceType, Result, ApiError};
+
+#[derive(Debug, Clone)]
+pub struct CreateShareLinkInternal {
+    pub user_id: Uuid,
+    pub resource_type: ShareResourceType,
+    pub resource_id: Uuid,
+    pub token_hash: String,
+    pub token_hint: String,
+    pub password_hash: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShareRepository {
+    pool: PgPool,
+}
+
+impl ShareRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, request: CreateShareLinkInternal) -> Result<ShareLink> {
+        let rec = sqlx::query_as!(
+            ShareLink,
+            r#"
+            INSERT INTO share_links (user_id, resource_type, resource_id, token_hash, token_hint, password_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING
+              id, user_id, resource_type as "resource_type: ShareResourceType", resource_id, token_hint,
+              password_hash, expires_at, access_count, last_accessed_at, created_at
+            "#,
+            request.user_id,
+            request.resource_type as ShareResourceType,
+            request.resource_id,
+            request.token_hash,
+            request.token_hint,
+            request.password_hash,
+            request.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert share link error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<ShareLink> {
+        let rec = sqlx::query_as!(
+            ShareLink,
+            r#"
+            SELECT
+              id, user_id, resource_type as "resource_type: ShareResourceType", resource_id, token_hint,
+              password_hash, expires_at, access_count, last_accessed_at, created_at
+            FROM share_links
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select share link error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Share link not found: {}", id)))
+    }
+
+    pub async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<ShareLink>> {
+        let rec = sqlx::query_as!(
+            ShareLink,
+            r#"
+            SELECT
+              id, user_id, resource_type as "resource_type: ShareResourceType", resource_id, token_hint,
+              password_hash, expires_at, access_count, last_accessed_at, created_at
+            FROM share_links
+            WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select share link by token error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<ShareLink>> {
+        let recs = sqlx::query_as!(
+            ShareLink,
+            r#"
+            SELECT
+              id, user_id, resource_type as "resource_type: ShareResourceType", resource_id, token_hint,
+              password_hash, expires_at, access_count, last_accessed_at, created_at
+            FROM share_links
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select share links by user error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    pub async fn record_access(&self, id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "UPDATE share_links SET access_count = access_count + 1, last_accessed_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update share link access error: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query!("DELETE FROM share_links WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete share link error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Share link not found: {}", id)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use sqlx::{Connection, PgConnection, Postgres, Transaction};
+use crate::domain::{Result, ApiError};
+use crate::domain::task::SLUG_SUFFIX_LENGTH;
+
+/// Bare slug first, then random suffixes; 36^6 suffixes make running out
+/// practically impossible
+const MAX_SLUG_ATTEMPTS: u32 = 5;
+
+/// One attempt at writing a row under a candidate slug
+pub(crate) type SlugAttempt<'c, T> = Pin<Box<dyn Future<Output = std::result::Result<T, sqlx::Error>> + Send + 'c>>;
+
+/// Run `attempt` with candidate slugs for `base_slug` until one does not trip
+/// the per-owner unique `constraint`.
+///
+/// Each attempt runs under its own savepoint of `tx`, so a clash only rolls
+/// back that attempt and the caller's transaction carries on. Other database
+/// errors are reported as `DB <action> error`.
+pub(crate) async fn with_unique_slug<T>(
+    tx: &mut Transaction<'_, Postgres>,
+    base_slug: &str,
+    constraint: &str,
+    action: &str,
+    mut attempt: impl for<'c> FnMut(&'c mut PgConnection, String) -> SlugAttempt<'c, T>,
+) -> Result<T> {
+    for n in 0..MAX_SLUG_ATTEMPTS {
+        let mut savepoint = tx.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB savepoint error: {}", e)))?;
+
+        match attempt(&mut savepoint, candidate_slug(base_slug, n)).await {
+            Ok(rec) => {
+                savepoint.commit().await
+                    .map_err(|e| ApiError::InternalError(format!("DB release savepoint error: {}", e)))?;
+                return Ok(rec);
+            }
+            Err(e) if is_unique_violation(&e, constraint) => {
+                savepoint.rollback().await
+                    .map_err(|e| ApiError::InternalError(format!("DB rollback savepoint error: {}", e)))?;
+            }
+            Err(e) => return Err(ApiError::InternalError(format!("DB {} error: {}", action, e))),
+        }
+    }
+
+    Err(ApiError::InternalError("Unable to generate unique slug".to_string()))
+}
+
+/// First attempt uses the bare slug, later ones add a random suffix
+fn candidate_slug(base_slug: &str, attempt: u32) -> String {
+    if attempt == 0 {
+        base_slug.to_string()
+    } else {
+        format!("{}-{}", base_slug, generate_random_suffix())
+    }
+}
+
+/// Whether a database error is a clash on the given unique constraint
+fn is_unique_violation(error: &sqlx::Error, constraint: &str) -> bool {
+    match error {
+        sqlx::Error::Database(db_error) => db_error.constraint() == Some(constraint),
+        _ => false,
+    }
+}
+
+/// Generate a 6-character base36 string for slug uniqueness
+fn generate_random_suffix() -> String {
+    use rand::Rng;
+
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut rng = rand::thread_rng();
+    (0..SLUG_SUFFIX_LENGTH)
+        .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+        .collect()
+}
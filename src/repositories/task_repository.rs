@@ -1,13 +1,39 @@
-use sqlx::PgPool;
+use sqlx::{types::Json, PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
-use crate::domain::{Task, Result, ApiError, TaskQueryParams, PaginatedResponse, PaginationMeta};
-use crate::domain::task::{slugify, TaskStatus};
+use crate::domain::{Task, TaskTransfer, ChecklistItem, Result, ApiError, TaskQueryParams, PaginatedResponse, PaginationMeta};
+use crate::domain::task::{slugify, slugify_words, slug_matches_title, TASK_FALLBACK_SLUG_PREFIX, TaskPermission, TaskPriority, TaskShare, TaskStatus};
+use super::slug::with_unique_slug;
+
+/// Unique constraint on `(user_id, slug)`
+const SLUG_UNIQUE_CONSTRAINT: &str = "tasks_user_slug_unique";
+
+/// Unique constraint on `note_task_links (note_id, item_text)`
+const NOTE_ITEM_UNIQUE_CONSTRAINT: &str = "note_task_links_note_item_unique";
 
 #[derive(Debug, Clone)]
 pub struct CreateTaskRequestInternal {
     pub title: String,
     pub description: Option<String>,
     pub user_id: Uuid,
+    pub checklist: Vec<ChecklistItem>,
+    pub tags: Vec<String>,
+    pub priority: TaskPriority,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub assignee_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+}
+
+/// Column changes for an update; `None` keeps the current value, and
+/// `Some(None)` clears a nullable column
+#[derive(Debug, Clone, Default)]
+pub struct UpdateTaskRequestInternal {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub priority: Option<TaskPriority>,
+    pub due_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub assignee_id: Option<Option<Uuid>>,
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,83 +46,372 @@ impl TaskRepository {
         Self { pool }
     }
 
+    /// Insert a task under a slug that is unique for its owner.
+    ///
+    /// The insert is attempted optimistically; when it trips the
+    /// `tasks_user_slug_unique` constraint only the savepoint around that
+    /// attempt is rolled back and the insert is retried with a random suffix,
+    /// all inside one transaction.
     pub async fn create(&self, request: CreateTaskRequestInternal) -> Result<Task> {
-        // Generate unique slug
-        let slug = self.generate_unique_slug(&request.title).await?;
-        
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let task = Self::insert(&mut tx, &request).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit task insert error: {}", e)))?;
+
+        Ok(task)
+    }
+
+    /// Create a task for a note's task-list item and link the two in one
+    /// transaction. Returns `None`, creating nothing, when the item already
+    /// has a task, e.g. because another extraction got there first.
+    pub async fn create_for_note_item(
+        &self,
+        request: CreateTaskRequestInternal,
+        status: TaskStatus,
+        note_id: Uuid,
+        item_text: &str,
+    ) -> Result<Option<Task>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let mut task = Self::insert(&mut tx, &request).await?;
+        if !matches!(status, TaskStatus::Todo) {
+            sqlx::query!(
+                "UPDATE tasks SET status = $2 WHERE id = $1",
+                task.id,
+                status.clone() as TaskStatus
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB update task status error: {}", e)))?;
+            task.status = status;
+        }
+
+        let linked = sqlx::query!(
+            "INSERT INTO note_task_links (task_id, note_id, item_text) VALUES ($1, $2, $3)",
+            task.id,
+            note_id,
+            item_text
+        )
+        .execute(&mut *tx)
+        .await;
+
+        match linked {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(db_error)) if db_error.constraint() == Some(NOTE_ITEM_UNIQUE_CONSTRAINT) => {
+                // Dropping the transaction rolls the task back
+                return Ok(None);
+            }
+            Err(e) => return Err(ApiError::InternalError(format!("DB insert note task link error: {}", e))),
+        }
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit task insert error: {}", e)))?;
+
+        Ok(Some(task))
+    }
+
+    /// Copy a task, and its whole subtree when `include_subtasks` is set, in
+    /// one transaction: either every copy is created or none is. `make` turns
+    /// a source task and the id of its copied parent into the insert for its
+    /// copy. Returns the copy of `source` and the copies of its subtasks.
+    pub async fn duplicate<F>(&self, source: &Task, include_subtasks: bool, make: F) -> Result<(Task, Vec<Task>)>
+    where
+        F: Fn(&Task, Option<Uuid>) -> CreateTaskRequestInternal,
+    {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let root = Self::insert(&mut tx, &make(source, source.parent_id)).await?;
+        let mut pending = vec![(source.id, root.id)];
+        let mut subtasks = Vec::new();
+
+        while include_subtasks && let Some((original_parent, copied_parent)) = pending.pop() {
+            for child in Self::select_children(&mut *tx, original_parent).await? {
+                let copy = Self::insert(&mut tx, &make(&child, Some(copied_parent))).await?;
+                pending.push((child.id, copy.id));
+                subtasks.push(copy);
+            }
+        }
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit task copy error: {}", e)))?;
+
+        Ok((root, subtasks))
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Task> {
         let rec = sqlx::query_as!(
             Task,
             r#"
-            INSERT INTO tasks (title, description, slug, status, user_id)
-            VALUES ($1, $2, $3, 'todo', $4)
-            RETURNING 
+            SELECT 
               id, title, description, slug, 
               status as "status: TaskStatus", 
-              user_id, created_at, updated_at
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
+            FROM tasks
+            WHERE id = $1
             "#,
-            request.title,
-            request.description,
-            slug,
-            request.user_id
+            id
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await
-        .map_err(|e| ApiError::InternalError(format!("DB insert task error: {}", e)))?;
+        .map_err(|e| ApiError::InternalError(format!("DB select task error: {}", e)))?;
 
-        Ok(rec)
+        rec.ok_or(ApiError::TaskNotFound { id })
     }
 
-    async fn generate_unique_slug(&self, title: &str) -> Result<String> {
-        let base_slug = slugify(title);
-        let mut slug = base_slug.clone();
-        let mut attempts = 0;
+    /// Apply an update; when the slug changes the previous one is kept as a redirect
+    pub async fn update(&self, task: &Task, changes: UpdateTaskRequestInternal) -> Result<Task> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
 
-        loop {
-            // Check if slug exists
-            let exists = sqlx::query_scalar!(
-                "SELECT EXISTS(SELECT 1 FROM tasks WHERE slug = $1)",
-                slug
+        if let Some(title) = &changes.title {
+            Self::reslug_for_title(&mut tx, task.id, title).await?;
+        }
+
+        let rec = sqlx::query_as!(
+            Task,
+            r#"
+            UPDATE tasks
+            SET title = COALESCE($2, title),
+                description = COALESCE($3, description),
+                status = COALESCE($4, status),
+                priority = COALESCE($5, priority),
+                due_at = CASE WHEN $9 THEN $6 ELSE due_at END,
+                assignee_id = CASE WHEN $10 THEN $7 ELSE assignee_id END,
+                tags = COALESCE($8, tags),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING 
+              id, title, description, slug, 
+              status as "status: TaskStatus", 
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
+            "#,
+            task.id,
+            changes.title,
+            changes.description,
+            changes.status as Option<TaskStatus>,
+            changes.priority as Option<TaskPriority>,
+            changes.due_at.flatten(),
+            changes.assignee_id.flatten(),
+            changes.tags.as_deref(),
+            changes.due_at.is_some(),
+            changes.assignee_id.is_some()
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update task error: {}", e)))?
+        .ok_or(ApiError::TaskNotFound { id: task.id })?;
+
+        if rec.slug != task.slug {
+            sqlx::query!(
+                r#"
+                INSERT INTO task_slug_redirects (user_id, old_slug, task_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, old_slug) DO UPDATE
+                  SET task_id = EXCLUDED.task_id, created_at = NOW()
+                "#,
+                task.user_id,
+                task.slug,
+                task.id
             )
-            .fetch_one(&self.pool)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| ApiError::InternalError(format!("DB check slug error: {}", e)))?;
+            .map_err(|e| ApiError::InternalError(format!("DB insert slug redirect error: {}", e)))?;
 
-            if !exists.unwrap_or(false) {
-                break;
-            }
+            // The new slug is live again, it must not redirect anywhere
+            sqlx::query!(
+                "DELETE FROM task_slug_redirects WHERE user_id = $1 AND old_slug = $2",
+                rec.user_id,
+                rec.slug
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete slug redirect error: {}", e)))?;
+        }
 
-            // Generate new slug with 4-digit random string
-            let random_suffix = generate_random_suffix();
-            slug = format!("{}-{}", base_slug, random_suffix);
-            attempts += 1;
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit task update error: {}", e)))?;
 
-            // Prevent infinite loop (safety check)
-            if attempts > 1000 {
-                return Err(ApiError::InternalError("Unable to generate unique slug".to_string()));
-            }
+        Ok(rec)
+    }
+
+    /// The insert behind `create` and `duplicate`, retried under savepoints of `tx`
+    async fn insert(tx: &mut Transaction<'_, Postgres>, request: &CreateTaskRequestInternal) -> Result<Task> {
+        let base_slug = slugify(&request.title, TASK_FALLBACK_SLUG_PREFIX);
+        let slug_base = slugify_words(&request.title);
+
+        with_unique_slug(tx, &base_slug, SLUG_UNIQUE_CONSTRAINT, "insert task", |conn, slug| {
+            let request = request.clone();
+            let slug_base = slug_base.clone();
+            Box::pin(async move {
+                sqlx::query_as!(
+                    Task,
+                    r#"
+                    INSERT INTO tasks (title, description, slug, slug_base, status, user_id, checklist, tags, priority, due_at, assignee_id, parent_id)
+                    VALUES ($1, $2, $3, $11, 'todo', $4, $5, $6, $7, $8, $9, $10)
+                    RETURNING 
+                      id, title, description, slug, 
+                      status as "status: TaskStatus", 
+                      priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+                      user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+                      created_at, updated_at
+                    "#,
+                    request.title,
+                    request.description,
+                    slug,
+                    request.user_id,
+                    Json(&request.checklist) as _,
+                    &request.tags,
+                    request.priority as TaskPriority,
+                    request.due_at,
+                    request.assignee_id,
+                    request.parent_id,
+                    slug_base
+                )
+                .fetch_one(conn)
+                .await
+            })
+        })
+        .await
+    }
+
+    /// Give a renamed task a slug for `title`, unless the slug's stored base
+    /// still fits the new title
+    async fn reslug_for_title(tx: &mut Transaction<'_, Postgres>, task_id: Uuid, title: &str) -> Result<()> {
+        let slug_base = sqlx::query_scalar!("SELECT slug_base FROM tasks WHERE id = $1 FOR UPDATE", task_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select slug base error: {}", e)))?
+            .ok_or(ApiError::TaskNotFound { id: task_id })?;
+
+        if slug_matches_title(&slug_base, title) {
+            return Ok(());
         }
 
-        Ok(slug)
+        Self::assign_unique_slug(tx, task_id, &slugify(title, TASK_FALLBACK_SLUG_PREFIX), None).await?;
+        sqlx::query!("UPDATE tasks SET slug_base = $2 WHERE id = $1", task_id, slugify_words(title))
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB update slug base error: {}", e)))?;
+        Ok(())
     }
 
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Task> {
+    /// Give an existing task a slug derived from `base_slug`, retrying with a
+    /// random suffix (under a savepoint) while it collides with the owner's
+    /// tasks. A transfer passes `new_owner` to move the task in the same
+    /// statement, so the collision check runs against the new owner's slugs.
+    async fn assign_unique_slug(tx: &mut Transaction<'_, Postgres>, task_id: Uuid, base_slug: &str, new_owner: Option<Uuid>) -> Result<String> {
+        with_unique_slug(tx, base_slug, SLUG_UNIQUE_CONSTRAINT, "update slug", |conn, slug| {
+            Box::pin(async move {
+                sqlx::query!(
+                    "UPDATE tasks SET slug = $2, user_id = COALESCE($3, user_id) WHERE id = $1",
+                    task_id,
+                    slug,
+                    new_owner
+                )
+                .execute(conn)
+                .await
+                .map(|_| slug)
+            })
+        })
+        .await
+    }
+
+    pub async fn find_by_slug(&self, user_id: Uuid, slug: &str) -> Result<Option<Task>> {
         let rec = sqlx::query_as!(
             Task,
             r#"
             SELECT 
               id, title, description, slug, 
               status as "status: TaskStatus", 
-              user_id, created_at, updated_at
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
             FROM tasks
-            WHERE id = $1
+            WHERE user_id = $1 AND slug = $2
             "#,
-            id
+            user_id,
+            slug
         )
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| ApiError::InternalError(format!("DB select task error: {}", e)))?;
+        .map_err(|e| ApiError::InternalError(format!("DB select task by slug error: {}", e)))?;
 
-        rec.ok_or(ApiError::TaskNotFound { id })
+        Ok(rec)
+    }
+
+    /// Task id a retired slug now points to, if any
+    pub async fn find_slug_redirect(&self, user_id: Uuid, slug: &str) -> Result<Option<Uuid>> {
+        let rec = sqlx::query_scalar!(
+            "SELECT task_id FROM task_slug_redirects WHERE user_id = $1 AND old_slug = $2",
+            user_id,
+            slug
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select slug redirect error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    /// Read-modify-write of a task's embedded checklist. The row stays locked
+    /// from the read to the write, so concurrent edits apply one after the
+    /// other instead of overwriting each other. Positions are renumbered to
+    /// match the order `modify` leaves the items in.
+    pub async fn modify_checklist<F>(&self, id: Uuid, modify: F) -> Result<Task>
+    where
+        F: FnOnce(&mut Vec<ChecklistItem>) -> Result<()>,
+    {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let current = sqlx::query_scalar!(
+            r#"SELECT checklist as "checklist: Json<Vec<ChecklistItem>>" FROM tasks WHERE id = $1 FOR UPDATE"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select checklist error: {}", e)))?
+        .ok_or(ApiError::TaskNotFound { id })?;
+
+        let mut checklist = current.0;
+        modify(&mut checklist)?;
+        for (position, item) in checklist.iter_mut().enumerate() {
+            item.position = position as i32;
+        }
+
+        let rec = sqlx::query_as!(
+            Task,
+            r#"
+            UPDATE tasks
+            SET checklist = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING 
+              id, title, description, slug, 
+              status as "status: TaskStatus", 
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
+            "#,
+            id,
+            Json(&checklist) as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update checklist error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit checklist error: {}", e)))?;
+
+        Ok(rec)
     }
 
     pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<Task>> {
@@ -106,7 +421,9 @@ impl TaskRepository {
             SELECT 
               id, title, description, slug, 
               status as "status: TaskStatus", 
-              user_id, created_at, updated_at
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
             FROM tasks
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -120,6 +437,300 @@ impl TaskRepository {
         Ok(recs)
     }
 
+    /// Direct subtasks of a task, oldest first
+    async fn select_children<'e>(executor: impl PgExecutor<'e>, parent_id: Uuid) -> Result<Vec<Task>> {
+        let recs = sqlx::query_as!(
+            Task,
+            r#"
+            SELECT 
+              id, title, description, slug, 
+              status as "status: TaskStatus", 
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
+            FROM tasks
+            WHERE parent_id = $1
+            ORDER BY created_at ASC
+            "#,
+            parent_id
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select subtasks error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// Record (or replace) the pending transfer of a task
+    pub async fn upsert_transfer_request(&self, task_id: Uuid, from_user_id: Uuid, to_user_id: Uuid) -> Result<TaskTransfer> {
+        let rec = sqlx::query_as!(
+            TaskTransfer,
+            r#"
+            INSERT INTO task_transfer_requests (task_id, from_user_id, to_user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (task_id) DO UPDATE
+              SET from_user_id = EXCLUDED.from_user_id,
+                  to_user_id = EXCLUDED.to_user_id,
+                  created_at = NOW()
+            RETURNING task_id, from_user_id, to_user_id, created_at
+            "#,
+            task_id,
+            from_user_id,
+            to_user_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB upsert transfer request error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_transfer_request(&self, task_id: Uuid) -> Result<Option<TaskTransfer>> {
+        let rec = sqlx::query_as!(
+            TaskTransfer,
+            r#"
+            SELECT task_id, from_user_id, to_user_id, created_at
+            FROM task_transfer_requests
+            WHERE task_id = $1
+            "#,
+            task_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select transfer request error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn delete_transfer_request(&self, task_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM task_transfer_requests WHERE task_id = $1", task_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete transfer request error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a task and all of its subtasks to a new owner, clearing any pending
+    /// transfer. Returns the ids of every task that changed owner.
+    pub async fn transfer_owner(&self, task_id: Uuid, new_user_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let moved = Self::move_subtree(&mut tx, task_id, new_user_id).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit transfer error: {}", e)))?;
+
+        Ok(moved)
+    }
+
+    /// Complete the pending transfer of a task to `to_user_id`.
+    ///
+    /// The request is claimed with a conditional `DELETE ... RETURNING`, so of
+    /// two concurrent accepts (or an accept racing a cancel) only one gets it,
+    /// and the task moves only while it still belongs to the requester. Returns
+    /// `None` when no transfer to that user is pending, otherwise the previous
+    /// owner and the ids of every task that changed owner.
+    pub async fn accept_transfer(&self, task_id: Uuid, to_user_id: Uuid) -> Result<Option<(Uuid, Vec<Uuid>)>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let Some(from_user_id) = sqlx::query_scalar!(
+            "DELETE FROM task_transfer_requests WHERE task_id = $1 AND to_user_id = $2 RETURNING from_user_id",
+            task_id,
+            to_user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB claim transfer request error: {}", e)))?
+        else {
+            return Ok(None);
+        };
+
+        let claimed = sqlx::query_scalar!(
+            "UPDATE tasks SET updated_at = NOW() WHERE id = $1 AND user_id = $2 RETURNING id",
+            task_id,
+            from_user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB lock transferred task error: {}", e)))?;
+
+        if claimed.is_none() {
+            // The task changed hands since the request was made; drop the stale request
+            tx.commit().await
+                .map_err(|e| ApiError::InternalError(format!("DB commit transfer error: {}", e)))?;
+            return Err(ApiError::conflict("Transfer request is no longer valid"));
+        }
+
+        let moved = Self::move_subtree(&mut tx, task_id, to_user_id).await?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit transfer error: {}", e)))?;
+
+        Ok(Some((from_user_id, moved)))
+    }
+
+    async fn move_subtree(tx: &mut Transaction<'_, Postgres>, task_id: Uuid, new_user_id: Uuid) -> Result<Vec<Uuid>> {
+        let subtree = sqlx::query!(
+            r#"
+            WITH RECURSIVE subtree AS (
+              SELECT id, slug FROM tasks WHERE id = $1
+              UNION ALL
+              SELECT t.id, t.slug FROM tasks t JOIN subtree s ON t.parent_id = s.id
+            )
+            SELECT id AS "id!", slug AS "slug!" FROM subtree
+            "#,
+            task_id
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select task subtree error: {}", e)))?;
+
+        if subtree.is_empty() {
+            return Err(ApiError::TaskNotFound { id: task_id });
+        }
+
+        // Slugs are per owner: a task keeps its slug unless the new owner
+        // already uses it, in which case it gets a random suffix
+        for task in &subtree {
+            Self::assign_unique_slug(tx, task.id, &task.slug, Some(new_user_id)).await?;
+        }
+        let moved: Vec<Uuid> = subtree.into_iter().map(|task| task.id).collect();
+
+        sqlx::query!("UPDATE tasks SET updated_at = NOW() WHERE id = ANY($1)", &moved)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB transfer task error: {}", e)))?;
+
+        // Old slugs belonged to the previous owner's namespace
+        sqlx::query!("DELETE FROM task_slug_redirects WHERE task_id = ANY($1)", &moved)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete slug redirects error: {}", e)))?;
+
+        // A note item of the previous owner must not drive the new owner's task
+        sqlx::query!("DELETE FROM note_task_links WHERE task_id = ANY($1)", &moved)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete note task links error: {}", e)))?;
+
+        // Public links handed out before the handover stop working with it
+        sqlx::query!(
+            "DELETE FROM share_links WHERE resource_type = 'task' AND resource_id = ANY($1) AND user_id <> $2",
+            &moved,
+            new_user_id
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete task share links error: {}", e)))?;
+
+        // The new owner no longer needs a share on what they now own
+        sqlx::query!("DELETE FROM task_shares WHERE task_id = ANY($1) AND user_id = $2", &moved, new_user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete task shares error: {}", e)))?;
+
+        sqlx::query!("DELETE FROM task_transfer_requests WHERE task_id = $1", task_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete transfer request error: {}", e)))?;
+
+        Ok(moved)
+    }
+
+    /// Grant a user access to a task, replacing any previous permission
+    pub async fn upsert_share(&self, task_id: Uuid, user_id: Uuid, permission: TaskPermission, granted_by: Uuid) -> Result<TaskShare> {
+        let rec = sqlx::query_as!(
+            TaskShare,
+            r#"
+            INSERT INTO task_shares (task_id, user_id, permission, granted_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (task_id, user_id) DO UPDATE
+              SET permission = EXCLUDED.permission,
+                  granted_by = EXCLUDED.granted_by,
+                  updated_at = NOW()
+            RETURNING task_id, user_id, permission as "permission: TaskPermission", granted_by, created_at, updated_at
+            "#,
+            task_id,
+            user_id,
+            permission as TaskPermission,
+            granted_by
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB upsert task share error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_shares(&self, task_id: Uuid) -> Result<Vec<TaskShare>> {
+        let recs = sqlx::query_as!(
+            TaskShare,
+            r#"
+            SELECT task_id, user_id, permission as "permission: TaskPermission", granted_by, created_at, updated_at
+            FROM task_shares
+            WHERE task_id = $1
+            ORDER BY created_at ASC
+            "#,
+            task_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select task shares error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    /// Permission a user was granted on a task, if any
+    pub async fn find_share_permission(&self, task_id: Uuid, user_id: Uuid) -> Result<Option<TaskPermission>> {
+        let rec = sqlx::query_scalar!(
+            r#"SELECT permission as "permission: TaskPermission" FROM task_shares WHERE task_id = $1 AND user_id = $2"#,
+            task_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select task share error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn delete_share(&self, task_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM task_shares WHERE task_id = $1 AND user_id = $2", task_id, user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete task share error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Tasks other users have shared with this user, newest first
+    pub async fn find_shared_with(&self, user_id: Uuid) -> Result<Vec<Task>> {
+        let recs = sqlx::query_as!(
+            Task,
+            r#"
+            SELECT 
+              t.id, t.title, t.description, t.slug, 
+              t.status as "status: TaskStatus", 
+              t.priority as "priority: TaskPriority", t.due_at, t.assignee_id, t.parent_id,
+              t.user_id, t.checklist as "checklist: Json<Vec<ChecklistItem>>", t.tags,
+              t.created_at, t.updated_at
+            FROM tasks t
+            JOIN task_shares s ON s.task_id = t.id
+            WHERE s.user_id = $1
+            ORDER BY t.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select shared tasks error: {}", e)))?;
+
+        Ok(recs)
+    }
+
     pub async fn find_all(&self) -> Result<Vec<Task>> {
         let recs = sqlx::query_as!(
             Task,
@@ -127,7 +738,9 @@ impl TaskRepository {
             SELECT 
               id, title, description, slug, 
               status as "status: TaskStatus", 
-              user_id, created_at, updated_at
+              priority as "priority: TaskPriority", due_at, assignee_id, parent_id,
+              user_id, checklist as "checklist: Json<Vec<ChecklistItem>>", tags,
+              created_at, updated_at
             FROM tasks
             ORDER BY created_at DESC
             "#
@@ -154,7 +767,7 @@ impl TaskRepository {
     pub async fn find_with_pagination(&self, query_params: &TaskQueryParams) -> Result<PaginatedResponse<Task>> {
         // Validate pagination parameters
         query_params.pagination.validate()
-            .map_err(|e| ApiError::bad_request(e))?;
+            .map_err(ApiError::bad_request)?;
 
         // Build count query
         let mut count_query = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM tasks WHERE 1=1");
@@ -189,6 +802,12 @@ impl TaskRepository {
             count_query.push(")");
         }
 
+        if let Some(shared_with) = &query_params.filters.shared_with {
+            count_query.push(" AND id IN (SELECT task_id FROM task_shares WHERE user_id = ");
+            count_query.push_bind(shared_with);
+            count_query.push(")");
+        }
+
         // Execute count query
         let total_count: i64 = count_query
             .build_query_scalar()
@@ -198,7 +817,7 @@ impl TaskRepository {
 
         // Build main query
         let mut query = sqlx::QueryBuilder::new(
-            "SELECT id, title, description, slug, status, user_id, created_at, updated_at FROM tasks WHERE 1=1"
+            "SELECT id, title, description, slug, status, priority, due_at, assignee_id, parent_id, user_id, checklist, tags, created_at, updated_at FROM tasks WHERE 1=1"
         );
         
         // Add same filters to main query
@@ -231,6 +850,12 @@ impl TaskRepository {
             query.push(")");
         }
 
+        if let Some(shared_with) = &query_params.filters.shared_with {
+            query.push(" AND id IN (SELECT task_id FROM task_shares WHERE user_id = ");
+            query.push_bind(shared_with);
+            query.push(")");
+        }
+
         // Add sorting
         let sort_direction = if query_params.pagination.sort_direction.to_lowercase() == "desc" {
             "DESC"
@@ -264,23 +889,3 @@ impl TaskRepository {
         })
     }
 }
-
-/// Generate a 4-digit random string for slug uniqueness
-fn generate_random_suffix() -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    use std::time::{SystemTime, UNIX_EPOCH};
-    
-    // Use current timestamp + random seed for uniqueness
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    
-    let mut hasher = DefaultHasher::new();
-    timestamp.hash(&mut hasher);
-    let hash = hasher.finish();
-    
-    // Convert to 4-digit string (base 36 for shorter strings)
-    format!("{:04x}", (hash % 65536) as u16)
-}
@@ -0,0 +1,83 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{TaskTemplate, CreateTaskTemplateRequest, Result, ApiError};
+
+#[derive(Debug, Clone)]
+pub struct TemplateRepository {
+    pool: PgPool,
+}
+
+impl TemplateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, request: CreateTaskTemplateRequest, user_id: Uuid) -> Result<TaskTemplate> {
+        let rec = sqlx::query_as!(
+            TaskTemplate,
+            r#"
+            INSERT INTO task_templates (user_id, name, title_pattern, description, checklist, default_tags)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, title_pattern, description, checklist, default_tags, created_at, updated_at
+            "#,
+            user_id,
+            request.name,
+            request.title_pattern,
+            request.description,
+            &request.checklist,
+            &request.default_tags
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert template error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<TaskTemplate> {
+        let rec = sqlx::query_as!(
+            TaskTemplate,
+            r#"
+            SELECT id, user_id, name, title_pattern, description, checklist, default_tags, created_at, updated_at
+            FROM task_templates
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select template error: {}", e)))?;
+
+        rec.ok_or_else(|| ApiError::not_found(format!("Template not found: {}", id)))
+    }
+
+    pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<TaskTemplate>> {
+        let recs = sqlx::query_as!(
+            TaskTemplate,
+            r#"
+            SELECT id, user_id, name, title_pattern, description, checklist, default_tags, created_at, updated_at
+            FROM task_templates
+            WHERE user_id = $1
+            ORDER BY name ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select templates by user error: {}", e)))?;
+
+        Ok(recs)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query!("DELETE FROM task_templates WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB delete template error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Template not found: {}", id)));
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 use crate::domain::{User, CreateUserRequest, Result, ApiError};
+use crate::domain::role::DEFAULT_ROLE;
+use super::RoleRepository;
 
 #[derive(Debug, Clone)]
 pub struct UserRepository {
@@ -29,13 +31,18 @@ impl UserRepository {
         let rec = sqlx::query_as!(
             User,
             r#"
-            INSERT INTO users (name, email, password_hash)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, email, role as "role: crate::domain::user::UserRole", created_at
+            WITH inserted AS (
+              INSERT INTO users (name, email, password_hash, role_id)
+              VALUES ($1, $2, $3, (SELECT id FROM roles WHERE name = $4))
+              RETURNING id, name, email, role_id, timezone, email_verified_at, created_at
+            )
+            SELECT i.id, i.name, i.email, r.name AS "role!", i.timezone, i.email_verified_at, i.created_at
+            FROM inserted i JOIN roles r ON r.id = i.role_id
             "#,
             name,
             email,
-            password_hash
+            password_hash,
+            DEFAULT_ROLE
         )
         .fetch_one(&self.pool)
         .await
@@ -47,16 +54,17 @@ impl UserRepository {
     pub async fn find_auth_by_email(&self, email: &str) -> std::result::Result<Option<(User, String)>, sqlx::Error> {
         let rec = sqlx::query!(
             r#"
-            SELECT id, name, email, role as "role: crate::domain::user::UserRole", created_at, password_hash
-            FROM users
-            WHERE email = $1
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at, u.password_hash
+            FROM users u
+            JOIN roles r ON r.id = u.role_id
+            WHERE u.email = $1
             "#,
             email
         )
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(rec.map(|r| (User { id: r.id, name: r.name, email: r.email, role: r.role, created_at: r.created_at }, r.password_hash)))
+        Ok(rec.map(|r| (User { id: r.id, name: r.name, email: r.email, role: r.role, timezone: r.timezone, email_verified_at: r.email_verified_at, created_at: r.created_at }, r.password_hash)))
     }
 
     pub async fn create(&self, request: CreateUserRequest) -> Result<User> {
@@ -76,12 +84,17 @@ impl UserRepository {
         let rec = sqlx::query_as!(
             User,
             r#"
-            INSERT INTO users (name, email, password_hash)
-            VALUES ($1, $2, '')
-            RETURNING id, name, email, role as "role: crate::domain::user::UserRole", created_at
+            WITH inserted AS (
+              INSERT INTO users (name, email, password_hash, role_id)
+              VALUES ($1, $2, '', (SELECT id FROM roles WHERE name = $3))
+              RETURNING id, name, email, role_id, timezone, email_verified_at, created_at
+            )
+            SELECT i.id, i.name, i.email, r.name AS "role!", i.timezone, i.email_verified_at, i.created_at
+            FROM inserted i JOIN roles r ON r.id = i.role_id
             "#,
             request.name,
-            request.email
+            request.email,
+            DEFAULT_ROLE
         )
         .fetch_one(&self.pool)
         .await
@@ -94,9 +107,10 @@ impl UserRepository {
         let rec = sqlx::query_as!(
             User,
             r#"
-            SELECT id, name, email, role as "role: crate::domain::user::UserRole", created_at
-            FROM users
-            WHERE id = $1
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM users u
+            JOIN roles r ON r.id = u.role_id
+            WHERE u.id = $1
             "#,
             id
         )
@@ -107,6 +121,235 @@ impl UserRepository {
         rec.ok_or(ApiError::UserNotFound { id })
     }
 
+    /// Resolve an `@handle` to a user, matching either the full email or its
+    /// local part. Only `user_id` and the people they share tasks with (in
+    /// either direction) are candidates, so handles can't be used to probe
+    /// for accounts.
+    pub async fn find_by_handle(&self, user_id: Uuid, handle: &str) -> Result<Option<User>> {
+        let handle = handle.trim().trim_start_matches('@').to_lowercase();
+        let recs = sqlx::query_as!(
+            User,
+            r#"
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM users u
+            JOIN roles r ON r.id = u.role_id
+            WHERE (lower(u.email) = $1 OR lower(split_part(u.email, '@', 1)) = $1)
+              AND (
+                u.id = $2
+                OR EXISTS (
+                  SELECT 1
+                  FROM task_shares s
+                  JOIN tasks t ON t.id = s.task_id
+                  WHERE (t.user_id = $2 AND s.user_id = u.id)
+                     OR (t.user_id = u.id AND s.user_id = $2)
+                )
+              )
+            LIMIT 2
+            "#,
+            handle,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select user by handle error: {}", e)))?;
+
+        if recs.len() > 1 {
+            return Err(ApiError::bad_request(format!("Ambiguous assignee @{}, use the full email address", handle)));
+        }
+        Ok(recs.into_iter().next())
+    }
+
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        let rec = sqlx::query_as!(
+            User,
+            r#"
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM users u
+            JOIN roles r ON r.id = u.role_id
+            WHERE u.email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB select user by email error: {}", e)))?;
+
+        Ok(rec)
+    }
+
+    pub async fn find_password_hash(&self, id: Uuid) -> Result<String> {
+        let rec = sqlx::query_scalar!("SELECT password_hash FROM users WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select password hash error: {}", e)))?;
+
+        rec.ok_or(ApiError::UserNotFound { id })
+    }
+
+    /// Replace the password hash. Callers also revoke the user's sessions,
+    /// which ends access tokens issued before the change; the bumped
+    /// `find_token_version` ends MFA challenges and tokens without a session.
+    pub async fn update_password_hash(&self, id: Uuid, password_hash: &str) -> Result<()> {
+        let result = sqlx::query!(
+            "UPDATE users SET password_hash = $2, token_version = token_version + 1 WHERE id = $1",
+            id,
+            password_hash
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB update password error: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiError::UserNotFound { id });
+        }
+        Ok(())
+    }
+
+    /// Version every access token for the user must carry to be accepted
+    pub async fn find_token_version(&self, id: Uuid) -> Result<Option<i32>> {
+        sqlx::query_scalar!("SELECT token_version FROM users WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select token version error: {}", e)))
+    }
+
+    pub async fn update_profile(&self, id: Uuid, name: Option<String>, timezone: Option<String>) -> Result<User> {
+        let rec = sqlx::query_as!(
+            User,
+            r#"
+            WITH updated AS (
+              UPDATE users SET name = COALESCE($2, name), timezone = COALESCE($3, timezone)
+              WHERE id = $1
+              RETURNING id, name, email, role_id, timezone, email_verified_at, created_at
+            )
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM updated u JOIN roles r ON r.id = u.role_id
+            "#,
+            id,
+            name,
+            timezone
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update user profile error: {}", e)))?;
+
+        rec.ok_or(ApiError::UserNotFound { id })
+    }
+
+    pub async fn set_pending_email(&self, id: Uuid, email: &str) -> Result<()> {
+        sqlx::query!("UPDATE users SET pending_email = $2 WHERE id = $1", id, email)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB set pending email error: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn find_pending_email(&self, id: Uuid) -> Result<Option<String>> {
+        let rec = sqlx::query_scalar!("SELECT pending_email FROM users WHERE id = $1", id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ApiError::InternalError(format!("DB select pending email error: {}", e)))?;
+
+        Ok(rec.flatten())
+    }
+
+    /// Swap in the confirmed pending address, which counts as verified.
+    /// `email` must still be the pending address; if another account has
+    /// taken it since, this fails with `EmailAlreadyExists`.
+    pub async fn apply_pending_email(&self, id: Uuid, email: &str) -> Result<User> {
+        let rec = sqlx::query_as!(
+            User,
+            r#"
+            WITH updated AS (
+              UPDATE users SET email = pending_email, pending_email = NULL, email_verified_at = NOW()
+              WHERE id = $1 AND pending_email = $2
+              RETURNING id, name, email, role_id, timezone, email_verified_at, created_at
+            )
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM updated u JOIN roles r ON r.id = u.role_id
+            "#,
+            id,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                ApiError::EmailAlreadyExists { email: email.to_string() }
+            }
+            e => ApiError::InternalError(format!("DB apply pending email error: {}", e)),
+        })?;
+
+        rec.ok_or_else(|| ApiError::bad_request("No email change is pending"))
+    }
+
+    /// Record that the user proved they own their email address
+    pub async fn mark_email_verified(&self, id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()) WHERE id = $1",
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB mark email verified error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Verify the email of an account that was still unverified and replace
+    /// its password hash in the same update, bumping `find_token_version`.
+    /// Whoever registered the address before its owner proved it keeps no
+    /// way in. Returns false when the address was already verified.
+    pub async fn claim_unverified_email(&self, id: Uuid, password_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET email_verified_at = NOW(), password_hash = $2, token_version = token_version + 1
+            WHERE id = $1 AND email_verified_at IS NULL
+            "#,
+            id,
+            password_hash
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB claim unverified email error: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a user to another role
+    /// Fails with a conflict when it would take `system:admin` from the last user holding it
+    pub async fn set_role(&self, id: Uuid, role_id: Uuid) -> Result<User> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+        let administrators = RoleRepository::lock_administrators(&mut tx).await?;
+
+        let rec = sqlx::query_as!(
+            User,
+            r#"
+            WITH updated AS (
+              UPDATE users SET role_id = $2 WHERE id = $1
+              RETURNING id, name, email, role_id, timezone, email_verified_at, created_at
+            )
+            SELECT u.id, u.name, u.email, r.name AS "role!", u.timezone, u.email_verified_at, u.created_at
+            FROM updated u JOIN roles r ON r.id = u.role_id
+            "#,
+            id,
+            role_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update user role error: {}", e)))?
+        .ok_or(ApiError::UserNotFound { id })?;
+
+        RoleRepository::ensure_administrator_remains(&mut tx, administrators).await?;
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit user role error: {}", e)))?;
+
+        Ok(rec)
+    }
+
     pub async fn exists(&self, id: Uuid) -> bool {
         let rec: Result<Option<(Uuid,)>> = sqlx::query_as(
             "SELECT id FROM users WHERE id = $1"
@@ -0,0 +1,134 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::{Result, ApiError};
+use crate::domain::user::UserTokenPurpose;
+
+#[derive(Debug, Clone)]
+pub struct UserTokenRepository {
+    pool: PgPool,
+}
+
+impl UserTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new token, replacing any unused token the user has for the same purpose
+    pub async fn replace(
+        &self,
+        user_id: Uuid,
+        purpose: UserTokenPurpose,
+        token_hash: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        sqlx::query!(
+            "DELETE FROM user_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+            user_id,
+            purpose as UserTokenPurpose
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete user tokens error: {}", e)))?;
+
+        sqlx::query!(
+            "INSERT INTO user_tokens (user_id, purpose, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+            user_id,
+            purpose as UserTokenPurpose,
+            token_hash,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB insert user token error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit user token error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Mark a live token as used and return its user. Expired, used or
+    /// unknown tokens yield `None`; a token can only be consumed once.
+    pub async fn consume(&self, purpose: UserTokenPurpose, token_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE user_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING user_id
+            "#,
+            token_hash,
+            purpose as UserTokenPurpose
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB consume user token error: {}", e)))?;
+
+        Ok(user_id)
+    }
+
+    /// Consume a password reset token and set the new password hash in one
+    /// transaction, voiding the user's other reset tokens. Returns the user,
+    /// or `None` when the token is expired, used or unknown.
+    pub async fn reset_password(&self, token_hash: &str, password_hash: &str) -> Result<Option<Uuid>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| ApiError::InternalError(format!("DB begin transaction error: {}", e)))?;
+
+        let Some(user_id) = sqlx::query_scalar!(
+            r#"
+            UPDATE user_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING user_id
+            "#,
+            token_hash,
+            UserTokenPurpose::PasswordReset as UserTokenPurpose
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB consume user token error: {}", e)))?
+        else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            "UPDATE users SET password_hash = $2, token_version = token_version + 1 WHERE id = $1",
+            user_id,
+            password_hash
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB update password error: {}", e)))?;
+
+        sqlx::query!(
+            "DELETE FROM user_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+            user_id,
+            UserTokenPurpose::PasswordReset as UserTokenPurpose
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete user tokens error: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| ApiError::InternalError(format!("DB commit password reset error: {}", e)))?;
+
+        Ok(Some(user_id))
+    }
+
+    /// Drop every unused token the user holds for `purpose`
+    pub async fn delete_unused(&self, user_id: Uuid, purpose: UserTokenPurpose) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM user_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+            user_id,
+            purpose as UserTokenPurpose
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ApiError::InternalError(format!("DB delete user tokens error: {}", e)))?;
+
+        Ok(())
+    }
+}
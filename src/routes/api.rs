@@ -1,29 +1,93 @@
 use axum::Router;
 
-use crate::services::{UserService, TaskService, AuthService};
-use crate::middleware::auth_middleware;
-use crate::config::settings::AuthConfig;
+use crate::services::{UserService, RoleService, TaskService, AuthService, MfaService, ApiKeyService, SessionService, LockoutService, OidcService, MagicLinkService, NoteService, ShareService};
+use crate::middleware::{auth_middleware, api_key_scope_middleware, ApiKeyAccess, AuthState};
 
-use super::{user_routes, task_routes, auth_routes};
+use super::{user_routes, account_routes, mfa_routes, api_key_routes, session_routes, lockout_routes, role_routes, user_role_routes, task_routes, auth_routes, magic_link_routes, oidc_routes, note_routes, notebook_routes, share_routes, public_routes};
+
+/// Everything the v1 routes are built from
+#[derive(Clone)]
+pub struct ApiServices {
+    pub user_service: UserService,
+    pub role_service: RoleService,
+    pub task_service: TaskService,
+    pub note_service: NoteService,
+    pub share_service: ShareService,
+    pub auth_service: AuthService,
+    pub mfa_service: MfaService,
+    pub api_key_service: ApiKeyService,
+    pub session_service: SessionService,
+    pub lockout_service: LockoutService,
+    pub oidc_service: OidcService,
+    pub magic_link_service: MagicLinkService,
+}
+
+pub fn api_v1_routes(services: ApiServices, auth_state: AuthState) -> Router {
+    let ApiServices {
+        user_service,
+        role_service,
+        task_service,
+        note_service,
+        share_service,
+        auth_service,
+        mfa_service,
+        api_key_service,
+        session_service,
+        lockout_service,
+        oidc_service,
+        magic_link_service,
+    } = services;
 
-pub fn api_v1_routes(
-    user_service: UserService,
-    task_service: TaskService,
-    auth_service: AuthService,
-    auth_config: AuthConfig,
-) -> Router {
     Router::new()
         .nest("/api/v1", Router::new()
-            .nest("/auth", auth_routes().with_state(auth_service))
+            .nest("/auth",
+                auth_routes()
+                    .with_state(auth_service.clone())
+                    .merge(magic_link_routes().with_state(magic_link_service))
+            )
+            .nest("/auth/oidc", oidc_routes().with_state(oidc_service))
+            .nest("/public", public_routes().with_state(share_service.clone()))
             .nest("/users", 
                 user_routes()
                     .with_state(user_service)
-                    .layer(axum::middleware::from_fn_with_state(auth_config.clone(), auth_middleware))
+                    .merge(account_routes().with_state(auth_service))
+                    .merge(mfa_routes().with_state(mfa_service))
+                    .merge(api_key_routes().with_state(api_key_service))
+                    .merge(session_routes().with_state(session_service))
+                    .merge(lockout_routes().with_state(lockout_service))
+                    .merge(user_role_routes().with_state(role_service.clone()))
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Profile, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
+            )
+            .nest("/roles",
+                role_routes()
+                    .with_state(role_service)
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Denied, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
             )
             .nest("/tasks", 
                 task_routes()
                     .with_state(task_service)
-                    .layer(axum::middleware::from_fn_with_state(auth_config, auth_middleware))
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Tasks, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
+            )
+            .nest("/notes",
+                note_routes()
+                    .with_state(note_service.clone())
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Notes, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
+            )
+            .nest("/notebooks",
+                notebook_routes()
+                    .with_state(note_service)
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Notes, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
+            )
+            .nest("/shares",
+                share_routes()
+                    .with_state(share_service)
+                    .layer(axum::middleware::from_fn_with_state(ApiKeyAccess::Denied, api_key_scope_middleware))
+                    .layer(axum::middleware::from_fn_with_state(auth_state, auth_middleware))
             )
         )
 }
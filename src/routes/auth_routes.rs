@@ -1,15 +1,41 @@
 use axum::{
-    routing::post,
+    routing::{get, post},
     Router,
 };
 
-use crate::handlers::{register, login};
+use crate::handlers::{
+    register, login, refresh_token, forgot_password, reset_password, verify_email, resend_verification, confirm_email_change,
+    verify_mfa, enroll_mfa, get_oidc_providers, start_oidc_login, finish_oidc_login,
+    request_magic_link, consume_magic_link,
+};
 use crate::services::auth_service::AuthService;
+use crate::services::{MagicLinkService, OidcService};
 
 pub fn auth_routes() -> Router<AuthService> {
     Router::new()
         .route("/register", post(register))
         .route("/login", post(login))
+        .route("/refresh", post(refresh_token))
+        .route("/password/forgot", post(forgot_password))
+        .route("/password/reset", post(reset_password))
+        .route("/verify-email", post(verify_email))
+        .route("/verify-email/resend", post(resend_verification))
+        .route("/email/confirm", post(confirm_email_change))
+        .route("/mfa/verify", post(verify_mfa))
+        .route("/mfa/enroll", post(enroll_mfa))
 }
 
+/// Passwordless sign-in, mounted under `/auth`
+pub fn magic_link_routes() -> Router<MagicLinkService> {
+    Router::new()
+        .route("/magic-link", post(request_magic_link))
+        .route("/magic-link/consume", post(consume_magic_link))
+}
 
+/// Sign-in through OpenID Connect providers, mounted under `/auth/oidc`
+pub fn oidc_routes() -> Router<OidcService> {
+    Router::new()
+        .route("/providers", get(get_oidc_providers))
+        .route("/:provider/authorize", post(start_oidc_login))
+        .route("/:provider/callback", post(finish_oidc_login))
+}
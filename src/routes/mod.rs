@@ -1,12 +1,22 @@
 // Routes module - route definitions
 pub mod api;
 pub mod user_routes;
+pub mod role_routes;
 pub mod task_routes;
 pub mod health_routes;
+pub mod well_known_routes;
 pub mod auth_routes;
+pub mod note_routes;
+pub mod notebook_routes;
+pub mod share_routes;
 
-pub use api::api_v1_routes;
-pub use user_routes::user_routes;
+pub use api::{api_v1_routes, ApiServices};
+pub use user_routes::{user_routes, account_routes, mfa_routes, api_key_routes, session_routes, lockout_routes};
+pub use role_routes::{role_routes, user_role_routes};
 pub use task_routes::task_routes;
 pub use health_routes::health_routes;
-pub use auth_routes::auth_routes;
+pub use well_known_routes::well_known_routes;
+pub use auth_routes::{auth_routes, magic_link_routes, oidc_routes};
+pub use note_routes::note_routes;
+pub use notebook_routes::notebook_routes;
+pub use share_routes::{share_routes, public_routes};
@@ -0,0 +1,25 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::{
+    create_note, get_notes, get_note, update_note, delete_note, move_note, extract_note_tasks,
+    get_note_backlinks, get_note_graph,
+    get_note_revisions, get_note_revision, diff_note_revisions, restore_note_revision,
+};
+use crate::services::NoteService;
+
+pub fn note_routes() -> Router<NoteService> {
+    Router::new()
+        .route("/", post(create_note).get(get_notes))
+        .route("/graph", get(get_note_graph))
+        .route("/:id", get(get_note).patch(update_note).delete(delete_note))
+        .route("/:id/move", post(move_note))
+        .route("/:id/extract-tasks", post(extract_note_tasks))
+        .route("/:id/backlinks", get(get_note_backlinks))
+        .route("/:id/revisions", get(get_note_revisions))
+        .route("/:id/revisions/diff", get(diff_note_revisions))
+        .route("/:id/revisions/:rev", get(get_note_revision))
+        .route("/:id/revisions/:rev/restore", post(restore_note_revision))
+}
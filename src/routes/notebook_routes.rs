@@ -0,0 +1,16 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::{
+    create_notebook, get_notebooks, get_notebook, rename_notebook, move_notebook, delete_notebook,
+};
+use crate::services::NoteService;
+
+pub fn notebook_routes() -> Router<NoteService> {
+    Router::new()
+        .route("/", post(create_notebook).get(get_notebooks))
+        .route("/:id", get(get_notebook).patch(rename_notebook).delete(delete_notebook))
+        .route("/:id/move", post(move_notebook))
+}
@@ -0,0 +1,21 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::{get_roles, get_permissions, create_role, get_role, update_role, delete_role, set_role_mfa, assign_user_role};
+use crate::services::RoleService;
+
+pub fn role_routes() -> Router<RoleService> {
+    Router::new()
+        .route("/", get(get_roles).post(create_role))
+        .route("/permissions", get(get_permissions))
+        .route("/:id", get(get_role).patch(update_role).delete(delete_role))
+        .route("/:id/mfa", put(set_role_mfa))
+}
+
+/// Role assignment, mounted under `/users`
+pub fn user_role_routes() -> Router<RoleService> {
+    Router::new()
+        .route("/:id/role", put(assign_user_role))
+}
@@ -0,0 +1,21 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::handlers::{create_share_link, get_share_links, revoke_share_link, open_public_share};
+use crate::services::ShareService;
+
+/// Owner-side management of share links (authenticated)
+pub fn share_routes() -> Router<ShareService> {
+    Router::new()
+        .route("/", post(create_share_link))
+        .route("/", get(get_share_links))
+        .route("/:id", delete(revoke_share_link))
+}
+
+/// Anonymous access to shared resources (no authentication)
+pub fn public_routes() -> Router<ShareService> {
+    Router::new()
+        .route("/shares/:token", get(open_public_share))
+}
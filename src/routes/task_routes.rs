@@ -1,14 +1,34 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
-use crate::handlers::{create_task, get_task, get_tasks};
+use crate::handlers::{
+    create_task, quick_add_task, get_task, get_task_by_slug, get_tasks, update_task,
+    add_checklist_item, toggle_checklist_item, remove_checklist_item, reorder_checklist,
+    duplicate_task, transfer_task, accept_task_transfer, cancel_task_transfer,
+    share_task, get_task_shares, revoke_task_share,
+    create_template, get_templates, get_template, delete_template, create_task_from_template,
+};
 use crate::services::TaskService;
 
 pub fn task_routes() -> Router<TaskService> {
     Router::new()
         .route("/", post(create_task))
         .route("/", get(get_tasks))
-        .route("/:id", get(get_task))
-}
\ No newline at end of file
+        .route("/quick", post(quick_add_task))
+        .route("/templates", post(create_template).get(get_templates))
+        .route("/templates/:id", get(get_template).delete(delete_template))
+        .route("/from-template/:id", post(create_task_from_template))
+        .route("/by-slug/:slug", get(get_task_by_slug))
+        .route("/:id", get(get_task).patch(update_task))
+        .route("/:id/duplicate", post(duplicate_task))
+        .route("/:id/transfer", post(transfer_task).delete(cancel_task_transfer))
+        .route("/:id/transfer/accept", post(accept_task_transfer))
+        .route("/:id/shares", post(share_task).get(get_task_shares))
+        .route("/:id/shares/:user_id", delete(revoke_task_share))
+        .route("/:id/checklist", post(add_checklist_item))
+        .route("/:id/checklist/order", put(reorder_checklist))
+        .route("/:id/checklist/:item_id", delete(remove_checklist_item))
+        .route("/:id/checklist/:item_id/toggle", post(toggle_checklist_item))
+}
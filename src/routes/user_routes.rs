@@ -1,13 +1,54 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 
-use crate::handlers::{create_user, get_user};
-use crate::services::UserService;
+use crate::handlers::{
+    create_user, get_user, get_me, update_me, change_password, change_email,
+    get_mfa_status, begin_mfa_enrollment, confirm_mfa_enrollment, disable_mfa, regenerate_recovery_codes,
+    get_api_keys, create_api_key, revoke_api_key, get_sessions, revoke_session, get_lockouts, unlock_user,
+};
+use crate::services::{ApiKeyService, AuthService, LockoutService, MfaService, SessionService, UserService};
 
 pub fn user_routes() -> Router<UserService> {
     Router::new()
         .route("/", post(create_user))
+        .route("/me", get(get_me).patch(update_me))
         .route("/:id", get(get_user))
 }
+
+/// The current user's credentials, mounted under `/users`
+pub fn account_routes() -> Router<AuthService> {
+    Router::new()
+        .route("/me/password", post(change_password))
+        .route("/me/email", post(change_email))
+}
+
+/// The current user's authenticator, mounted under `/users`
+pub fn mfa_routes() -> Router<MfaService> {
+    Router::new()
+        .route("/me/mfa", get(get_mfa_status).post(begin_mfa_enrollment).delete(disable_mfa))
+        .route("/me/mfa/confirm", post(confirm_mfa_enrollment))
+        .route("/me/mfa/recovery-codes", post(regenerate_recovery_codes))
+}
+
+/// The current user's API keys, mounted under `/users`
+pub fn api_key_routes() -> Router<ApiKeyService> {
+    Router::new()
+        .route("/me/api-keys", get(get_api_keys).post(create_api_key))
+        .route("/me/api-keys/:id", delete(revoke_api_key))
+}
+
+/// The current user's logins, mounted under `/users`
+pub fn session_routes() -> Router<SessionService> {
+    Router::new()
+        .route("/me/sessions", get(get_sessions))
+        .route("/me/sessions/:id", delete(revoke_session))
+}
+
+/// Admin view of failed-login lockouts, mounted under `/users`
+pub fn lockout_routes() -> Router<LockoutService> {
+    Router::new()
+        .route("/lockouts", get(get_lockouts))
+        .route("/:id/unlock", post(unlock_user))
+}
@@ -0,0 +1,12 @@
+use axum::{
+    routing::get,
+    Router,
+};
+
+use crate::handlers::jwks;
+use crate::security::JwtKeys;
+
+pub fn well_known_routes() -> Router<JwtKeys> {
+    Router::new()
+        .route("/.well-known/jwks.json", get(jwks))
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{decode, decode_header, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use simple_asn1::{oid, ASN1Block};
+
+use crate::config::settings::AuthConfig;
+use crate::domain::{ApiError, Result};
+
+/// The keys tokens are signed and verified with. Either a shared HS256 secret,
+/// or an RS256/EdDSA private key plus any number of extra public keys that
+/// are still accepted, so a key can be rotated without logging everyone out.
+/// Asymmetric tokens carry the RFC 7638 thumbprint of their key as `kid`.
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    kid: Option<String>,
+    encoding_key: EncodingKey,
+    /// By `kid`; the HS256 secret is stored under the empty string
+    decoding_keys: HashMap<String, (Algorithm, DecodingKey)>,
+    jwks: JwkSet,
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeys")
+            .field("algorithm", &self.algorithm)
+            .field("kid", &self.kid)
+            .field("verification_kids", &self.decoding_keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl JwtKeys {
+    /// Asymmetric keys when `jwt_private_key_path` is set, the shared secret otherwise
+    pub fn from_config(cfg: &AuthConfig) -> Result<Self> {
+        match &cfg.jwt_private_key_path {
+            Some(path) => Self::from_pem_files(path, &cfg.jwt_verification_key_paths),
+            None => Ok(Self::hs256(&cfg.jwt_secret)),
+        }
+    }
+
+    pub fn hs256(secret: &str) -> Self {
+        let decoding_keys = HashMap::from([(
+            String::new(),
+            (Algorithm::HS256, DecodingKey::from_secret(secret.as_bytes())),
+        )]);
+        Self {
+            algorithm: Algorithm::HS256,
+            kid: None,
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_keys,
+            // The secret is never published
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    /// Sign with the private key in `signing_path`; also accept tokens signed
+    /// by the keys in `verification_paths`, which may be public or private PEMs
+    pub fn from_pem_files(signing_path: &str, verification_paths: &[String]) -> Result<Self> {
+        let signing_pem = read_pem_file(signing_path)?;
+        if !pem::parse(&signing_pem).is_ok_and(|pem| pem.tag().ends_with("PRIVATE KEY")) {
+            return Err(key_error(signing_path, "signing needs a private key"));
+        }
+        let signing_key = PublicKey::from_pem(&signing_pem, signing_path)?;
+        let algorithm = signing_key.algorithm();
+        let encoding_key = match algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(&signing_pem),
+            _ => EncodingKey::from_rsa_pem(&signing_pem),
+        }
+        .map_err(|e| key_error(signing_path, e))?;
+
+        let mut keys = vec![signing_key];
+        for path in verification_paths {
+            keys.push(PublicKey::from_pem(&read_pem_file(path)?, path)?);
+        }
+
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+        for key in keys {
+            let jwk = key.to_jwk();
+            let kid = jwk.common.key_id.clone().unwrap_or_default();
+            if decoding_keys.contains_key(&kid) {
+                continue;
+            }
+            let decoding_key = DecodingKey::from_jwk(&jwk).map_err(|e| key_error(&kid, e))?;
+            decoding_keys.insert(kid, (key.algorithm(), decoding_key));
+            jwks.keys.push(jwk);
+        }
+
+        Ok(Self {
+            algorithm,
+            kid: jwks.keys.first().and_then(|jwk| jwk.common.key_id.clone()),
+            encoding_key,
+            decoding_keys,
+            jwks,
+        })
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.kid.clone();
+        encode(&header, claims, &self.encoding_key)
+            .map_err(|e| ApiError::internal_error(format!("JWT encoding failed: {}", e)))
+    }
+
+    /// Verify with the key named by the token's `kid`. `validation` supplies
+    /// issuer, audience and the like; the algorithm always comes from the key.
+    pub fn decode<T: DeserializeOwned>(&self, token: &str, validation: &Validation) -> jsonwebtoken::errors::Result<T> {
+        let kid = decode_header(token)?.kid.unwrap_or_default();
+        let (algorithm, key) = self.decoding_keys
+            .get(&kid)
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidSignature))?;
+
+        let mut validation = validation.clone();
+        validation.algorithms = vec![*algorithm];
+        Ok(decode::<T>(token, key, &validation)?.claims)
+    }
+
+    /// Public half of every key tokens are accepted from; empty for HS256
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+/// The public half of a signing key, as much as a JWK needs
+enum PublicKey {
+    Rsa { n: Vec<u8>, e: Vec<u8> },
+    Ed25519 { x: Vec<u8> },
+}
+
+impl PublicKey {
+    /// PKCS#8 and PKCS#1 private keys, SubjectPublicKeyInfo and PKCS#1 public keys
+    fn from_pem(pem_bytes: &[u8], path: &str) -> Result<Self> {
+        let pem = pem::parse(pem_bytes).map_err(|e| key_error(path, e))?;
+        let der = pem.contents();
+
+        let key = match pem.tag() {
+            "PRIVATE KEY" => match Ed25519KeyPair::from_pkcs8_maybe_unchecked(der) {
+                Ok(pair) => Some(PublicKey::Ed25519 { x: pair.public_key().as_ref().to_vec() }),
+                Err(_) => RsaKeyPair::from_pkcs8(der).ok().map(|pair| Self::from_rsa_pair(&pair)),
+            },
+            "RSA PRIVATE KEY" => RsaKeyPair::from_der(der).ok().map(|pair| Self::from_rsa_pair(&pair)),
+            "PUBLIC KEY" => Self::from_spki(der),
+            "RSA PUBLIC KEY" => Self::from_rsa_public_der(der),
+            _ => None,
+        };
+
+        key.ok_or_else(|| key_error(path, format!("unsupported {} (expected an RSA or Ed25519 key)", pem.tag())))
+    }
+
+    fn from_rsa_pair(pair: &RsaKeyPair) -> Self {
+        let components = ring::rsa::PublicKeyComponents::<Vec<u8>>::from(pair.public());
+        PublicKey::Rsa { n: components.n, e: components.e }
+    }
+
+    fn from_spki(der: &[u8]) -> Option<Self> {
+        let blocks = simple_asn1::from_der(der).ok()?;
+        let [ASN1Block::Sequence(_, spki)] = blocks.as_slice() else {
+            return None;
+        };
+        let [ASN1Block::Sequence(_, algorithm), ASN1Block::BitString(_, _, key)] = spki.as_slice() else {
+            return None;
+        };
+        let Some(ASN1Block::ObjectIdentifier(_, oid)) = algorithm.first() else {
+            return None;
+        };
+
+        if *oid == oid!(1, 3, 101, 112) {
+            Some(PublicKey::Ed25519 { x: key.clone() })
+        } else if *oid == oid!(1, 2, 840, 113549, 1, 1, 1) {
+            Self::from_rsa_public_der(key)
+        } else {
+            None
+        }
+    }
+
+    fn from_rsa_public_der(der: &[u8]) -> Option<Self> {
+        let blocks = simple_asn1::from_der(der).ok()?;
+        let [ASN1Block::Sequence(_, fields)] = blocks.as_slice() else {
+            return None;
+        };
+        let [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] = fields.as_slice() else {
+            return None;
+        };
+        Some(PublicKey::Rsa { n: n.to_bytes_be().1, e: e.to_bytes_be().1 })
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        match self {
+            PublicKey::Rsa { .. } => Algorithm::RS256,
+            PublicKey::Ed25519 { .. } => Algorithm::EdDSA,
+        }
+    }
+
+    /// RFC 7638: SHA-256 over the required members, in lexicographic order
+    fn thumbprint(&self) -> String {
+        let members = match self {
+            PublicKey::Rsa { n, e } => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                URL_SAFE_NO_PAD.encode(e),
+                URL_SAFE_NO_PAD.encode(n),
+            ),
+            PublicKey::Ed25519 { x } => format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#, URL_SAFE_NO_PAD.encode(x)),
+        };
+        URL_SAFE_NO_PAD.encode(Sha256::digest(members.as_bytes()))
+    }
+
+    fn to_jwk(&self) -> Jwk {
+        let (key_algorithm, algorithm) = match self {
+            PublicKey::Rsa { n, e } => (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n),
+                    e: URL_SAFE_NO_PAD.encode(e),
+                }),
+            ),
+            PublicKey::Ed25519 { x } => (
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(x),
+                }),
+            ),
+        };
+
+        Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(self.thumbprint()),
+                ..Default::default()
+            },
+            algorithm,
+        }
+    }
+}
+
+fn read_pem_file(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| key_error(path, e))
+}
+
+fn key_error(source: &str, e: impl fmt::Display) -> ApiError {
+    ApiError::InternalError(format!("JWT key {}: {}", source, e))
+}
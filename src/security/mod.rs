@@ -0,0 +1,10 @@
+// Security module - shared helpers for secrets handed out to clients
+pub mod jwt_keys;
+pub mod passwords;
+pub mod tokens;
+pub mod totp;
+
+pub use jwt_keys::JwtKeys;
+pub use passwords::{hash_password, verify_password};
+pub use tokens::{generate_token, hash_token};
+pub use totp::{generate_totp_secret, totp_uri, verify_totp};
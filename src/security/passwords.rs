@@ -0,0 +1,20 @@
+use argon2::{password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString}, Argon2};
+use rand::rngs::OsRng;
+
+use crate::domain::{ApiError, Result};
+
+/// Argon2id hash with a fresh salt, in PHC string format
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| ApiError::internal_error(format!("Password hashing failed: {}", e)))?;
+    Ok(hash.to_string())
+}
+
+/// Whether `password` matches a hash produced by `hash_password`
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<bool> {
+    let parsed = PasswordHash::new(stored_hash)
+        .map_err(|_| ApiError::internal_error("Corrupt password hash"))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
@@ -0,0 +1,18 @@
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Random bytes behind every token; 256 bits
+const TOKEN_BYTES: usize = 32;
+
+/// Random URL-safe token (hex-encoded)
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Tokens are only stored as their SHA-256; they carry enough entropy that
+/// a slow hash is not needed
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
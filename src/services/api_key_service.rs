@@ -0,0 +1,73 @@
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::{ApiKey, CreateApiKeyRequest, CreatedApiKey, Result, ApiError};
+use crate::domain::api_key::API_KEY_PREFIX;
+use crate::repositories::ApiKeyRepository;
+use crate::security::{generate_token, hash_token};
+
+const MAX_KEYS_PER_USER: i64 = 25;
+const MAX_NAME_LENGTH: usize = 100;
+/// Characters after the prefix kept as the key's hint
+const KEY_HINT_LENGTH: usize = 6;
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyService {
+    api_key_repository: ApiKeyRepository,
+}
+
+impl ApiKeyService {
+    pub fn new(api_key_repository: ApiKeyRepository) -> Self {
+        Self { api_key_repository }
+    }
+
+    pub async fn get_api_keys(&self, user_id: Uuid) -> Result<Vec<ApiKey>> {
+        self.api_key_repository.find_by_user(user_id).await
+    }
+
+    pub async fn create_api_key(&self, user_id: Uuid, request: CreateApiKeyRequest) -> Result<CreatedApiKey> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(ApiError::ValidationError("Name cannot be empty".to_string()));
+        }
+        if name.len() > MAX_NAME_LENGTH {
+            return Err(ApiError::ValidationError(format!("Name cannot exceed {} characters", MAX_NAME_LENGTH)));
+        }
+        if request.scopes.is_empty() {
+            return Err(ApiError::ValidationError("An API key needs at least one scope".to_string()));
+        }
+        if let Some(expires_at) = request.expires_at
+            && expires_at <= chrono::Utc::now()
+        {
+            return Err(ApiError::ValidationError("Expiry must be in the future".to_string()));
+        }
+
+        if self.api_key_repository.count_by_user(user_id).await? >= MAX_KEYS_PER_USER {
+            return Err(ApiError::conflict(format!("A user can have at most {} API keys", MAX_KEYS_PER_USER)));
+        }
+
+        let mut scopes = request.scopes;
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+
+        let secret = generate_token();
+        let key = format!("{}{}", API_KEY_PREFIX, secret);
+        let key_hint = format!("{}{}", API_KEY_PREFIX, &secret[..KEY_HINT_LENGTH]);
+
+        let api_key = self.api_key_repository
+            .create(user_id, name, &key_hint, &hash_token(&key), &scopes, request.expires_at)
+            .await?;
+
+        info!("API key {} created for user {} with scopes {:?}", api_key.id, user_id, api_key.scopes);
+        Ok(CreatedApiKey { api_key, key })
+    }
+
+    pub async fn revoke_api_key(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        if !self.api_key_repository.delete(user_id, id).await? {
+            return Err(ApiError::not_found(format!("API key not found: {}", id)));
+        }
+
+        info!("API key {} of user {} revoked", id, user_id);
+        Ok(())
+    }
+}
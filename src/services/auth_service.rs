@@ -1,11 +1,19 @@
-use crate::config::settings::AuthConfig;
-use crate::domain::{ApiError, Result, User};
-use crate::repositories::UserRepository;
+use std::sync::Arc;
+
+use crate::cache::{verification_resends_key, RedisCache};
+use crate::config::settings::{AuthConfig, EmailVerificationGate};
+use uuid::Uuid;
+
+use crate::domain::{ApiError, ClientInfo, MfaEnrollment, Result, User};
+use crate::domain::user::UserTokenPurpose;
+use crate::mail::{EmailMessage, Mailer};
+use crate::repositories::{RoleRepository, UserRepository, UserTokenRepository};
+use crate::services::{LockoutService, MfaService, SessionService};
+use crate::security::{generate_token, hash_password, hash_token, verify_password, JwtKeys};
 use crate::validation::Validator;
-use argon2::{password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString}, Argon2};
-use jsonwebtoken::{encode, EncodingKey, Header};
-use rand::rngs::OsRng;
+use jsonwebtoken::Validation;
 use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterRequest {
@@ -20,9 +28,94 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEmailRequest {
+    pub new_email: String,
+    pub current_password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmEmailChangeRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaVerifyRequest {
+    pub mfa_token: String,
+    /// TOTP code, or a recovery code
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaEnrollRequest {
+    pub mfa_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
     pub token: String,
+    /// Exchanged for a new access token at `/auth/refresh`; each use replaces it
+    pub refresh_token: String,
+}
+
+/// `login` either signs the user in or asks for a second factor
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Token(TokenResponse),
+    MfaRequired(MfaChallenge),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaChallenge {
+    pub mfa_required: bool,
+    /// Exchanged for an access token at `/auth/mfa/verify`
+    pub mfa_token: String,
+    /// The user's role requires MFA and they haven't set it up yet;
+    /// `/auth/mfa/enroll` hands out the secret
+    pub enrollment_required: bool,
+}
+
+/// Response of `/auth/mfa/verify`
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaVerifyResponse {
+    pub token: String,
+    pub refresh_token: String,
+    /// Only when the verified code finished enrolment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,79 +123,579 @@ pub struct Claims {
     pub sub: String,        // user id
     pub email: String,
     pub role: String,       // user role
+    #[serde(default)]
+    pub permissions: Vec<String>, // granted by the role when the token was issued
     pub iss: String,
     pub aud: String,
     pub exp: usize,
+    #[serde(default)]
+    pub ver: i32,           // users.token_version when the token was issued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>, // session the token belongs to
+}
+
+/// Proof that the password step of a login succeeded. Signed with the same
+/// key as access tokens but for a different audience, so it is useless as one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MfaChallengeClaims {
+    sub: String,
+    iss: String,
+    aud: String,
+    exp: usize,
+    ver: i32,
+}
+
+/// How long a user has to enter their code after the password step
+const MFA_CHALLENGE_MINUTES: i64 = 5;
+
+/// Everything `AuthService` reads and writes in the database
+#[derive(Debug, Clone)]
+pub struct AuthRepositories {
+    pub users: UserRepository,
+    pub roles: RoleRepository,
+    pub tokens: UserTokenRepository,
+}
+
+/// The services `AuthService` hands parts of a login to
+#[derive(Debug, Clone)]
+pub struct AuthServices {
+    pub mfa: MfaService,
+    pub lockout: LockoutService,
+    pub sessions: SessionService,
 }
 
 #[derive(Clone)]
 pub struct AuthService {
     user_repository: UserRepository,
-    jwt_key: EncodingKey,
+    role_repository: RoleRepository,
+    token_repository: UserTokenRepository,
+    mfa_service: MfaService,
+    lockout_service: LockoutService,
+    session_service: SessionService,
+    /// Counts verification resends per address; without it they are not limited
+    cache: Option<RedisCache>,
+    mailer: Arc<dyn Mailer>,
+    jwt_keys: JwtKeys,
     cfg: AuthConfig,
+    /// Base URL of the web app, for links in emails
+    app_base_url: String,
 }
 
 impl AuthService {
-    pub fn new(user_repository: UserRepository, cfg: AuthConfig) -> Self {
-        let jwt_key = EncodingKey::from_secret(cfg.jwt_secret.as_bytes());
-        Self { user_repository, jwt_key, cfg }
+    pub fn new(
+        repositories: AuthRepositories,
+        services: AuthServices,
+        cache: Option<RedisCache>,
+        mailer: Arc<dyn Mailer>,
+        jwt_keys: JwtKeys,
+        cfg: AuthConfig,
+        app_base_url: String,
+    ) -> Self {
+        Self {
+            user_repository: repositories.users,
+            role_repository: repositories.roles,
+            token_repository: repositories.tokens,
+            mfa_service: services.mfa,
+            lockout_service: services.lockout,
+            session_service: services.sessions,
+            cache,
+            mailer,
+            jwt_keys,
+            cfg,
+            app_base_url,
+        }
     }
 
     pub async fn register(&self, req: RegisterRequest) -> Result<User> {
         // Validate all input fields
         Validator::validate_register_request(&req)?;
 
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(req.password.as_bytes(), &salt)
-            .map_err(|e| ApiError::internal_error(format!("Password hashing failed: {}", e)))?
-            .to_string();
+        let password_hash = hash_password(&req.password)?;
 
         let user = self.user_repository
             .create_with_password_hash(req.name.trim().to_string(), req.email.trim().to_lowercase(), password_hash)
             .await?;
+
+        let service = self.clone();
+        let new_user = user.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_email_verification(&new_user).await {
+                warn!("Verification email for user {} failed: {}", new_user.id, e);
+            }
+        });
+
         Ok(user)
     }
 
-    pub async fn login(&self, req: LoginRequest) -> Result<TokenResponse> {
+    /// Password step of a login. Failures count towards a lockout of the
+    /// email and of the client address; while either is locked the
+    /// password is not even checked.
+    pub async fn login(&self, req: LoginRequest, client: &ClientInfo) -> Result<LoginResponse> {
         // Validate input fields
         Validator::validate_login_request(&req)?;
 
+        let email = req.email.trim().to_lowercase();
+        self.lockout_service.check(&email, client.ip).await?;
+
         let auth = self.user_repository
-            .find_auth_by_email(&req.email.trim().to_lowercase())
+            .find_auth_by_email(&email)
             .await
             .map_err(|e| ApiError::internal_error(format!("Login lookup failed: {}", e)))?;
 
-        let (user, stored_hash) = auth.ok_or_else(|| ApiError::validation_error("Invalid email or password"))?;
+        let user = match auth {
+            Some((user, stored_hash)) if verify_password(&req.password, &stored_hash)? => user,
+            _ => {
+                self.lockout_service.record_failure(&email, client.ip).await;
+                return Err(ApiError::validation_error("Invalid email or password"));
+            }
+        };
+
+        let response = self.sign_in(&user, client).await?;
+        if let LoginResponse::Token(_) = response {
+            self.lockout_service.record_success(&email).await;
+        }
+        Ok(response)
+    }
+
+    /// Everything after the first factor, for a user who has proven who they
+    /// are (password, identity provider, ...): the verified-email gate, then
+    /// either a token or the MFA challenge. A token starts a session.
+    pub async fn sign_in(&self, user: &User, client: &ClientInfo) -> Result<LoginResponse> {
+        if self.cfg.email_verification == EmailVerificationGate::Login && !user.is_email_verified() {
+            return Err(ApiError::forbidden("Verify your email address before logging in"));
+        }
+
+        let mfa = self.mfa_service.state(user.id).await?;
+        if mfa.enabled || mfa.required {
+            debug!("Login for user {} waits for a second factor", user.id);
+            return Ok(LoginResponse::MfaRequired(MfaChallenge {
+                mfa_required: true,
+                mfa_token: self.issue_mfa_challenge(user).await?,
+                enrollment_required: !mfa.enabled,
+            }));
+        }
+
+        Ok(LoginResponse::Token(self.issue_token(user, client).await?))
+    }
+
+    /// Second step of a login. For a user still enrolling, a valid code also
+    /// enables MFA and the recovery codes come back with the token. Wrong
+    /// codes count towards the same lockout as wrong passwords.
+    pub async fn verify_mfa(&self, req: MfaVerifyRequest, client: &ClientInfo) -> Result<MfaVerifyResponse> {
+        let user = self.check_mfa_challenge(&req.mfa_token).await?;
+        self.lockout_service.check(&user.email, client.ip).await?;
+
+        let recovery_codes = if self.mfa_service.state(user.id).await?.enabled {
+            if !self.mfa_service.verify(user.id, &req.code).await? {
+                warn!("Invalid MFA code for user {}", user.id);
+                self.lockout_service.record_failure(&user.email, client.ip).await;
+                return Err(ApiError::Unauthorized("Invalid authentication code".to_string()));
+            }
+            None
+        } else {
+            Some(self.mfa_service.confirm_enrollment(user.id, &req.code).await?.recovery_codes)
+        };
+
+        self.lockout_service.record_success(&user.email).await;
+        let TokenResponse { token, refresh_token } = self.issue_token(&user, client).await?;
+        info!("User {} passed MFA", user.id);
+        Ok(MfaVerifyResponse { token, refresh_token, recovery_codes })
+    }
+
+    /// Enrolment for users whose role requires MFA before they may sign in
+    pub async fn enroll_mfa(&self, req: MfaEnrollRequest) -> Result<MfaEnrollment> {
+        let user = self.check_mfa_challenge(&req.mfa_token).await?;
+        self.mfa_service.begin_enrollment(user.id).await
+    }
+
+    /// Change the password of a logged-in user. Every token and session so
+    /// far, including the caller's, stops working; the caller gets a fresh one.
+    pub async fn change_password(&self, user_id: Uuid, req: ChangePasswordRequest, client: &ClientInfo) -> Result<TokenResponse> {
+        let stored_hash = self.user_repository.find_password_hash(user_id).await?;
+        if !verify_password(&req.current_password, &stored_hash)? {
+            return Err(ApiError::validation_error("Current password is incorrect"));
+        }
+
+        Validator::validate_new_password(&req.new_password)?;
+        if req.new_password == req.current_password {
+            return Err(ApiError::validation_error("New password must differ from the current one"));
+        }
+
+        let password_hash = hash_password(&req.new_password)?;
+        self.user_repository.update_password_hash(user_id, &password_hash).await?;
+        self.token_repository.delete_unused(user_id, UserTokenPurpose::PasswordReset).await?;
+        self.session_service.revoke_all(user_id).await?;
+
+        info!("Password changed for user {}, other sessions revoked", user_id);
+        let user = self.user_repository.find_by_id(user_id).await?;
+        self.issue_token(&user, client).await
+    }
+
+    /// Start moving a logged-in user to a new address. Nothing changes until
+    /// the link mailed to that address is followed.
+    pub async fn request_email_change(&self, user_id: Uuid, req: ChangeEmailRequest) -> Result<()> {
+        let new_email = req.new_email.trim().to_lowercase();
+        if !Validator::is_valid_email(&new_email) || new_email.len() > 255 {
+            return Err(ApiError::validation_error("Please provide a valid email address"));
+        }
+
+        let stored_hash = self.user_repository.find_password_hash(user_id).await?;
+        if !verify_password(&req.current_password, &stored_hash)? {
+            return Err(ApiError::validation_error("Current password is incorrect"));
+        }
+
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if user.email == new_email {
+            return Err(ApiError::validation_error("That is already your email address"));
+        }
+        if self.user_repository.find_by_email(&new_email).await?.is_some() {
+            return Err(ApiError::EmailAlreadyExists { email: new_email });
+        }
+
+        self.user_repository.set_pending_email(user_id, &new_email).await?;
+
+        let token = generate_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(self.cfg.email_verification_ttl_minutes as i64);
+        self.token_repository
+            .replace(user_id, UserTokenPurpose::EmailChange, &hash_token(&token), expires_at)
+            .await?;
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_email_change(&user, &new_email, &token).await {
+                warn!("Email change confirmation for user {} failed: {}", user.id, e);
+            }
+        });
 
-        let parsed = PasswordHash::new(&stored_hash)
-            .map_err(|_| ApiError::internal_error("Corrupt password hash"))?;
-        let ok = Argon2::default()
-            .verify_password(req.password.as_bytes(), &parsed)
-            .is_ok();
-        if !ok {
-            return Err(ApiError::validation_error("Invalid email or password"));
+        Ok(())
+    }
+
+    /// Finish an email change with the token mailed to the new address. The
+    /// old address is told about the change.
+    pub async fn confirm_email_change(&self, req: ConfirmEmailChangeRequest) -> Result<User> {
+        if req.token.trim().is_empty() {
+            return Err(ApiError::validation_error("Token is required"));
+        }
+
+        let user_id = self.token_repository
+            .consume(UserTokenPurpose::EmailChange, &hash_token(req.token.trim()))
+            .await?
+            .ok_or_else(|| ApiError::bad_request("Invalid or expired confirmation token"))?;
+
+        let previous = self.user_repository.find_by_id(user_id).await?;
+        let pending = self.user_repository
+            .find_pending_email(user_id)
+            .await?
+            .ok_or_else(|| ApiError::bad_request("No email change is pending"))?;
+
+        // Someone may have registered the address since the change was
+        // requested; a registration racing this one is caught by the update
+        if self.user_repository.find_by_email(&pending).await?.is_some() {
+            return Err(ApiError::EmailAlreadyExists { email: pending });
+        }
+
+        let user = self.user_repository.apply_pending_email(user_id, &pending).await?;
+        info!("Email changed for user {}", user_id);
+
+        let service = self.clone();
+        let new_email = user.email.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_email_changed_notice(&previous, &new_email).await {
+                warn!("Email change notice for user {} failed: {}", previous.id, e);
+            }
+        });
+
+        Ok(user)
+    }
+
+    async fn issue_mfa_challenge(&self, user: &User) -> Result<String> {
+        let exp = (chrono::Utc::now() + chrono::Duration::minutes(MFA_CHALLENGE_MINUTES)).timestamp() as usize;
+        let ver = self.user_repository
+            .find_token_version(user.id)
+            .await?
+            .ok_or(ApiError::UserNotFound { id: user.id })?;
+
+        let claims = MfaChallengeClaims {
+            sub: user.id.to_string(),
+            iss: self.cfg.issuer.clone(),
+            aud: self.mfa_audience(),
+            exp,
+            ver,
+        };
+
+        self.jwt_keys.encode(&claims)
+    }
+
+    async fn check_mfa_challenge(&self, mfa_token: &str) -> Result<User> {
+        let invalid = || ApiError::Unauthorized("Invalid or expired MFA token".to_string());
+
+        let mut validation = Validation::default();
+        validation.set_issuer(&[&self.cfg.issuer]);
+        validation.set_audience(&[self.mfa_audience()]);
+        let claims = self.jwt_keys
+            .decode::<MfaChallengeClaims>(mfa_token.trim(), &validation)
+            .map_err(|_| invalid())?;
+
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| invalid())?;
+        // A password change in the meantime voids the challenge
+        if self.user_repository.find_token_version(user_id).await? != Some(claims.ver) {
+            return Err(invalid());
         }
 
+        self.user_repository.find_by_id(user_id).await
+    }
+
+    fn mfa_audience(&self) -> String {
+        format!("{}#mfa", self.cfg.audience)
+    }
+
+    /// A new access token for the session of `refresh_token`, which is
+    /// replaced by the one returned alongside
+    pub async fn refresh(&self, req: RefreshTokenRequest) -> Result<TokenResponse> {
+        if req.refresh_token.trim().is_empty() {
+            return Err(ApiError::validation_error("Refresh token is required"));
+        }
+
+        let (session_id, user_id, refresh_token) = self.session_service.refresh(&req.refresh_token).await?;
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let token = self.issue_access_token(&user, session_id).await?;
+
+        debug!("Session {} refreshed", session_id);
+        Ok(TokenResponse { token, refresh_token })
+    }
+
+    /// Start a session and hand out its first access token
+    async fn issue_token(&self, user: &User, client: &ClientInfo) -> Result<TokenResponse> {
+        let (session_id, refresh_token) = self.session_service.start(user.id, client).await?;
+        let token = self.issue_access_token(user, session_id).await?;
+        Ok(TokenResponse { token, refresh_token })
+    }
+
+    async fn issue_access_token(&self, user: &User, session_id: Uuid) -> Result<String> {
         let exp = chrono::Utc::now()
             .checked_add_signed(chrono::Duration::minutes(self.cfg.expiry_minutes as i64))
             .ok_or_else(|| ApiError::internal_error("Failed to compute token expiry"))?
             .timestamp() as usize;
 
+        let permissions = self.role_repository.find_permissions_for_user(user.id).await?;
+        let ver = self.user_repository
+            .find_token_version(user.id)
+            .await?
+            .ok_or(ApiError::UserNotFound { id: user.id })?;
+
         let claims = Claims {
             sub: user.id.to_string(),
             email: user.email.clone(),
-            role: user.role.to_string(),
+            role: user.role.clone(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
             iss: self.cfg.issuer.clone(),
             aud: self.cfg.audience.clone(),
             exp,
+            ver,
+            sid: Some(session_id.to_string()),
         };
 
-        let token = encode(&Header::default(), &claims, &self.jwt_key)
-            .map_err(|e| ApiError::internal_error(format!("JWT encoding failed: {}", e)))?;
+        self.jwt_keys.encode(&claims)
+    }
 
-        Ok(TokenResponse { token })
+    /// Mail a reset link if the address belongs to an account. The work runs
+    /// in the background so neither the response nor its timing reveals
+    /// whether it does.
+    pub fn request_password_reset(&self, req: ForgotPasswordRequest) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_password_reset(&req.email).await {
+                warn!("Password reset email failed: {}", e);
+            }
+        });
     }
-}
 
+    /// Set a new password with a token from a reset email
+    pub async fn reset_password(&self, req: ResetPasswordRequest) -> Result<()> {
+        Validator::validate_reset_password_request(&req)?;
+
+        let password_hash = hash_password(&req.new_password)?;
+        let user_id = self.token_repository
+            .reset_password(&hash_token(req.token.trim()), &password_hash)
+            .await?
+            .ok_or_else(|| ApiError::bad_request("Invalid or expired reset token"))?;
+
+        self.session_service.revoke_all(user_id).await?;
+
+        info!("Password reset for user {}", user_id);
+        Ok(())
+    }
 
+    /// Mark the account's email as verified with a token from a verification email
+    pub async fn verify_email(&self, req: VerifyEmailRequest) -> Result<()> {
+        if req.token.trim().is_empty() {
+            return Err(ApiError::validation_error("Token is required"));
+        }
+
+        let user_id = self.token_repository
+            .consume(UserTokenPurpose::EmailVerification, &hash_token(req.token.trim()))
+            .await?
+            .ok_or_else(|| ApiError::bad_request("Invalid or expired verification token"))?;
+
+        self.user_repository.mark_email_verified(user_id).await?;
+        self.token_repository.delete_unused(user_id, UserTokenPurpose::EmailVerification).await?;
+
+        info!("Email verified for user {}", user_id);
+        Ok(())
+    }
+
+    /// Verify an unverified account's email for a sign-in that proved the
+    /// mailbox (a magic link). The password is replaced with a random one and
+    /// every session revoked, since whoever set them may not own the address;
+    /// the owner can set a password through a reset.
+    pub async fn claim_unverified_account(&self, user_id: Uuid) -> Result<()> {
+        let password_hash = hash_password(&generate_token())?;
+        if !self.user_repository.claim_unverified_email(user_id, &password_hash).await? {
+            return Ok(());
+        }
+
+        self.session_service.revoke_all(user_id).await?;
+        self.token_repository.delete_unused(user_id, UserTokenPurpose::EmailVerification).await?;
+
+        info!("Email verified for user {} by sign-in, password and sessions reset", user_id);
+        Ok(())
+    }
+
+    /// Send a fresh verification link. Requests are counted per submitted
+    /// address before it is looked up, so unknown and already verified
+    /// addresses are answered, and refused, exactly like unverified ones.
+    pub async fn resend_verification(&self, req: ResendVerificationRequest) -> Result<()> {
+        let email = req.email.trim().to_lowercase();
+        if !Validator::is_valid_email(&email) {
+            return Err(ApiError::validation_error("Invalid email format"));
+        }
+        self.check_resend_limit(&email).await?;
+
+        let Some(user) = self.user_repository.find_by_email(&email).await? else {
+            debug!("Verification resend requested for an unknown address");
+            return Ok(());
+        };
+        if user.is_email_verified() {
+            return Ok(());
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_email_verification(&user).await {
+                warn!("Verification email for user {} failed: {}", user.id, e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// 429 when the address asked for a verification email within the resend interval
+    async fn check_resend_limit(&self, email: &str) -> Result<()> {
+        let Some(cache) = &self.cache else {
+            return Ok(());
+        };
+
+        let interval = self.cfg.email_verification_resend_secs.max(1);
+        let key = verification_resends_key(email);
+        match cache.incr_with_ttl(&key, interval).await {
+            Ok(requests) if requests > 1 => {
+                let retry_after_secs = cache.ttl(&key).await.unwrap_or(None).unwrap_or(interval);
+                Err(ApiError::too_many_requests(
+                    "A verification email was sent recently, please wait before asking again",
+                    retry_after_secs,
+                ))
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                warn!("Verification resend limit skipped: {}", e);
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_email_verification(&self, user: &User) -> Result<()> {
+        let token = generate_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(self.cfg.email_verification_ttl_minutes as i64);
+        self.token_repository
+            .replace(user.id, UserTokenPurpose::EmailVerification, &hash_token(&token), expires_at)
+            .await?;
+
+        let link = format!("{}/verify-email?token={}", self.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nPlease confirm your email address by opening the link below \
+             within {} minutes:\n\n{}\n\n\
+             If you didn't create an account, you can ignore this email.\n",
+            user.name, self.cfg.email_verification_ttl_minutes, link
+        );
+
+        self.mailer
+            .send(EmailMessage { to: user.email.clone(), subject: "Verify your email address".to_string(), body })
+            .await?;
+
+        info!("Verification email sent to user {}", user.id);
+        Ok(())
+    }
+
+    async fn send_email_change(&self, user: &User, new_email: &str, token: &str) -> Result<()> {
+        let link = format!("{}/confirm-email?token={}", self.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nYou asked to use this address for your account. \
+             Open the link below within {} minutes to confirm the change:\n\n{}\n\n\
+             If you didn't ask for this, you can ignore this email.\n",
+            user.name, self.cfg.email_verification_ttl_minutes, link
+        );
+
+        self.mailer
+            .send(EmailMessage { to: new_email.to_string(), subject: "Confirm your new email address".to_string(), body })
+            .await?;
+
+        info!("Email change confirmation sent for user {}", user.id);
+        Ok(())
+    }
+
+    /// Tell the old address that the account now uses `new_email`
+    async fn send_email_changed_notice(&self, user: &User, new_email: &str) -> Result<()> {
+        let body = format!(
+            "Hi {},\n\nThe email address of your account was changed to {}. \
+             From now on, sign-in links and notifications go there.\n\n\
+             If you didn't make this change, reset your password and contact us right away.\n",
+            user.name, new_email
+        );
+
+        self.mailer
+            .send(EmailMessage { to: user.email.clone(), subject: "Your email address was changed".to_string(), body })
+            .await?;
+
+        info!("Email change notice sent for user {}", user.id);
+        Ok(())
+    }
+
+    async fn send_password_reset(&self, email: &str) -> Result<()> {
+        let email = email.trim().to_lowercase();
+        let Some(user) = self.user_repository.find_by_email(&email).await? else {
+            debug!("Password reset requested for an unknown address");
+            return Ok(());
+        };
+
+        let token = generate_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(self.cfg.password_reset_ttl_minutes as i64);
+        self.token_repository
+            .replace(user.id, UserTokenPurpose::PasswordReset, &hash_token(&token), expires_at)
+            .await?;
+
+        let link = format!("{}/reset-password?token={}", self.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nSomeone asked to reset the password for your account. \
+             Open the link below within {} minutes to choose a new one:\n\n{}\n\n\
+             If this wasn't you, you can ignore this email.\n",
+            user.name, self.cfg.password_reset_ttl_minutes, link
+        );
+
+        self.mailer
+            .send(EmailMessage { to: user.email, subject: "Reset your password".to_string(), body })
+            .await?;
+
+        info!("Password reset email sent to user {}", user.id);
+        Ok(())
+    }
+}
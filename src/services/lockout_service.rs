@@ -0,0 +1,227 @@
+use std::net::IpAddr;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::cache::{login_failures_key, login_lock_key, RedisCache};
+use crate::config::settings::LockoutConfig;
+use crate::domain::{AccountLockout, ApiError, Result};
+use crate::repositories::UserRepository;
+use crate::security::hash_token;
+
+/// Counts failed logins per account and per client address in Redis and locks
+/// either out once it crosses its threshold. Wrong passwords for public share
+/// links are counted the same way, per link and per address. Without Redis,
+/// or when Redis errors, attempts are let through rather than refused.
+#[derive(Debug, Clone)]
+pub struct LockoutService {
+    cache: Option<RedisCache>,
+    user_repository: UserRepository,
+    cfg: LockoutConfig,
+}
+
+fn account_scope(email: &str) -> String {
+    format!("account:{}", email.trim().to_lowercase())
+}
+
+/// A scope as it may be logged. Account scopes name an email address, which
+/// may not even belong to an account, so only a short hash of it is shown.
+fn loggable_scope(scope: &str) -> String {
+    match scope.strip_prefix("account:") {
+        Some(email) => format!("account:{}", &hash_token(email)[..12]),
+        None => scope.to_string(),
+    }
+}
+
+fn ip_scope(ip: IpAddr) -> String {
+    format!("ip:{}", ip)
+}
+
+fn share_link_scope(link_id: Uuid) -> String {
+    format!("share:{}", link_id)
+}
+
+/// Kept apart from `ip:` so guessing share passwords doesn't lock logins
+fn share_ip_scope(ip: IpAddr) -> String {
+    format!("share_ip:{}", ip)
+}
+
+impl LockoutService {
+    pub fn new(cache: Option<RedisCache>, user_repository: UserRepository, cfg: LockoutConfig) -> Self {
+        Self { cache, user_repository, cfg }
+    }
+
+    /// 429 while either the account or the client address is locked
+    pub async fn check(&self, email: &str, ip: Option<IpAddr>) -> Result<()> {
+        self.check_scopes(self.scopes(email, ip), "Too many failed login attempts, try again later").await
+    }
+
+    pub async fn record_failure(&self, email: &str, ip: Option<IpAddr>) {
+        self.record_scopes(self.scopes(email, ip)).await;
+    }
+
+    /// 429 while either the share link or the client address is locked
+    pub async fn check_share(&self, link_id: Uuid, ip: Option<IpAddr>) -> Result<()> {
+        self.check_scopes(self.share_scopes(link_id, ip), "Too many wrong share passwords, try again later").await
+    }
+
+    pub async fn record_share_failure(&self, link_id: Uuid, ip: Option<IpAddr>) {
+        self.record_scopes(self.share_scopes(link_id, ip)).await;
+    }
+
+    /// Forget the account's failures; the client address keeps its count
+    pub async fn record_success(&self, email: &str) {
+        let scope = account_scope(email);
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.del(&login_failures_key(&scope)).await
+        {
+            warn!("Login failures for {} not cleared: {}", loggable_scope(&scope), e);
+        }
+    }
+
+    /// Accounts currently locked, by email
+    pub async fn locked_accounts(&self) -> Result<Vec<AccountLockout>> {
+        let Some(cache) = &self.cache else {
+            return Ok(Vec::new());
+        };
+
+        let prefix = login_lock_key("account:");
+        let keys = cache
+            .scan_keys(&format!("{}*", prefix))
+            .await
+            .map_err(|e| ApiError::InternalError(format!("Redis scan error: {}", e)))?;
+
+        let mut lockouts = Vec::with_capacity(keys.len());
+        for key in keys {
+            let Some(email) = key.strip_prefix(&prefix) else {
+                continue;
+            };
+            // Skip locks that ran out since the scan
+            let Some(retry_after_secs) = cache.ttl(&key).await.unwrap_or(None) else {
+                continue;
+            };
+            let failed_attempts = cache.get_json::<i64>(&key).await.unwrap_or(None).unwrap_or(0);
+            let user_id = self.user_repository.find_by_email(email).await?.map(|user| user.id);
+
+            lockouts.push(AccountLockout {
+                email: email.to_string(),
+                user_id,
+                failed_attempts,
+                retry_after_secs,
+            });
+        }
+
+        lockouts.sort_by(|a, b| a.email.cmp(&b.email));
+        Ok(lockouts)
+    }
+
+    /// Lift the lock on a user's account and forget its failures. Returns
+    /// whether the account was locked.
+    pub async fn unlock(&self, user_id: Uuid) -> Result<bool> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let Some(cache) = &self.cache else {
+            return Ok(false);
+        };
+
+        let scope = account_scope(&user.email);
+        let redis_error = |e: redis::RedisError| ApiError::InternalError(format!("Redis unlock error: {}", e));
+        let was_locked = cache.ttl(&login_lock_key(&scope)).await.map_err(redis_error)?.is_some();
+        cache.del(&login_lock_key(&scope)).await.map_err(redis_error)?;
+        cache.del(&login_failures_key(&scope)).await.map_err(redis_error)?;
+
+        if was_locked {
+            info!("Login lock on user {} lifted", user_id);
+        }
+        Ok(was_locked)
+    }
+
+    fn scopes(&self, email: &str, ip: Option<IpAddr>) -> Vec<(String, u32)> {
+        let mut scopes = vec![(account_scope(email), self.cfg.account_threshold)];
+        if let Some(ip) = ip {
+            scopes.push((ip_scope(ip), self.cfg.ip_threshold));
+        }
+        scopes
+    }
+
+    /// A link is held to the account threshold, an address to the IP one
+    fn share_scopes(&self, link_id: Uuid, ip: Option<IpAddr>) -> Vec<(String, u32)> {
+        let mut scopes = vec![(share_link_scope(link_id), self.cfg.account_threshold)];
+        if let Some(ip) = ip {
+            scopes.push((share_ip_scope(ip), self.cfg.ip_threshold));
+        }
+        scopes
+    }
+
+    async fn check_scopes(&self, scopes: Vec<(String, u32)>, message: &str) -> Result<()> {
+        let Some(cache) = &self.cache else {
+            return Ok(());
+        };
+
+        let mut retry_after_secs = 0;
+        for (scope, _) in scopes {
+            match cache.ttl(&login_lock_key(&scope)).await {
+                Ok(Some(secs)) => retry_after_secs = retry_after_secs.max(secs),
+                Ok(None) => {}
+                Err(e) => warn!("Lockout check for {} skipped: {}", loggable_scope(&scope), e),
+            }
+        }
+
+        if retry_after_secs > 0 {
+            return Err(ApiError::too_many_requests(message, retry_after_secs));
+        }
+        Ok(())
+    }
+
+    async fn record_scopes(&self, scopes: Vec<(String, u32)>) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+
+        for (scope, threshold) in scopes {
+            if let Err(e) = self.count_failure(cache, &scope, threshold).await {
+                warn!("Failed attempt for {} not counted: {}", loggable_scope(&scope), e);
+            }
+        }
+    }
+
+    async fn count_failure(&self, cache: &RedisCache, scope: &str, threshold: u32) -> redis::RedisResult<()> {
+        let failures_key = login_failures_key(scope);
+        let failures = cache.incr_with_ttl(&failures_key, self.cfg.failure_window_secs).await?;
+
+        let threshold = i64::from(threshold.max(1));
+        if failures < threshold {
+            return Ok(());
+        }
+
+        let lock_secs = self.lock_secs(failures - threshold);
+        cache.set_json_with_ttl(&login_lock_key(scope), &failures, lock_secs).await?;
+        // Keep counting past the lock so the next failure backs off further
+        cache.expire(&failures_key, lock_secs + self.cfg.failure_window_secs).await?;
+
+        warn!("Locked {} for {}s after {} failed attempts", loggable_scope(scope), lock_secs, failures);
+        Ok(())
+    }
+
+    /// `base_lock_secs` doubled once per failure past the threshold, capped
+    fn lock_secs(&self, failures_past_threshold: i64) -> u64 {
+        let doublings = failures_past_threshold.clamp(0, 20) as u32;
+        self.cfg.base_lock_secs
+            .saturating_mul(1 << doublings)
+            .min(self.cfg.max_lock_secs)
+            .max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_scopes_are_logged_without_the_address() {
+        let logged = loggable_scope(&account_scope("Victim@Example.com"));
+        assert!(logged.starts_with("account:"));
+        assert!(!logged.contains("victim") && !logged.contains("example"));
+        assert_eq!(logged, loggable_scope(&account_scope("victim@example.com ")));
+        assert_eq!(loggable_scope("ip:203.0.113.7"), "ip:203.0.113.7");
+    }
+}
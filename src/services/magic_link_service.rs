@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use tracing::{debug, info, warn};
+
+use crate::cache::{magic_link_requests_key, RedisCache};
+use crate::config::settings::MagicLinkConfig;
+use crate::domain::{ApiError, ClientInfo, ConsumeMagicLinkRequest, MagicLinkIssued, MagicLinkRequest, Result};
+use crate::mail::{EmailMessage, Mailer};
+use crate::repositories::{MagicLinkRepository, UserRepository};
+use crate::security::{generate_token, hash_token};
+use crate::services::auth_service::{AuthService, LoginResponse};
+use crate::validation::Validator;
+
+/// Passwordless sign-in: a single-use link is mailed to the address, and it
+/// only works together with the device code returned to whoever asked for
+/// it. Requests are counted per address in Redis; without Redis, or when
+/// Redis errors, they are let through.
+#[derive(Clone)]
+pub struct MagicLinkService {
+    magic_link_repository: MagicLinkRepository,
+    user_repository: UserRepository,
+    auth_service: AuthService,
+    cache: Option<RedisCache>,
+    mailer: Arc<dyn Mailer>,
+    cfg: MagicLinkConfig,
+    /// Base URL of the web app, for the link in the email
+    app_base_url: String,
+}
+
+impl MagicLinkService {
+    pub fn new(
+        magic_link_repository: MagicLinkRepository,
+        user_repository: UserRepository,
+        auth_service: AuthService,
+        cache: Option<RedisCache>,
+        mailer: Arc<dyn Mailer>,
+        cfg: MagicLinkConfig,
+        app_base_url: String,
+    ) -> Self {
+        Self {
+            magic_link_repository,
+            user_repository,
+            auth_service,
+            cache,
+            mailer,
+            cfg,
+            app_base_url,
+        }
+    }
+
+    /// Mail a link if the address belongs to an account. The device code
+    /// comes back either way, and the email goes out in the background, so
+    /// neither the response nor its timing reveals whether it does.
+    pub async fn request(&self, req: MagicLinkRequest) -> Result<MagicLinkIssued> {
+        let email = req.email.trim().to_lowercase();
+        if !Validator::is_valid_email(&email) {
+            return Err(ApiError::validation_error("Invalid email format"));
+        }
+        self.check_rate_limit(&email).await?;
+
+        let device_code = generate_token();
+        let device_code_hash = hash_token(&device_code);
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_link(&email, &device_code_hash).await {
+                warn!("Magic link email failed: {}", e);
+            }
+        });
+
+        Ok(MagicLinkIssued {
+            device_code,
+            expires_in_secs: self.cfg.ttl_minutes.saturating_mul(60),
+        })
+    }
+
+    /// Sign in with a link's token and the device code it was issued with.
+    /// Same response as a password login: a token, or an MFA challenge.
+    pub async fn consume(&self, req: ConsumeMagicLinkRequest, client: &ClientInfo) -> Result<LoginResponse> {
+        if req.token.trim().is_empty() || req.device_code.trim().is_empty() {
+            return Err(ApiError::validation_error("Token and device code are required"));
+        }
+
+        let user_id = self.magic_link_repository
+            .consume(&hash_token(req.token.trim()), &hash_token(req.device_code.trim()))
+            .await?
+            .ok_or_else(|| ApiError::unauthorized("Invalid or expired sign-in link"))?;
+
+        let mut user = self.user_repository.find_by_id(user_id).await?;
+        if !user.is_email_verified() {
+            // Following the link proves the mailbox is theirs, but not that
+            // they chose the password the account was registered with
+            self.auth_service.claim_unverified_account(user.id).await?;
+            user = self.user_repository.find_by_id(user.id).await?;
+        }
+
+        info!("User {} signed in with a magic link", user.id);
+        self.auth_service.sign_in(&user, client).await
+    }
+
+    /// 429 once the address has asked for `max_requests` links in the window
+    async fn check_rate_limit(&self, email: &str) -> Result<()> {
+        let Some(cache) = &self.cache else {
+            return Ok(());
+        };
+
+        let key = magic_link_requests_key(email);
+        match cache.incr_with_ttl(&key, self.cfg.request_window_secs).await {
+            Ok(requests) if requests > i64::from(self.cfg.max_requests) => {
+                let retry_after_secs = cache.ttl(&key).await.unwrap_or(None).unwrap_or(self.cfg.request_window_secs);
+                Err(ApiError::too_many_requests(
+                    "Too many sign-in links requested for this address, try again later",
+                    retry_after_secs,
+                ))
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                warn!("Magic link rate limit skipped: {}", e);
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_link(&self, email: &str, device_code_hash: &str) -> Result<()> {
+        let Some(user) = self.user_repository.find_by_email(email).await? else {
+            debug!("Magic link requested for an unknown address");
+            return Ok(());
+        };
+
+        let token = generate_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(self.cfg.ttl_minutes as i64);
+        self.magic_link_repository
+            .replace(user.id, &hash_token(&token), device_code_hash, expires_at)
+            .await?;
+
+        let link = format!("{}/magic-link?token={}", self.app_base_url.trim_end_matches('/'), token);
+        let body = format!(
+            "Hi {},\n\nOpen the link below within {} minutes to sign in. \
+             It works once, and only on the device you asked from:\n\n{}\n\n\
+             If you didn't ask to sign in, you can ignore this email.\n",
+            user.name, self.cfg.ttl_minutes, link
+        );
+
+        self.mailer
+            .send(EmailMessage { to: user.email, subject: "Your sign-in link".to_string(), body })
+            .await?;
+
+        info!("Magic link sent to user {}", user.id);
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use rand::{rngs::OsRng, RngCore};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::{ApiError, MfaEnrollment, MfaStatus, RecoveryCodes, Result};
+use crate::repositories::{MfaRepository, RoleRepository, UserRepository};
+use crate::security::{generate_totp_secret, hash_token, totp_uri, verify_totp};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// 80 bits per code, printed as four groups of five hex digits
+const RECOVERY_CODE_BYTES: usize = 10;
+
+/// Whether a user has to pass a second factor, and whether they can
+#[derive(Debug, Clone, Copy)]
+pub struct MfaState {
+    pub enabled: bool,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MfaService {
+    mfa_repository: MfaRepository,
+    user_repository: UserRepository,
+    role_repository: RoleRepository,
+    /// Name authenticator apps show next to the account
+    issuer: String,
+}
+
+impl MfaService {
+    pub fn new(
+        mfa_repository: MfaRepository,
+        user_repository: UserRepository,
+        role_repository: RoleRepository,
+        issuer: String,
+    ) -> Self {
+        Self { mfa_repository, user_repository, role_repository, issuer }
+    }
+
+    pub async fn state(&self, user_id: Uuid) -> Result<MfaState> {
+        let enabled = self.mfa_repository
+            .find(user_id)
+            .await?
+            .is_some_and(|mfa| mfa.is_confirmed());
+        let required = self.role_repository.requires_mfa_for_user(user_id).await?;
+        Ok(MfaState { enabled, required })
+    }
+
+    pub async fn status(&self, user_id: Uuid) -> Result<MfaStatus> {
+        let state = self.state(user_id).await?;
+        let recovery_codes_remaining = if state.enabled {
+            self.mfa_repository.count_unused_recovery_codes(user_id).await?
+        } else {
+            0
+        };
+        Ok(MfaStatus { enabled: state.enabled, required: state.required, recovery_codes_remaining })
+    }
+
+    /// Generate a secret for the user's authenticator app. MFA is not enabled
+    /// until `confirm_enrollment` sees a code generated from it.
+    pub async fn begin_enrollment(&self, user_id: Uuid) -> Result<MfaEnrollment> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+
+        let secret = generate_totp_secret();
+        if !self.mfa_repository.upsert_pending(user_id, &secret).await? {
+            return Err(ApiError::conflict("Two-factor authentication is already enabled"));
+        }
+
+        let otpauth_uri = totp_uri(&secret, &self.issuer, &user.email)?;
+        info!("MFA enrolment started for user {}", user_id);
+        Ok(MfaEnrollment { secret, otpauth_uri })
+    }
+
+    /// Enable MFA with the first code from the authenticator; returns the recovery codes
+    pub async fn confirm_enrollment(&self, user_id: Uuid, code: &str) -> Result<RecoveryCodes> {
+        let mfa = self.mfa_repository
+            .find(user_id)
+            .await?
+            .filter(|mfa| !mfa.is_confirmed())
+            .ok_or_else(|| ApiError::bad_request("No MFA enrolment is pending"))?;
+
+        let step = verify_totp(&mfa.secret, code.trim(), now_secs())?
+            .ok_or_else(|| ApiError::Unauthorized("Invalid authentication code".to_string()))?;
+
+        let (codes, hashes) = generate_recovery_codes();
+        self.mfa_repository.confirm(user_id, step, &hashes).await?;
+
+        info!("MFA enabled for user {}", user_id);
+        Ok(RecoveryCodes { recovery_codes: codes })
+    }
+
+    /// Check a TOTP code or a recovery code for a user with MFA enabled.
+    /// Each code works once.
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<bool> {
+        let mfa = self.mfa_repository
+            .find(user_id)
+            .await?
+            .filter(|mfa| mfa.is_confirmed())
+            .ok_or_else(|| ApiError::bad_request("Two-factor authentication is not enabled"))?;
+
+        let code = code.trim();
+        if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            return match verify_totp(&mfa.secret, code, now_secs())? {
+                Some(step) => self.mfa_repository.record_step(user_id, step).await,
+                None => Ok(false),
+            };
+        }
+
+        let used = self.mfa_repository
+            .consume_recovery_code(user_id, &hash_token(&normalize_recovery_code(code)))
+            .await?;
+        if used {
+            warn!("Recovery code used by user {}", user_id);
+        }
+        Ok(used)
+    }
+
+    /// Turn MFA off after checking a code; not possible while the role requires it
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<()> {
+        if self.role_repository.requires_mfa_for_user(user_id).await? {
+            return Err(ApiError::forbidden("Your role requires two-factor authentication"));
+        }
+        if !self.verify(user_id, code).await? {
+            return Err(ApiError::Unauthorized("Invalid authentication code".to_string()));
+        }
+
+        self.mfa_repository.delete(user_id).await?;
+        info!("MFA disabled for user {}", user_id);
+        Ok(())
+    }
+
+    /// Replace all recovery codes after checking a code
+    pub async fn regenerate_recovery_codes(&self, user_id: Uuid, code: &str) -> Result<RecoveryCodes> {
+        if !self.verify(user_id, code).await? {
+            return Err(ApiError::Unauthorized("Invalid authentication code".to_string()));
+        }
+
+        let (codes, hashes) = generate_recovery_codes();
+        self.mfa_repository.replace_recovery_codes(user_id, &hashes).await?;
+
+        info!("Recovery codes regenerated for user {}", user_id);
+        Ok(RecoveryCodes { recovery_codes: codes })
+    }
+}
+
+/// Plain codes to show the user, and the hashes to store
+fn generate_recovery_codes() -> (Vec<String>, Vec<String>) {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+            OsRng.fill_bytes(&mut bytes);
+            let hex = hex::encode(bytes);
+            let code = hex
+                .as_bytes()
+                .chunks(5)
+                .map(|group| std::str::from_utf8(group).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("-");
+            (code, hash_token(&hex))
+        })
+        .unzip()
+}
+
+/// Recovery codes are accepted with or without dashes, in either case
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
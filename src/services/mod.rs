@@ -1,8 +1,26 @@
 // Services module - business logic layer
 pub mod user_service;
+pub mod role_service;
 pub mod task_service;
 pub mod auth_service;
+pub mod mfa_service;
+pub mod lockout_service;
+pub mod session_service;
+pub mod oidc_service;
+pub mod magic_link_service;
+pub mod api_key_service;
+pub mod note_service;
+pub mod share_service;
 
 pub use user_service::UserService;
-pub use task_service::TaskService;
-pub use auth_service::{AuthService, RegisterRequest, LoginRequest, TokenResponse};
+pub use role_service::RoleService;
+pub use task_service::{TaskService, QuickAddRequest, QuickAddResponse, TransferOutcome, SlugLookup};
+pub use auth_service::{AuthService, AuthRepositories, AuthServices, RegisterRequest, LoginRequest, ForgotPasswordRequest, ResetPasswordRequest, TokenResponse};
+pub use mfa_service::MfaService;
+pub use lockout_service::LockoutService;
+pub use session_service::SessionService;
+pub use oidc_service::OidcService;
+pub use magic_link_service::MagicLinkService;
+pub use api_key_service::ApiKeyService;
+pub use note_service::NoteService;
+pub use share_service::ShareService;
@@ -0,0 +1,409 @@
+use uuid::Uuid;
+use tracing::{info, debug};
+
+use crate::config::settings::NotesConfig;
+use crate::domain::{
+    Note, CreateNoteRequest, UpdateNoteRequest, NoteRevision, NoteRevisionSummary, NoteDiff, DiffFormat, NoteRef, NoteGraph,
+    Notebook, NotebookWithCounts, CreateNotebookRequest, NotebookDeleteMode, ExtractTasksRequest, ExtractTasksResponse,
+    CreateTaskRequest, UpdateTaskRequest, Result, ApiError,
+};
+use crate::domain::task::TaskStatus;
+use crate::parser::{parse_wiki_links, rewrite_wiki_links, parse_task_items};
+use crate::repositories::{NoteRepository, NotebookRepository, CreateNoteRequestInternal, UpdateNoteRequestInternal};
+use super::TaskService;
+
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_BODY_LENGTH: usize = 100_000;
+const MAX_NOTEBOOK_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct NoteService {
+    note_repository: NoteRepository,
+    notebook_repository: NotebookRepository,
+    task_service: TaskService,
+    config: NotesConfig,
+}
+
+impl NoteService {
+    pub fn new(
+        note_repository: NoteRepository,
+        notebook_repository: NotebookRepository,
+        task_service: TaskService,
+        config: NotesConfig,
+    ) -> Self {
+        Self { note_repository, notebook_repository, task_service, config }
+    }
+
+    pub async fn create_note(&self, request: CreateNoteRequest, user_id: Uuid) -> Result<Note> {
+        Self::validate_title(&request.title)?;
+        Self::validate_body(&request.body)?;
+
+        if let Some(notebook_id) = request.notebook_id {
+            self.get_owned_notebook(notebook_id, user_id).await?;
+        }
+
+        let links = parse_wiki_links(&request.body);
+        let note = self
+            .note_repository
+            .create(CreateNoteRequestInternal {
+                user_id,
+                notebook_id: request.notebook_id,
+                title: request.title.trim().to_string(),
+                body: request.body,
+                links,
+            })
+            .await?;
+
+        info!("Note created: {} (slug: {})", note.id, note.slug);
+        Ok(note)
+    }
+
+    pub async fn get_note(&self, id: Uuid) -> Result<Note> {
+        self.note_repository.find_by_id(id).await
+    }
+
+    pub async fn get_notes_by_user(&self, user_id: Uuid) -> Result<Vec<Note>> {
+        self.note_repository.find_by_user_id(user_id).await
+    }
+
+    /// Notes of `user_id` in a notebook, optionally including its sub-notebooks
+    pub async fn get_notes_in_notebook(&self, user_id: Uuid, notebook_id: Uuid, include_descendants: bool) -> Result<Vec<Note>> {
+        self.get_owned_notebook(notebook_id, user_id).await?;
+
+        let notebook_ids = if include_descendants {
+            self.notebook_repository
+                .find_subtree(notebook_id)
+                .await?
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect()
+        } else {
+            vec![notebook_id]
+        };
+
+        self.note_repository.find_by_notebooks(user_id, &notebook_ids).await
+    }
+
+    /// File a note in another notebook of its owner, or at the top level
+    pub async fn move_note(&self, note: &Note, notebook_id: Option<Uuid>) -> Result<Note> {
+        if let Some(notebook_id) = notebook_id {
+            self.get_owned_notebook(notebook_id, note.user_id).await?;
+        }
+
+        let moved = self.note_repository.set_notebook(note.id, notebook_id).await?;
+        info!("Note {} moved to notebook {:?}", note.id, notebook_id);
+        Ok(moved)
+    }
+
+    pub async fn create_notebook(&self, request: CreateNotebookRequest, user_id: Uuid) -> Result<Notebook> {
+        Self::validate_notebook_name(&request.name)?;
+
+        if let Some(parent_id) = request.parent_id {
+            self.get_owned_notebook(parent_id, user_id).await?;
+        }
+
+        let notebook = self
+            .notebook_repository
+            .create(user_id, request.name.trim(), request.parent_id)
+            .await?;
+
+        info!("Notebook created: {} ({})", notebook.id, notebook.name);
+        Ok(notebook)
+    }
+
+    pub async fn get_notebook(&self, id: Uuid) -> Result<Notebook> {
+        self.notebook_repository.find_by_id(id).await
+    }
+
+    pub async fn get_notebooks(&self, user_id: Uuid) -> Result<Vec<NotebookWithCounts>> {
+        self.notebook_repository.find_with_counts(user_id, None).await
+    }
+
+    pub async fn get_notebook_with_counts(&self, notebook: &Notebook) -> Result<NotebookWithCounts> {
+        self.notebook_repository
+            .find_with_counts(notebook.user_id, Some(notebook.id))
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::not_found(format!("Notebook not found: {}", notebook.id)))
+    }
+
+    pub async fn rename_notebook(&self, id: Uuid, name: &str) -> Result<Notebook> {
+        Self::validate_notebook_name(name)?;
+        self.notebook_repository.rename(id, name.trim()).await
+    }
+
+    /// Re-parent a notebook within its owner's tree, keeping the tree acyclic
+    /// and within `MAX_NOTEBOOK_DEPTH`
+    pub async fn move_notebook(&self, notebook: &Notebook, parent_id: Option<Uuid>) -> Result<Notebook> {
+        if let Some(parent_id) = parent_id {
+            self.get_owned_notebook(parent_id, notebook.user_id).await?;
+        }
+
+        let moved = self.notebook_repository.set_parent(notebook, parent_id).await?;
+        info!("Notebook {} moved under {:?}", notebook.id, parent_id);
+        Ok(moved)
+    }
+
+    pub async fn delete_notebook(&self, notebook: &Notebook, mode: NotebookDeleteMode) -> Result<()> {
+        match mode {
+            NotebookDeleteMode::Rehome => {
+                self.notebook_repository.delete_rehoming(notebook).await?;
+                info!("Notebook {} deleted, contents moved to {:?}", notebook.id, notebook.parent_id);
+            }
+            NotebookDeleteMode::Cascade => {
+                let deleted_notes = self.notebook_repository.delete_cascading(notebook.id).await?;
+                info!("Notebook {} deleted with {} notes", notebook.id, deleted_notes);
+            }
+        }
+        Ok(())
+    }
+
+    /// A notebook that must belong to `user_id`; other users' notebooks look missing
+    async fn get_owned_notebook(&self, notebook_id: Uuid, user_id: Uuid) -> Result<Notebook> {
+        let notebook = self.notebook_repository.find_by_id(notebook_id).await?;
+        if notebook.user_id != user_id {
+            return Err(ApiError::not_found(format!("Notebook not found: {}", notebook_id)));
+        }
+        Ok(notebook)
+    }
+
+    /// Save changes to a note as a new revision by `author_id`. Saves that
+    /// change nothing do not create a revision.
+    pub async fn update_note(&self, note: &Note, request: UpdateNoteRequest, author_id: Uuid) -> Result<Note> {
+        if let Some(title) = &request.title {
+            Self::validate_title(title)?;
+        }
+        if let Some(body) = &request.body {
+            Self::validate_body(body)?;
+        }
+
+        let title = request.title.map(|title| title.trim().to_string());
+        let updated = self.save_note(note, title, request.body, author_id).await?;
+
+        if request.rewrite_links && updated.title != note.title {
+            self.rewrite_links_to(note, &updated, author_id).await?;
+        }
+        Ok(updated)
+    }
+
+    pub async fn delete_note(&self, id: Uuid) -> Result<()> {
+        self.note_repository.delete(id).await?;
+        info!("Note deleted: {}", id);
+        Ok(())
+    }
+
+    pub async fn get_revisions(&self, note_id: Uuid) -> Result<Vec<NoteRevisionSummary>> {
+        self.note_repository.find_revisions(note_id).await
+    }
+
+    pub async fn get_revision(&self, note_id: Uuid, revision: i32) -> Result<NoteRevision> {
+        self.note_repository.find_revision(note_id, revision).await
+    }
+
+    pub async fn diff_revisions(&self, note_id: Uuid, from: i32, to: i32, format: DiffFormat) -> Result<NoteDiff> {
+        let from = self.note_repository.find_revision(note_id, from).await?;
+        let to = self.note_repository.find_revision(note_id, to).await?;
+        Ok(NoteDiff::between(&from, &to, format))
+    }
+
+    /// Bring back the title and body of an old revision. The restore is
+    /// itself recorded as the newest revision, so it can be undone.
+    pub async fn restore_revision(&self, note: &Note, revision: i32, author_id: Uuid) -> Result<Note> {
+        let old = self.note_repository.find_revision(note.id, revision).await?;
+
+        let request = UpdateNoteRequest {
+            title: Some(old.title),
+            body: Some(old.body),
+            ..Default::default()
+        };
+
+        info!("Restoring note {} to revision {}", note.id, revision);
+        self.update_note(note, request, author_id).await
+    }
+
+    pub async fn get_backlinks(&self, note_id: Uuid) -> Result<Vec<NoteRef>> {
+        self.note_repository.find_backlinks(note_id).await
+    }
+
+    pub async fn get_graph(&self, user_id: Uuid) -> Result<NoteGraph> {
+        let (nodes, edges) = self.note_repository.find_graph(user_id).await?;
+        debug!("Note graph for user {}: {} nodes, {} edges", user_id, nodes.len(), edges.len());
+        Ok(NoteGraph { nodes, edges })
+    }
+
+    /// Persist title/body changes, re-parsing links when the body changes
+    async fn save_note(&self, note: &Note, title: Option<String>, body: Option<String>, author_id: Uuid) -> Result<Note> {
+        let title = title.filter(|title| *title != note.title);
+        let body = body.filter(|body| *body != note.body);
+
+        if title.is_none() && body.is_none() {
+            debug!("Note {} unchanged, no revision recorded", note.id);
+            return Ok(note.clone());
+        }
+
+        let links = body.as_deref().map(parse_wiki_links);
+
+        let changes = UpdateNoteRequestInternal { title, body, links };
+        let updated = self.note_repository.update(note, changes, author_id, &self.config).await?;
+
+        info!("Note {} saved by {}", updated.id, author_id);
+
+        if updated.body != note.body {
+            self.sync_linked_tasks(note, &updated, author_id).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Create tasks for the note's task-list items that have none yet. Tasks
+    /// belong to the note's owner and stay linked to their item.
+    pub async fn extract_tasks(&self, note: &Note, request: ExtractTasksRequest) -> Result<ExtractTasksResponse> {
+        let linked: Vec<String> = self
+            .note_repository
+            .find_task_links(note.id)
+            .await?
+            .into_iter()
+            .map(|link| link.item_text)
+            .collect();
+
+        let mut created = Vec::new();
+        let mut already_linked = 0;
+        let mut seen: Vec<&str> = Vec::new();
+
+        // Task titles share the note title limit; fail before creating anything
+        let items = parse_task_items(&note.body);
+        if let Some(item) = items.iter().find(|item| item.text.len() > MAX_TITLE_LENGTH) {
+            return Err(ApiError::ValidationError(format!(
+                "Task item on line {} exceeds {} characters",
+                item.line + 1,
+                MAX_TITLE_LENGTH
+            )));
+        }
+
+        for item in &items {
+            if seen.contains(&item.text.as_str()) {
+                continue;
+            }
+            seen.push(&item.text);
+
+            if linked.contains(&item.text) {
+                already_linked += 1;
+                continue;
+            }
+            if item.checked && !request.include_checked {
+                continue;
+            }
+
+            let task_request = CreateTaskRequest {
+                title: item.text.clone(),
+                description: Some(format!("From note \"{}\"", note.title)),
+                checklist: Vec::new(),
+                tags: Vec::new(),
+                priority: None,
+                due_at: None,
+                assignee_id: None,
+                parent_id: None,
+            };
+            let task = self.task_service
+                .create_task_for_note_item(task_request, note.user_id, note.id, &item.text, item.checked)
+                .await?;
+            match task {
+                Some(task) => created.push(task),
+                // Linked by a concurrent extraction since we looked
+                None => already_linked += 1,
+            }
+        }
+
+        info!("Extracted {} tasks from note {} ({} already linked)", created.len(), note.id, already_linked);
+        Ok(ExtractTasksResponse { created, already_linked })
+    }
+
+    /// Complete or reopen linked tasks whose item was ticked or unticked in this save
+    async fn sync_linked_tasks(&self, before: &Note, after: &Note, author_id: Uuid) -> Result<()> {
+        let links = self.note_repository.find_task_links(after.id).await?;
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let old_items = parse_task_items(&before.body);
+        let new_items = parse_task_items(&after.body);
+
+        for link in links {
+            let was_checked = old_items.iter().find(|item| item.text == link.item_text).map(|item| item.checked);
+            let is_checked = new_items.iter().find(|item| item.text == link.item_text).map(|item| item.checked);
+            let (Some(was_checked), Some(is_checked)) = (was_checked, is_checked) else {
+                continue;
+            };
+            if was_checked == is_checked {
+                continue;
+            }
+
+            let task = self.task_service.get_task(link.task_id).await?;
+            if task.user_id != after.user_id {
+                debug!("Task {} no longer belongs to the owner of note {}, not syncing", task.id, after.id);
+                continue;
+            }
+            let status = match (is_checked, &task.status) {
+                (true, TaskStatus::Done) | (false, TaskStatus::Todo | TaskStatus::InProgress) => continue,
+                (true, _) => TaskStatus::Done,
+                (false, _) => TaskStatus::Todo,
+            };
+
+            debug!("Note {} item ticked = {}, updating task {}", after.id, is_checked, task.id);
+            let update = UpdateTaskRequest { status: Some(status), ..Default::default() };
+            self.task_service.update_task(&task, update, author_id).await?;
+        }
+        Ok(())
+    }
+
+    /// After `before` was renamed to `after`, rewrite `[[old title]]` and
+    /// `[[old-slug]]` links in the notes that reference it
+    async fn rewrite_links_to(&self, before: &Note, after: &Note, author_id: Uuid) -> Result<()> {
+        let old_title = before.title.to_lowercase();
+        let matches = |target: &str| target == before.slug || target.to_lowercase() == old_title;
+        let new_target = |target: &str| {
+            if target == before.slug { after.slug.clone() } else { after.title.clone() }
+        };
+
+        let mut rewritten = 0;
+        for source in self.note_repository.find_linking_notes(before.id).await? {
+            let body = rewrite_wiki_links(&source.body, matches, new_target);
+            if body != source.body {
+                self.save_note(&source, None, Some(body), author_id).await?;
+                rewritten += 1;
+            }
+        }
+
+        info!("Rewrote links to note {} in {} notes", after.id, rewritten);
+        Ok(())
+    }
+
+    fn validate_title(title: &str) -> Result<()> {
+        if title.trim().is_empty() {
+            return Err(ApiError::ValidationError("Title cannot be empty".to_string()));
+        }
+        if title.len() > MAX_TITLE_LENGTH {
+            return Err(ApiError::ValidationError(format!("Title cannot exceed {} characters", MAX_TITLE_LENGTH)));
+        }
+        Ok(())
+    }
+
+    fn validate_notebook_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(ApiError::ValidationError("Notebook name cannot be empty".to_string()));
+        }
+        if name.len() > MAX_NOTEBOOK_NAME_LENGTH {
+            return Err(ApiError::ValidationError(format!(
+                "Notebook name cannot exceed {} characters",
+                MAX_NOTEBOOK_NAME_LENGTH
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_body(body: &str) -> Result<()> {
+        if body.len() > MAX_BODY_LENGTH {
+            return Err(ApiError::ValidationError(format!("Note body cannot exceed {} characters", MAX_BODY_LENGTH)));
+        }
+        Ok(())
+    }
+}
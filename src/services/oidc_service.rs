@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::config::settings::{OidcConfig, OidcProviderConfig};
+use crate::domain::{ApiError, ClientInfo, OidcAuthRequest, OidcAuthorization, OidcCallbackRequest, OidcProvider, Result, User};
+use crate::repositories::{OidcRepository, UserRepository};
+use crate::security::{generate_token, hash_password, hash_token};
+use crate::services::auth_service::{AuthService, LoginResponse};
+
+/// How long a user has to come back from the provider
+const AUTH_REQUEST_MINUTES: i64 = 10;
+/// Discovery documents and provider keys are refetched this often
+const DISCOVERY_TTL: Duration = Duration::from_secs(60 * 60);
+/// An unknown `kid` refetches the provider's keys, but no more often than this
+const JWKS_REFRESH_MIN_INTERVAL: Duration = Duration::from_secs(60);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The parts of a provider's discovery document the code flow needs
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Clone)]
+struct DiscoveredProvider {
+    metadata: ProviderMetadata,
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenEndpointResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    /// A bool per the spec, but some providers send the string "true"
+    email_verified: Option<serde_json::Value>,
+    name: Option<String>,
+}
+
+impl IdTokenClaims {
+    fn has_verified_email(&self) -> bool {
+        match &self.email_verified {
+            Some(serde_json::Value::Bool(verified)) => *verified,
+            Some(serde_json::Value::String(verified)) => verified == "true",
+            _ => false,
+        }
+    }
+}
+
+/// Sign-in through OpenID Connect providers: authorization code flow with
+/// PKCE, state and nonce. A provider account is linked to a local user on
+/// first sign-in when both sides verified the same email, or a new user is
+/// created.
+#[derive(Clone)]
+pub struct OidcService {
+    providers: Arc<Vec<OidcProviderConfig>>,
+    oidc_repository: OidcRepository,
+    user_repository: UserRepository,
+    auth_service: AuthService,
+    http: reqwest::Client,
+    discovered: Arc<RwLock<HashMap<String, DiscoveredProvider>>>,
+}
+
+impl OidcService {
+    pub fn new(
+        config: OidcConfig,
+        oidc_repository: OidcRepository,
+        user_repository: UserRepository,
+        auth_service: AuthService,
+    ) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .expect("Failed to build OIDC HTTP client");
+
+        Self {
+            providers: Arc::new(config.providers),
+            oidc_repository,
+            user_repository,
+            auth_service,
+            http,
+            discovered: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn providers(&self) -> Vec<OidcProvider> {
+        self.providers
+            .iter()
+            .map(|p| OidcProvider { name: p.name.clone(), display_name: p.display_name.clone() })
+            .collect()
+    }
+
+    /// Start a sign-in: the returned URL sends the browser to the provider
+    pub async fn authorize(&self, provider: &str) -> Result<OidcAuthorization> {
+        let cfg = self.provider(provider)?;
+        let discovered = self.discover(cfg, false).await?;
+
+        let state = generate_token();
+        let request = OidcAuthRequest {
+            provider: cfg.name.clone(),
+            nonce: generate_token(),
+            code_verifier: generate_token(),
+        };
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(request.code_verifier.as_bytes()));
+
+        let authorization_url = reqwest::Url::parse_with_params(
+            &discovered.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", cfg.client_id.as_str()),
+                ("redirect_uri", cfg.redirect_uri.as_str()),
+                ("scope", cfg.scopes.join(" ").as_str()),
+                ("state", state.as_str()),
+                ("nonce", request.nonce.as_str()),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| ApiError::internal_error(format!("Invalid authorization endpoint for {}: {}", cfg.name, e)))?;
+
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(AUTH_REQUEST_MINUTES);
+        self.oidc_repository.create_auth_request(&hash_token(&state), &request, expires_at).await?;
+
+        Ok(OidcAuthorization { authorization_url: authorization_url.into(), state })
+    }
+
+    /// Finish a sign-in with what the provider redirected back
+    pub async fn callback(&self, provider: &str, req: OidcCallbackRequest, client: &ClientInfo) -> Result<LoginResponse> {
+        let cfg = self.provider(provider)?;
+        let pending = self.oidc_repository
+            .take_auth_request(&hash_token(req.state.trim()), &cfg.name)
+            .await?
+            .ok_or_else(|| ApiError::unauthorized("Sign-in request is invalid or has expired"))?;
+
+        let discovered = self.discover(cfg, false).await?;
+        let id_token = self.exchange_code(cfg, &discovered.metadata, &req.code, &pending.code_verifier).await?;
+        let claims = self.validate_id_token(cfg, &id_token, &pending.nonce).await?;
+
+        let user = self.resolve_user(cfg, &claims).await?;
+        info!("User {} signed in with {}", user.id, cfg.name);
+        self.auth_service.sign_in(&user, client).await
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProviderConfig> {
+        self.providers
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| ApiError::not_found(format!("Unknown sign-in provider: {}", name)))
+    }
+
+    async fn exchange_code(
+        &self,
+        cfg: &OidcProviderConfig,
+        metadata: &ProviderMetadata,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String> {
+        let form = [
+            ("grant_type", "authorization_code"),
+            ("code", code.trim()),
+            ("redirect_uri", cfg.redirect_uri.as_str()),
+            ("client_id", cfg.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        let mut request = self.http.post(&metadata.token_endpoint).form(&form);
+        if let Some(secret) = &cfg.client_secret {
+            request = request.basic_auth(&cfg.client_id, Some(secret));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Token request to {} failed: {}", cfg.name, e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            warn!("Token endpoint of {} refused the code ({}): {}", cfg.name, status, body);
+            return Err(ApiError::unauthorized(format!("Sign-in with {} failed", cfg.display_name)));
+        }
+
+        response
+            .json::<TokenEndpointResponse>()
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Invalid token response from {}: {}", cfg.name, e)))?
+            .id_token
+            .ok_or_else(|| ApiError::unauthorized(format!("{} did not return an ID token", cfg.display_name)))
+    }
+
+    async fn validate_id_token(&self, cfg: &OidcProviderConfig, id_token: &str, nonce: &str) -> Result<IdTokenClaims> {
+        let invalid = |reason: String| {
+            warn!("Rejected ID token from {}: {}", cfg.name, reason);
+            ApiError::unauthorized(format!("Sign-in with {} failed", cfg.display_name))
+        };
+
+        let header = decode_header(id_token).map_err(|e| invalid(e.to_string()))?;
+        // Asymmetric algorithms only; "none" never parses and HMAC would
+        // make the client secret a signing key
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(invalid(format!("unsupported algorithm {:?}", header.alg)));
+        }
+
+        let mut discovered = self.discover(cfg, false).await?;
+        if find_jwk(&discovered.jwks, header.kid.as_deref()).is_none()
+            && discovered.fetched_at.elapsed() >= JWKS_REFRESH_MIN_INTERVAL
+        {
+            discovered = self.discover(cfg, true).await?;
+        }
+        let jwk = find_jwk(&discovered.jwks, header.kid.as_deref())
+            .ok_or_else(|| invalid(format!("unknown key {:?}", header.kid)))?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|e| invalid(e.to_string()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&discovered.metadata.issuer]);
+        validation.set_audience(&[&cfg.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| invalid(e.to_string()))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid("nonce mismatch".to_string()));
+        }
+
+        Ok(claims)
+    }
+
+    /// The linked user, else the user with the same verified email, else a
+    /// new user. A local account whose email was never verified is not
+    /// linked: whoever registered it may not own the address, and would keep
+    /// its password once the provider vouched for it.
+    async fn resolve_user(&self, cfg: &OidcProviderConfig, claims: &IdTokenClaims) -> Result<User> {
+        let email = claims.email.as_deref().map(|e| e.trim().to_lowercase());
+
+        if let Some(user_id) = self.oidc_repository.touch_identity(&cfg.name, &claims.sub, email.as_deref()).await? {
+            return self.user_repository.find_by_id(user_id).await;
+        }
+
+        let email = email
+            .filter(|_| cfg.trust_email || claims.has_verified_email())
+            .ok_or_else(|| ApiError::forbidden(format!("{} did not share a verified email address", cfg.display_name)))?;
+
+        let user = match self.user_repository.find_by_email(&email).await? {
+            Some(user) if !user.is_email_verified() => {
+                warn!("Not linking {} account to user {} with an unverified email", cfg.name, user.id);
+                return Err(ApiError::conflict(format!(
+                    "An account with this email exists but the address was never verified. \
+                     Verify it or sign in with its password before using {}",
+                    cfg.display_name
+                )));
+            }
+            Some(user) => {
+                info!("Linking {} account to existing user {}", cfg.name, user.id);
+                user
+            }
+            None => {
+                let name = claims.name
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|n| (2..=100).contains(&n.chars().count()))
+                    .map(str::to_string)
+                    .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+                // Nobody knows this password; a reset sets a real one
+                let password_hash = hash_password(&generate_token())?;
+                let user = self.user_repository.create_with_password_hash(name, email.clone(), password_hash).await?;
+                // The provider has just vouched for the address
+                self.user_repository.mark_email_verified(user.id).await?;
+                info!("Created user {} from {} account", user.id, cfg.name);
+                user
+            }
+        };
+
+        self.oidc_repository.link_identity(&cfg.name, &claims.sub, user.id, &email).await?;
+
+        self.user_repository.find_by_id(user.id).await
+    }
+
+    /// Discovery document and keys, cached for `DISCOVERY_TTL`
+    async fn discover(&self, cfg: &OidcProviderConfig, refresh: bool) -> Result<DiscoveredProvider> {
+        if !refresh
+            && let Some(discovered) = self.discovered.read().await.get(&cfg.name)
+            && discovered.fetched_at.elapsed() < DISCOVERY_TTL
+        {
+            return Ok(discovered.clone());
+        }
+
+        let metadata: ProviderMetadata = self
+            .get_json(cfg, &format!("{}/.well-known/openid-configuration", cfg.issuer))
+            .await?;
+        if metadata.issuer.trim_end_matches('/') != cfg.issuer {
+            return Err(ApiError::internal_error(format!(
+                "Provider {} reports issuer {}, expected {}",
+                cfg.name, metadata.issuer, cfg.issuer
+            )));
+        }
+        let jwks: JwkSet = self.get_json(cfg, &metadata.jwks_uri).await?;
+
+        let discovered = DiscoveredProvider { metadata, jwks, fetched_at: Instant::now() };
+        self.discovered.write().await.insert(cfg.name.clone(), discovered.clone());
+        Ok(discovered)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, cfg: &OidcProviderConfig, url: &str) -> Result<T> {
+        let fetch_error = |e: reqwest::Error| ApiError::internal_error(format!("Fetching {} for {} failed: {}", url, cfg.name, e));
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(fetch_error)?
+            .json::<T>()
+            .await
+            .map_err(fetch_error)
+    }
+}
+
+/// The key named by `kid`; without one, the provider's only key
+fn find_jwk<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
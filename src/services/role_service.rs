@@ -0,0 +1,127 @@
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::{Role, CreateRoleRequest, UpdateRoleRequest, AssignRoleRequest, SetMfaRequirementRequest, User, Result, ApiError};
+use crate::repositories::{RoleRepository, UserRepository};
+use crate::services::SessionService;
+
+const MAX_ROLE_NAME_LENGTH: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct RoleService {
+    role_repository: RoleRepository,
+    user_repository: UserRepository,
+    session_service: SessionService,
+}
+
+impl RoleService {
+    pub fn new(role_repository: RoleRepository, user_repository: UserRepository, session_service: SessionService) -> Self {
+        Self { role_repository, user_repository, session_service }
+    }
+
+    pub async fn get_roles(&self) -> Result<Vec<Role>> {
+        self.role_repository.find_all().await
+    }
+
+    pub async fn get_role(&self, id: Uuid) -> Result<Role> {
+        self.role_repository.find_by_id(id).await
+    }
+
+    pub async fn create_role(&self, request: CreateRoleRequest) -> Result<Role> {
+        let name = request.name.trim().to_lowercase();
+        Self::validate_role_name(&name)?;
+
+        if self.role_repository.find_by_name(&name).await?.is_some() {
+            return Err(ApiError::conflict(format!("Role already exists: {}", name)));
+        }
+
+        let role = self.role_repository
+            .create(&name, request.description.trim(), &request.permissions)
+            .await?;
+
+        info!("Role {} created with permissions {:?}", role.name, role.permissions);
+        Ok(role)
+    }
+
+    /// Change a role's description or permissions. Access tokens carry the
+    /// permissions they were issued with, so a permission change signs every
+    /// holder of the role out.
+    pub async fn update_role(&self, id: Uuid, request: UpdateRoleRequest) -> Result<Role> {
+        let role = self.role_repository.find_by_id(id).await?;
+        if role.built_in {
+            return Err(ApiError::bad_request(format!("Built-in role {} cannot be changed", role.name)));
+        }
+
+        let role = self.role_repository
+            .update(id, request.description.as_deref().map(str::trim), request.permissions.as_deref())
+            .await?;
+
+        info!("Role {} updated, permissions {:?}", role.name, role.permissions);
+
+        if request.permissions.is_some() {
+            for user_id in self.role_repository.find_user_ids(id).await? {
+                self.session_service.revoke_all(user_id).await?;
+            }
+        }
+        Ok(role)
+    }
+
+    /// Make MFA mandatory (or optional) for a role's members. Unlike other
+    /// changes this is allowed on built-in roles, so admins can require it.
+    pub async fn set_mfa_requirement(&self, id: Uuid, request: SetMfaRequirementRequest) -> Result<Role> {
+        let role = self.role_repository.set_require_mfa(id, request.required).await?;
+        info!("Role {} MFA requirement set to {}", role.name, role.require_mfa);
+        Ok(role)
+    }
+
+    pub async fn delete_role(&self, id: Uuid) -> Result<()> {
+        let role = self.role_repository.find_by_id(id).await?;
+        if role.built_in {
+            return Err(ApiError::bad_request(format!("Built-in role {} cannot be deleted", role.name)));
+        }
+
+        let holders = self.role_repository.count_users(id).await?;
+        if holders > 0 {
+            return Err(ApiError::conflict(format!("Role {} is still assigned to {} users", role.name, holders)));
+        }
+
+        self.role_repository.delete(id).await?;
+        info!("Role {} deleted", role.name);
+        Ok(())
+    }
+
+    /// Put a user on a role. A change signs them out everywhere, since their
+    /// access tokens carry the old role's permissions.
+    pub async fn assign_role(&self, user_id: Uuid, request: AssignRoleRequest) -> Result<User> {
+        let name = request.role.trim().to_lowercase();
+        let role = self.role_repository
+            .find_by_name(&name)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Role not found: {}", name)))?;
+
+        let previous = self.user_repository.find_by_id(user_id).await?;
+        let user = self.user_repository.set_role(user_id, role.id).await?;
+        info!("User {} assigned role {}", user_id, role.name);
+
+        if previous.role != user.role {
+            self.session_service.revoke_all(user_id).await?;
+        }
+        Ok(user)
+    }
+
+    fn validate_role_name(name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(ApiError::ValidationError("Role name cannot be empty".to_string()));
+        }
+
+        if name.len() > MAX_ROLE_NAME_LENGTH {
+            return Err(ApiError::ValidationError(format!("Role name cannot exceed {} characters", MAX_ROLE_NAME_LENGTH)));
+        }
+
+        if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
+            return Err(ApiError::ValidationError("Role name may only contain letters, digits, '-' and '_'".to_string()));
+        }
+
+        Ok(())
+    }
+}
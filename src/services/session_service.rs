@@ -0,0 +1,148 @@
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::cache::{session_key, RedisCache};
+use crate::config::settings::AuthConfig;
+use crate::domain::{ApiError, ClientInfo, Result, Session};
+use crate::repositories::SessionRepository;
+use crate::security::{generate_token, hash_token};
+
+/// How long an active session is trusted from Redis before the database is
+/// asked again; also how stale `last_seen_at` may get
+const ACTIVE_CACHE_SECS: u64 = 60;
+
+/// One row per login. Access tokens name their session in `sid`, and
+/// `auth_middleware` asks `is_active` on every request; the answer is cached
+/// in Redis so only a miss reaches the database. Revoking overwrites the
+/// cached answer, while a miss only fills an empty slot, so a check racing a
+/// revoke cannot cache the session as active again. Without Redis, or when
+/// Redis errors, every check goes to the database.
+#[derive(Debug, Clone)]
+pub struct SessionService {
+    session_repository: SessionRepository,
+    cache: Option<RedisCache>,
+    session_ttl_days: u64,
+    /// A revoked session is remembered as long as its access tokens live
+    access_token_secs: u64,
+}
+
+impl SessionService {
+    pub fn new(session_repository: SessionRepository, cache: Option<RedisCache>, cfg: &AuthConfig) -> Self {
+        Self {
+            session_repository,
+            cache,
+            session_ttl_days: cfg.session_ttl_days,
+            access_token_secs: cfg.expiry_minutes.saturating_mul(60),
+        }
+    }
+
+    /// Record a login; returns the session and its refresh token
+    pub async fn start(&self, user_id: Uuid, client: &ClientInfo) -> Result<(Uuid, String)> {
+        let refresh_token = generate_token();
+        let ip_address = client.ip.map(|ip| ip.to_string());
+        let session_id = self.session_repository
+            .create(
+                user_id,
+                client.user_agent.as_deref(),
+                ip_address.as_deref(),
+                &hash_token(&refresh_token),
+                self.expires_at(),
+            )
+            .await?;
+
+        info!("Session {} started for user {}", session_id, user_id);
+        Ok((session_id, refresh_token))
+    }
+
+    /// Trade a refresh token for a new one. Returns the session, its user and
+    /// the new token; the old token stops working.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<(Uuid, Uuid, String)> {
+        let new_refresh_token = generate_token();
+        let (session_id, user_id) = self.session_repository
+            .rotate_refresh_token(&hash_token(refresh_token.trim()), &hash_token(&new_refresh_token), self.expires_at())
+            .await?
+            .ok_or_else(|| ApiError::unauthorized("Invalid or expired refresh token"))?;
+
+        Ok((session_id, user_id, new_refresh_token))
+    }
+
+    /// Whether tokens of the session are still accepted
+    pub async fn is_active(&self, session_id: Uuid) -> Result<bool> {
+        let key = session_key(&session_id);
+        if let Some(cache) = &self.cache {
+            match cache.get_json::<bool>(&key).await {
+                Ok(Some(active)) => return Ok(active),
+                Ok(None) => {}
+                Err(e) => warn!("Session cache lookup for {} failed: {}", session_id, e),
+            }
+        }
+
+        let active = self.session_repository.touch_if_active(session_id).await?;
+        if active {
+            self.cache_active(session_id).await;
+        } else {
+            self.cache_revoked(session_id).await;
+        }
+        Ok(active)
+    }
+
+    /// The user's live sessions, most recently used first
+    pub async fn list(&self, user_id: Uuid, current: Option<Uuid>) -> Result<Vec<Session>> {
+        let mut sessions = self.session_repository.find_active_by_user(user_id).await?;
+        for session in &mut sessions {
+            session.current = Some(session.id) == current;
+        }
+        Ok(sessions)
+    }
+
+    pub async fn revoke(&self, user_id: Uuid, session_id: Uuid) -> Result<()> {
+        if !self.session_repository.revoke(user_id, session_id).await? {
+            return Err(ApiError::not_found(format!("Session with id {} not found", session_id)));
+        }
+
+        self.cache_revoked(session_id).await;
+        info!("Session {} of user {} revoked", session_id, user_id);
+        Ok(())
+    }
+
+    /// Sign the user out everywhere
+    pub async fn revoke_all(&self, user_id: Uuid) -> Result<()> {
+        let session_ids = self.session_repository.revoke_all(user_id).await?;
+        for session_id in &session_ids {
+            self.cache_revoked(*session_id).await;
+        }
+
+        if !session_ids.is_empty() {
+            info!("{} sessions of user {} revoked", session_ids.len(), user_id);
+        }
+        Ok(())
+    }
+
+    /// Remember a session the database just found active, unless a revoke
+    /// has written its state in the meantime
+    async fn cache_active(&self, session_id: Uuid) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+
+        if let Err(e) = cache.set_json_if_absent_with_ttl(&session_key(&session_id), &true, ACTIVE_CACHE_SECS).await {
+            warn!("Session cache update for {} failed: {}", session_id, e);
+        }
+    }
+
+    /// A revoked session never comes back, so this overwrites whatever is cached
+    async fn cache_revoked(&self, session_id: Uuid) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+
+        let ttl_secs = self.access_token_secs.max(1);
+        if let Err(e) = cache.set_json_with_ttl(&session_key(&session_id), &false, ttl_secs).await {
+            warn!("Session cache update for {} failed: {}", session_id, e);
+        }
+    }
+
+    fn expires_at(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now() + chrono::Duration::days(self.session_ttl_days as i64)
+    }
+}
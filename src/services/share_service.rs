@@ -0,0 +1,161 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+use tracing::{info, debug};
+
+use crate::domain::{
+    ShareLink, ShareResourceType, CreateShareLinkRequest, CreatedShareLink, PublicShareView, Result, ApiError,
+};
+use crate::markdown::render_markdown;
+use crate::repositories::{ShareRepository, CreateShareLinkInternal, TaskRepository, NoteRepository};
+use crate::security::{generate_token, hash_password, hash_token, verify_password};
+use crate::services::LockoutService;
+
+const TOKEN_HINT_LENGTH: usize = 6;
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct ShareService {
+    share_repository: ShareRepository,
+    task_repository: TaskRepository,
+    note_repository: NoteRepository,
+    /// Throttles wrong passwords per link and per client address
+    lockout: LockoutService,
+}
+
+impl ShareService {
+    pub fn new(
+        share_repository: ShareRepository,
+        task_repository: TaskRepository,
+        note_repository: NoteRepository,
+        lockout: LockoutService,
+    ) -> Self {
+        Self { share_repository, task_repository, note_repository, lockout }
+    }
+
+    /// Owner of the shared task or note; callers check it before creating a link
+    pub async fn resource_owner(&self, resource_type: ShareResourceType, resource_id: Uuid) -> Result<Uuid> {
+        match resource_type {
+            ShareResourceType::Task => Ok(self.task_repository.find_by_id(resource_id).await?.user_id),
+            ShareResourceType::Note => Ok(self.note_repository.find_by_id(resource_id).await?.user_id),
+        }
+    }
+
+    pub async fn create_link(&self, request: CreateShareLinkRequest, user_id: Uuid) -> Result<CreatedShareLink> {
+        if let Some(expires_at) = request.expires_at
+            && expires_at <= chrono::Utc::now()
+        {
+            return Err(ApiError::ValidationError("Expiry must be in the future".to_string()));
+        }
+
+        let password_hash = match request.password.as_deref() {
+            Some(password) => {
+                if password.is_empty() || password.len() > MAX_PASSWORD_LENGTH {
+                    return Err(ApiError::ValidationError(format!(
+                        "Share password must be between 1 and {} characters",
+                        MAX_PASSWORD_LENGTH
+                    )));
+                }
+                Some(hash_password(password)?)
+            }
+            None => None,
+        };
+
+        let token = generate_token();
+        let link = self
+            .share_repository
+            .create(CreateShareLinkInternal {
+                user_id,
+                resource_type: request.resource_type,
+                resource_id: request.resource_id,
+                token_hash: hash_token(&token),
+                token_hint: token[..TOKEN_HINT_LENGTH].to_string(),
+                password_hash,
+                expires_at: request.expires_at,
+            })
+            .await?;
+
+        info!("Share link {} created for {:?} {}", link.id, link.resource_type, link.resource_id);
+        Ok(CreatedShareLink {
+            path: format!("/api/v1/public/shares/{}", token),
+            link,
+            token,
+        })
+    }
+
+    pub async fn get_link(&self, id: Uuid) -> Result<ShareLink> {
+        self.share_repository.find_by_id(id).await
+    }
+
+    pub async fn get_links_by_user(&self, user_id: Uuid) -> Result<Vec<ShareLink>> {
+        self.share_repository.find_by_user_id(user_id).await
+    }
+
+    pub async fn revoke_link(&self, id: Uuid) -> Result<()> {
+        self.share_repository.delete(id).await?;
+        info!("Share link revoked: {}", id);
+        Ok(())
+    }
+
+    /// Resolve a public token into the limited view of what it shares.
+    /// Unknown, expired and dangling links all look the same to visitors;
+    /// repeated wrong passwords lock the link and the client address out.
+    pub async fn open_link(&self, token: &str, password: Option<&str>, ip: Option<IpAddr>) -> Result<PublicShareView> {
+        let not_found = || ApiError::not_found("Share link not found or expired");
+
+        let link = self
+            .share_repository
+            .find_by_token_hash(&hash_token(token))
+            .await?
+            .ok_or_else(not_found)?;
+
+        if link.expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now()) {
+            debug!("Share link {} expired", link.id);
+            return Err(not_found());
+        }
+
+        if let Some(stored_hash) = &link.password_hash {
+            let password = password.ok_or_else(|| ApiError::Unauthorized("This share link requires a password".to_string()))?;
+            self.lockout.check_share(link.id, ip).await?;
+            if !verify_password(password, stored_hash)? {
+                self.lockout.record_share_failure(link.id, ip).await;
+                return Err(ApiError::Unauthorized("Invalid share password".to_string()));
+            }
+        }
+
+        let view = match link.resource_type {
+            ShareResourceType::Task => {
+                let task = match self.task_repository.find_by_id(link.resource_id).await {
+                    Err(ApiError::TaskNotFound { .. }) => return Err(not_found()),
+                    result => result?,
+                };
+                PublicShareView::Task {
+                    description_html: task.description.as_deref().map(render_markdown),
+                    title: task.title,
+                    description: task.description,
+                    status: task.status,
+                    priority: task.priority,
+                    due_at: task.due_at,
+                    checklist: task.checklist,
+                    tags: task.tags,
+                    updated_at: task.updated_at,
+                }
+            }
+            ShareResourceType::Note => {
+                let note = match self.note_repository.find_by_id(link.resource_id).await {
+                    Err(ApiError::NotFound(_)) => return Err(not_found()),
+                    result => result?,
+                };
+                PublicShareView::Note {
+                    body_html: render_markdown(&note.body),
+                    title: note.title,
+                    body: note.body,
+                    updated_at: note.updated_at,
+                }
+            }
+        };
+
+        self.share_repository.record_access(link.id).await?;
+        Ok(view)
+    }
+}
@@ -1,55 +1,369 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use tracing::{info, debug};
 
-use crate::domain::{Task, CreateTaskRequest, Result, ApiError, TaskQueryParams, PaginatedResponse};
-use crate::repositories::{TaskRepository, UserRepository, CreateTaskRequestInternal};
-use crate::cache::{RedisCache, task_key, user_tasks_key, all_tasks_key};
+use crate::domain::{
+    Task, TaskTransfer, RenderedTask, CreateTaskRequest, UpdateTaskRequest, ChecklistItem, Result, ApiError, TaskQueryParams, PaginatedResponse,
+    TaskTemplate, CreateTaskTemplateRequest, InstantiateTemplateRequest, TaskShare, ShareTaskRequest,
+};
+use crate::authz::{authorize, Action, Actor, Resource};
+use crate::domain::task::{build_checklist, normalize_tags, DuplicateTaskRequest, TaskStatus};
+use crate::domain::template::expand_placeholders;
+use crate::parser::{parse_quick_add, set_task_item_checked, QuickAddParse};
+use crate::repositories::{
+    TaskRepository, UserRepository, TemplateRepository, NoteRepository, CreateTaskRequestInternal, UpdateTaskRequestInternal,
+};
+use crate::cache::{RedisCache, task_key, task_html_key, user_tasks_key, all_tasks_key};
+use crate::markdown::render_markdown;
+
+const MAX_CHECKLIST_ITEMS: usize = 100;
+const MAX_TAGS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickAddRequest {
+    pub text: String,
+    /// IANA timezone overriding the user's saved timezone for this request
+    pub timezone: Option<String>,
+    /// Only parse the text, do not create a task
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickAddResponse {
+    pub parsed: QuickAddParse,
+    pub assignee_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+}
+
+/// Result of `POST /tasks/:id/transfer`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransferOutcome {
+    /// Waiting for the recipient to accept
+    Pending { transfer: TaskTransfer },
+    Completed { task: Task },
+}
+
+/// Rendered description cached next to the task, keyed by the task version it was built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedTaskHtml {
+    updated_at: chrono::DateTime<chrono::Utc>,
+    html: String,
+}
+
+/// Result of resolving a slug within one owner's tasks
+#[derive(Debug, Clone)]
+pub enum SlugLookup {
+    Found(Task),
+    /// The slug was retired by a rename; the task now lives at its new slug
+    Moved(Task),
+}
 
 #[derive(Debug, Clone)]
 pub struct TaskService {
     task_repository: TaskRepository,
     user_repository: UserRepository,
+    template_repository: TemplateRepository,
+    note_repository: NoteRepository,
     cache: Option<RedisCache>,
+    /// Refuse new tasks for accounts that haven't verified their email
+    require_verified_email: bool,
 }
 
 impl TaskService {
-    pub fn new(task_repository: TaskRepository, user_repository: UserRepository, cache: Option<RedisCache>) -> Self {
+    pub fn new(
+        task_repository: TaskRepository,
+        user_repository: UserRepository,
+        template_repository: TemplateRepository,
+        note_repository: NoteRepository,
+        cache: Option<RedisCache>,
+        require_verified_email: bool,
+    ) -> Self {
         Self {
             task_repository,
             user_repository,
+            template_repository,
+            note_repository,
             cache,
+            require_verified_email,
         }
     }
 
     pub async fn create_task(&self, request: CreateTaskRequest, user_id: Uuid) -> Result<Task> {
-        // Business logic validation
-        self.validate_task_request(&request)?;
-        
-        // Verify user exists
-        if !self.user_repository.exists(user_id).await {
-            return Err(ApiError::UserNotFound {
-                id: user_id,
-            });
+        let internal_request = self.prepare_create(request, user_id).await?;
+
+        // Delegate to repository
+        let task = self.task_repository.create(internal_request).await?;
+
+        self.refresh_task_cache(&task).await;
+
+        Ok(task)
+    }
+
+    /// Create a task for a note's task-list item, linked to it in the same
+    /// transaction; `done` creates it completed. `None` when the item already
+    /// has a task.
+    pub async fn create_task_for_note_item(
+        &self,
+        request: CreateTaskRequest,
+        user_id: Uuid,
+        note_id: Uuid,
+        item_text: &str,
+        done: bool,
+    ) -> Result<Option<Task>> {
+        let internal_request = self.prepare_create(request, user_id).await?;
+        let status = if done { TaskStatus::Done } else { TaskStatus::Todo };
+
+        let task = self.task_repository
+            .create_for_note_item(internal_request, status, note_id, item_text)
+            .await?;
+        if let Some(task) = &task {
+            self.refresh_task_cache(task).await;
         }
+        Ok(task)
+    }
 
-        // Create internal request with user_id
-        let internal_request = CreateTaskRequestInternal {
-            title: request.title,
-            description: request.description,
-            user_id,
+    /// Parse a quick-add line and, unless `dry_run` is set, create the task it describes
+    pub async fn quick_add(&self, request: QuickAddRequest, user_id: Uuid) -> Result<QuickAddResponse> {
+        let user = self.user_repository.find_by_id(user_id).await?;
+        let timezone = request.timezone.as_deref().unwrap_or(&user.timezone);
+        let tz: Tz = timezone
+            .parse()
+            .map_err(|_| ApiError::bad_request(format!("Unknown timezone: {}", timezone)))?;
+
+        let parsed = parse_quick_add(&request.text, chrono::Utc::now(), tz);
+        debug!("Quick-add parse result: {:?}", parsed);
+
+        let assignee_id = match &parsed.assignee {
+            Some(handle) => self.user_repository.find_by_handle(user_id, handle).await?.map(|u| u.id),
+            None => None,
         };
 
-        // Delegate to repository
-        let task = self.task_repository.create(internal_request).await?;
+        if request.dry_run {
+            return Ok(QuickAddResponse { parsed, assignee_id, task: None });
+        }
 
-        // Invalidate caches related to tasks
-        if let Some(cache) = &self.cache {
-            let _ = cache.del(&all_tasks_key()).await;
-            let _ = cache.del(&user_tasks_key(&task.user_id)).await;
-            let _ = cache.set_json(&task_key(&task.id), &task).await;
+        if let Some(handle) = &parsed.assignee
+            && assignee_id.is_none()
+        {
+            return Err(ApiError::bad_request(format!("Unknown assignee @{}", handle)));
         }
 
-        Ok(task)
+        let create_request = CreateTaskRequest {
+            title: parsed.title.clone(),
+            description: None,
+            checklist: Vec::new(),
+            tags: parsed.tags.clone(),
+            priority: parsed.priority,
+            due_at: parsed.due_at,
+            assignee_id,
+            parent_id: None,
+        };
+        let task = self.create_task(create_request, user_id).await?;
+
+        Ok(QuickAddResponse { parsed, assignee_id, task: Some(task) })
+    }
+
+    /// Copy a task for its owner. The copy starts as `todo` with an unchecked
+    /// checklist; subtasks are copied recursively when requested.
+    pub async fn duplicate_task(&self, source: &Task, options: DuplicateTaskRequest) -> Result<Task> {
+        let title = match &options.title {
+            Some(title) => {
+                if title.trim().is_empty() {
+                    return Err(ApiError::ValidationError("Title cannot be empty".to_string()));
+                }
+                if title.len() > 200 {
+                    return Err(ApiError::ValidationError("Title cannot exceed 200 characters".to_string()));
+                }
+                title.trim().to_string()
+            }
+            None => source.title.clone(),
+        };
+
+        // The copies belong to the source task's owner
+        self.ensure_email_verified(source.user_id).await?;
+
+        let (copy, subtasks) = self.task_repository
+            .duplicate(source, options.include_subtasks, |task, parent_id| {
+                let title = if task.id == source.id { title.clone() } else { task.title.clone() };
+                Self::copy_request(task, title, &options, parent_id)
+            })
+            .await?;
+        for task in std::iter::once(&copy).chain(&subtasks) {
+            self.refresh_task_cache(task).await;
+        }
+
+        info!("Task {} duplicated as {}", source.id, copy.id);
+        Ok(copy)
+    }
+
+    /// Ask `to_user_id` to take over a task; nothing moves until they accept
+    pub async fn request_transfer(&self, task: &Task, to_user_id: Uuid) -> Result<TaskTransfer> {
+        self.validate_transfer_target(task, to_user_id).await?;
+
+        let transfer = self.task_repository
+            .upsert_transfer_request(task.id, task.user_id, to_user_id)
+            .await?;
+
+        info!("Transfer of task {} from {} to {} requested", task.id, task.user_id, to_user_id);
+        Ok(transfer)
+    }
+
+    /// Move a task (and its subtasks) to a new owner straight away
+    pub async fn transfer_task(&self, task: &Task, to_user_id: Uuid) -> Result<Task> {
+        self.validate_transfer_target(task, to_user_id).await?;
+
+        let moved = self.task_repository.transfer_owner(task.id, to_user_id).await?;
+        self.invalidate_transferred(task.user_id, to_user_id, &moved).await;
+
+        info!("Task {} transferred from {} to {} ({} tasks moved)", task.id, task.user_id, to_user_id, moved.len());
+        self.task_repository.find_by_id(task.id).await
+    }
+
+    /// Complete a pending transfer on behalf of its recipient
+    pub async fn accept_transfer(&self, task_id: Uuid, user_id: Uuid) -> Result<Task> {
+        let transfer = self.task_repository
+            .find_transfer_request(task_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("No pending transfer for task {}", task_id)))?;
+
+        if transfer.to_user_id != user_id {
+            return Err(ApiError::forbidden("Only the recipient can accept this transfer"));
+        }
+
+        // Claimed atomically: a concurrent accept or cancel leaves nothing to claim
+        let (from_user_id, moved) = self.task_repository
+            .accept_transfer(task_id, user_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("No pending transfer for task {}", task_id)))?;
+        self.invalidate_transferred(from_user_id, user_id, &moved).await;
+
+        info!("Task {} transferred from {} to {} ({} tasks moved)", task_id, from_user_id, user_id, moved.len());
+        self.task_repository.find_by_id(task_id).await
+    }
+
+    pub async fn get_transfer_request(&self, task_id: Uuid) -> Result<Option<TaskTransfer>> {
+        self.task_repository.find_transfer_request(task_id).await
+    }
+
+    pub async fn cancel_transfer(&self, task_id: Uuid) -> Result<()> {
+        if !self.task_repository.delete_transfer_request(task_id).await? {
+            return Err(ApiError::not_found(format!("No pending transfer for task {}", task_id)));
+        }
+        Ok(())
+    }
+
+    /// Append an unchecked item at the end of the task's checklist
+    pub async fn add_checklist_item(&self, task_id: Uuid, text: String) -> Result<Task> {
+        Self::validate_checklist_text(&text)?;
+
+        self.modify_checklist(task_id, |items| {
+            if items.len() >= MAX_CHECKLIST_ITEMS {
+                return Err(ApiError::ValidationError(format!("A checklist cannot have more than {} items", MAX_CHECKLIST_ITEMS)));
+            }
+            items.push(ChecklistItem::new(text.trim().to_string(), items.len() as i32));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Flip the `checked` state of a checklist item
+    pub async fn toggle_checklist_item(&self, task_id: Uuid, item_id: Uuid) -> Result<Task> {
+        self.modify_checklist(task_id, |items| {
+            let item = items
+                .iter_mut()
+                .find(|item| item.id == item_id)
+                .ok_or_else(|| ApiError::not_found(format!("Checklist item not found: {}", item_id)))?;
+            item.checked = !item.checked;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn remove_checklist_item(&self, task_id: Uuid, item_id: Uuid) -> Result<Task> {
+        self.modify_checklist(task_id, |items| {
+            let before = items.len();
+            items.retain(|item| item.id != item_id);
+            if items.len() == before {
+                return Err(ApiError::not_found(format!("Checklist item not found: {}", item_id)));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Reorder the checklist; `item_ids` must list every existing item exactly once
+    pub async fn reorder_checklist(&self, task_id: Uuid, item_ids: Vec<Uuid>) -> Result<Task> {
+        self.modify_checklist(task_id, |items| {
+            let mut sorted_ids = item_ids.clone();
+            sorted_ids.sort();
+            sorted_ids.dedup();
+            if sorted_ids.len() != item_ids.len() || item_ids.len() != items.len()
+                || !items.iter().all(|item| item_ids.contains(&item.id))
+            {
+                return Err(ApiError::bad_request("item_ids must contain every checklist item exactly once"));
+            }
+
+            items.sort_by_key(|item| item_ids.iter().position(|id| *id == item.id));
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn create_template(&self, request: CreateTaskTemplateRequest, user_id: Uuid) -> Result<TaskTemplate> {
+        self.validate_template_request(&request)?;
+
+        let request = CreateTaskTemplateRequest {
+            name: request.name.trim().to_string(),
+            title_pattern: request.title_pattern.trim().to_string(),
+            description: request.description,
+            checklist: request.checklist.iter().map(|text| text.trim().to_string()).collect(),
+            default_tags: normalize_tags(&request.default_tags),
+        };
+        self.template_repository.create(request, user_id).await
+    }
+
+    pub async fn get_template(&self, id: Uuid) -> Result<TaskTemplate> {
+        self.template_repository.find_by_id(id).await
+    }
+
+    pub async fn get_templates_by_user(&self, user_id: Uuid) -> Result<Vec<TaskTemplate>> {
+        self.template_repository.find_by_user_id(user_id).await
+    }
+
+    pub async fn delete_template(&self, id: Uuid) -> Result<()> {
+        self.template_repository.delete(id).await
+    }
+
+    /// Create a task for `user_id` from a template, expanding `{{placeholders}}`
+    pub async fn create_task_from_template(
+        &self,
+        template: &TaskTemplate,
+        request: InstantiateTemplateRequest,
+        user_id: Uuid,
+    ) -> Result<Task> {
+        let now = chrono::Utc::now();
+        let expand = |input: &str| expand_placeholders(input, now, &request.variables);
+
+        let mut tags = template.default_tags.clone();
+        tags.extend(request.tags.iter().cloned());
+
+        let create_request = CreateTaskRequest {
+            title: expand(&template.title_pattern),
+            description: template.description.as_deref().map(expand),
+            checklist: template.checklist.iter().map(|text| expand(text)).collect(),
+            tags,
+            priority: None,
+            due_at: None,
+            assignee_id: None,
+            parent_id: None,
+        };
+
+        info!("Instantiating template {} for user {}", template.id, user_id);
+        self.create_task(create_request, user_id).await
     }
 
     pub async fn get_task(&self, id: Uuid) -> Result<Task> {
@@ -72,16 +386,133 @@ impl TaskService {
         Ok(task)
     }
 
+    /// Attach the sanitised HTML rendering of the task's description
+    pub async fn render_task(&self, task: Task) -> RenderedTask {
+        let Some(description) = task.description.as_deref() else {
+            return RenderedTask { task, description_html: None };
+        };
+
+        if let Some(cache) = &self.cache
+            && let Ok(Some(cached)) = cache.get_json::<CachedTaskHtml>(&task_html_key(&task.id)).await
+            && cached.updated_at == task.updated_at
+        {
+            debug!("Cache HIT for rendered task: {}", task.id);
+            return RenderedTask { task, description_html: Some(cached.html) };
+        }
+
+        let html = render_markdown(description);
+
+        if let Some(cache) = &self.cache {
+            let cached = CachedTaskHtml { updated_at: task.updated_at, html: html.clone() };
+            let _ = cache.set_json(&task_html_key(&task.id), &cached).await;
+        }
+        RenderedTask { task, description_html: Some(html) }
+    }
+
+    /// Resolve `slug` in `user_id`'s namespace, following rename redirects
+    pub async fn get_task_by_slug(&self, user_id: Uuid, slug: &str) -> Result<SlugLookup> {
+        if let Some(task) = self.task_repository.find_by_slug(user_id, slug).await? {
+            return Ok(SlugLookup::Found(task));
+        }
+
+        match self.task_repository.find_slug_redirect(user_id, slug).await? {
+            Some(task_id) => Ok(SlugLookup::Moved(self.get_task(task_id).await?)),
+            None => Err(ApiError::not_found(format!("Task not found for slug: {}", slug))),
+        }
+    }
+
+    /// Check `action` on a task against the authorization policy, looking
+    /// up the actor's share when they do not own it
+    pub async fn authorize(&self, task: &Task, actor: &Actor, action: Action) -> Result<()> {
+        let shared = if actor.id == task.user_id {
+            None
+        } else {
+            self.task_repository.find_share_permission(task.id, actor.id).await?
+        };
+
+        authorize(actor, action, &Resource::Task { owner_id: task.user_id, shared })
+    }
+
+    /// Grant (or change) another user's access to a task
+    pub async fn share_task(&self, task: &Task, request: ShareTaskRequest, granted_by: Uuid) -> Result<TaskShare> {
+        if request.user_id == task.user_id {
+            return Err(ApiError::bad_request("Task already belongs to this user"));
+        }
+
+        if !self.user_repository.exists(request.user_id).await {
+            return Err(ApiError::UserNotFound { id: request.user_id });
+        }
+
+        let share = self.task_repository
+            .upsert_share(task.id, request.user_id, request.permission, granted_by)
+            .await?;
+
+        info!("Task {} shared with {} ({:?})", task.id, request.user_id, request.permission);
+        Ok(share)
+    }
+
+    pub async fn get_task_shares(&self, task_id: Uuid) -> Result<Vec<TaskShare>> {
+        self.task_repository.find_shares(task_id).await
+    }
+
+    pub async fn revoke_task_share(&self, task_id: Uuid, user_id: Uuid) -> Result<()> {
+        if !self.task_repository.delete_share(task_id, user_id).await? {
+            return Err(ApiError::not_found(format!("Task {} is not shared with user {}", task_id, user_id)));
+        }
+
+        info!("Share of task {} with {} revoked", task_id, user_id);
+        Ok(())
+    }
+
+    /// Tasks other users have shared with `user_id`
+    pub async fn get_tasks_shared_with(&self, user_id: Uuid) -> Result<Vec<Task>> {
+        self.task_repository.find_shared_with(user_id).await
+    }
+
+    /// Update a task on behalf of `actor_id`. A new title gets a new slug and
+    /// the old one keeps redirecting to the task.
+    pub async fn update_task(&self, task: &Task, request: UpdateTaskRequest, actor_id: Uuid) -> Result<Task> {
+        self.validate_update_request(&request)?;
+
+        if let Some(Some(assignee_id)) = request.assignee_id
+            && !self.user_repository.exists(assignee_id).await
+        {
+            return Err(ApiError::UserNotFound { id: assignee_id });
+        }
+
+        let changes = UpdateTaskRequestInternal {
+            title: request.title.map(|title| title.trim().to_string()),
+            description: request.description,
+            status: request.status,
+            priority: request.priority,
+            due_at: request.due_at,
+            assignee_id: request.assignee_id,
+            tags: request.tags.map(|tags| normalize_tags(&tags)),
+        };
+
+        let updated = self.task_repository.update(task, changes).await?;
+        self.refresh_task_cache(&updated).await;
+
+        if is_done(&updated.status) != is_done(&task.status) {
+            self.sync_note_item(&updated, actor_id).await?;
+        }
+
+        if updated.slug != task.slug {
+            info!("Task {} renamed, slug {} -> {}", task.id, task.slug, updated.slug);
+        }
+        Ok(updated)
+    }
+
     pub async fn get_tasks_by_user(&self, user_id: Uuid) -> Result<Vec<Task>> {
         // Verify user exists
         if !self.user_repository.exists(user_id).await {
             return Err(ApiError::UserNotFound { id: user_id });
         }
 
-        if let Some(cache) = &self.cache {
-            if let Ok(Some(tasks)) = cache.get_json::<Vec<Task>>(&user_tasks_key(&user_id)).await {
-                return Ok(tasks);
-            }
+        if let Some(cache) = &self.cache
+            && let Ok(Some(tasks)) = cache.get_json::<Vec<Task>>(&user_tasks_key(&user_id)).await
+        {
+            return Ok(tasks);
         }
 
         let tasks = self.task_repository.find_by_user_id(user_id).await?;
@@ -92,10 +523,10 @@ impl TaskService {
     }
 
     pub async fn get_all_tasks(&self) -> Result<Vec<Task>> {
-        if let Some(cache) = &self.cache {
-            if let Ok(Some(tasks)) = cache.get_json::<Vec<Task>>(&all_tasks_key()).await {
-                return Ok(tasks);
-            }
+        if let Some(cache) = &self.cache
+            && let Ok(Some(tasks)) = cache.get_json::<Vec<Task>>(&all_tasks_key()).await
+        {
+            return Ok(tasks);
         }
 
         let tasks = self.task_repository.find_all().await?;
@@ -125,6 +556,48 @@ impl TaskService {
         Ok(result)
     }
 
+    /// Validate a create request and resolve it for `user_id`
+    async fn prepare_create(&self, request: CreateTaskRequest, user_id: Uuid) -> Result<CreateTaskRequestInternal> {
+        // Business logic validation
+        self.validate_task_request(&request)?;
+        
+        // Verify user exists
+        if !self.user_repository.exists(user_id).await {
+            return Err(ApiError::UserNotFound {
+                id: user_id,
+            });
+        }
+        self.ensure_email_verified(user_id).await?;
+
+        // Verify assignee exists
+        if let Some(assignee_id) = request.assignee_id
+            && !self.user_repository.exists(assignee_id).await
+        {
+            return Err(ApiError::UserNotFound { id: assignee_id });
+        }
+
+        // Subtasks must hang under a task of the same owner
+        if let Some(parent_id) = request.parent_id {
+            let parent = self.task_repository.find_by_id(parent_id).await?;
+            if parent.user_id != user_id {
+                return Err(ApiError::ValidationError("Parent task must belong to the same user".to_string()));
+            }
+        }
+
+        // Create internal request with user_id
+        Ok(CreateTaskRequestInternal {
+            title: request.title,
+            description: request.description,
+            user_id,
+            checklist: build_checklist(&request.checklist),
+            tags: normalize_tags(&request.tags),
+            priority: request.priority.unwrap_or_default(),
+            due_at: request.due_at,
+            assignee_id: request.assignee_id,
+            parent_id: request.parent_id,
+        })
+    }
+
     fn validate_task_request(&self, request: &CreateTaskRequest) -> Result<()> {
         if request.title.trim().is_empty() {
             return Err(ApiError::ValidationError("Title cannot be empty".to_string()));
@@ -134,12 +607,206 @@ impl TaskService {
             return Err(ApiError::ValidationError("Title cannot exceed 200 characters".to_string()));
         }
         
-        if let Some(desc) = &request.description {
-            if desc.len() > 1000 {
-                return Err(ApiError::ValidationError("Description cannot exceed 1000 characters".to_string()));
+        if let Some(desc) = &request.description
+            && desc.len() > 1000
+        {
+            return Err(ApiError::ValidationError("Description cannot exceed 1000 characters".to_string()));
+        }
+
+        if request.checklist.len() > MAX_CHECKLIST_ITEMS {
+            return Err(ApiError::ValidationError(format!("A checklist cannot have more than {} items", MAX_CHECKLIST_ITEMS)));
+        }
+        for text in &request.checklist {
+            Self::validate_checklist_text(text)?;
+        }
+
+        Self::validate_tags(&request.tags)?;
+
+        Ok(())
+    }
+
+    fn validate_update_request(&self, request: &UpdateTaskRequest) -> Result<()> {
+        if let Some(title) = &request.title {
+            if title.trim().is_empty() {
+                return Err(ApiError::ValidationError("Title cannot be empty".to_string()));
+            }
+            if title.len() > 200 {
+                return Err(ApiError::ValidationError("Title cannot exceed 200 characters".to_string()));
             }
         }
 
+        if let Some(desc) = &request.description
+            && desc.len() > 1000
+        {
+            return Err(ApiError::ValidationError("Description cannot exceed 1000 characters".to_string()));
+        }
+
+        if let Some(tags) = &request.tags {
+            Self::validate_tags(tags)?;
+        }
+
         Ok(())
     }
+
+    fn validate_template_request(&self, request: &CreateTaskTemplateRequest) -> Result<()> {
+        if request.name.trim().is_empty() {
+            return Err(ApiError::ValidationError("Template name cannot be empty".to_string()));
+        }
+
+        if request.name.len() > 100 {
+            return Err(ApiError::ValidationError("Template name cannot exceed 100 characters".to_string()));
+        }
+
+        if request.title_pattern.trim().is_empty() {
+            return Err(ApiError::ValidationError("Title pattern cannot be empty".to_string()));
+        }
+
+        if request.title_pattern.len() > 200 {
+            return Err(ApiError::ValidationError("Title pattern cannot exceed 200 characters".to_string()));
+        }
+
+        if request.checklist.len() > MAX_CHECKLIST_ITEMS {
+            return Err(ApiError::ValidationError(format!("A checklist cannot have more than {} items", MAX_CHECKLIST_ITEMS)));
+        }
+        for text in &request.checklist {
+            Self::validate_checklist_text(text)?;
+        }
+
+        Self::validate_tags(&request.default_tags)
+    }
+
+    fn validate_checklist_text(text: &str) -> Result<()> {
+        if text.trim().is_empty() {
+            return Err(ApiError::ValidationError("Checklist item text cannot be empty".to_string()));
+        }
+
+        if text.len() > 500 {
+            return Err(ApiError::ValidationError("Checklist item text cannot exceed 500 characters".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn validate_tags(tags: &[String]) -> Result<()> {
+        if tags.len() > MAX_TAGS {
+            return Err(ApiError::ValidationError(format!("A task cannot have more than {} tags", MAX_TAGS)));
+        }
+
+        if tags.iter().any(|tag| tag.trim().len() > 50) {
+            return Err(ApiError::ValidationError("Tags cannot exceed 50 characters".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn validate_transfer_target(&self, task: &Task, to_user_id: Uuid) -> Result<()> {
+        if task.user_id == to_user_id {
+            return Err(ApiError::bad_request("Task already belongs to this user"));
+        }
+
+        if task.parent_id.is_some() {
+            return Err(ApiError::bad_request("Subtasks move with their parent task"));
+        }
+
+        if !self.user_repository.exists(to_user_id).await {
+            return Err(ApiError::UserNotFound { id: to_user_id });
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_email_verified(&self, user_id: Uuid) -> Result<()> {
+        if !self.require_verified_email {
+            return Ok(());
+        }
+        let user = self.user_repository.find_by_id(user_id).await?;
+        if !user.is_email_verified() {
+            return Err(ApiError::forbidden("Verify your email address before creating tasks"));
+        }
+        Ok(())
+    }
+
+    fn copy_request(source: &Task, title: String, options: &DuplicateTaskRequest, parent_id: Option<Uuid>) -> CreateTaskRequestInternal {
+        let checklist = if options.include_checklist {
+            let texts: Vec<String> = source.checklist.iter().map(|item| item.text.clone()).collect();
+            build_checklist(&texts)
+        } else {
+            Vec::new()
+        };
+
+        CreateTaskRequestInternal {
+            title,
+            description: if options.include_description { source.description.clone() } else { None },
+            user_id: source.user_id,
+            checklist,
+            tags: if options.include_tags { source.tags.clone() } else { Vec::new() },
+            priority: source.priority,
+            due_at: source.due_at,
+            assignee_id: source.assignee_id,
+            parent_id,
+        }
+    }
+
+    /// Change a checklist under the task's row lock, so concurrent edits don't overwrite each other
+    async fn modify_checklist<F>(&self, task_id: Uuid, modify: F) -> Result<Task>
+    where
+        F: FnOnce(&mut Vec<ChecklistItem>) -> Result<()>,
+    {
+        let task = self.task_repository.modify_checklist(task_id, modify).await?;
+        self.refresh_task_cache(&task).await;
+        Ok(task)
+    }
+
+    /// Tick or untick the note item a task was extracted from to match the
+    /// task's status; the note revision is authored by whoever changed the task
+    async fn sync_note_item(&self, task: &Task, actor_id: Uuid) -> Result<()> {
+        let Some(link) = self.note_repository.find_task_link_by_task(task.id).await? else {
+            return Ok(());
+        };
+
+        let note = self.note_repository.find_by_id(link.note_id).await?;
+        if note.user_id != task.user_id {
+            debug!("Note {} no longer belongs to the owner of task {}, not syncing", note.id, task.id);
+            return Ok(());
+        }
+        let Some(body) = set_task_item_checked(&note.body, &link.item_text, is_done(&task.status)) else {
+            debug!("Item for task {} no longer in note {}", task.id, note.id);
+            return Ok(());
+        };
+        if body == note.body {
+            return Ok(());
+        }
+
+        self.note_repository.set_body(&note, &body, actor_id).await?;
+
+        info!("Synced note {} item for task {}", note.id, task.id);
+        Ok(())
+    }
+
+    /// Both owners' task lists and every moved task are stale after a transfer
+    async fn invalidate_transferred(&self, from_user_id: Uuid, to_user_id: Uuid, moved: &[Uuid]) {
+        if let Some(cache) = &self.cache {
+            let _ = cache.del(&all_tasks_key()).await;
+            let _ = cache.del(&user_tasks_key(&from_user_id)).await;
+            let _ = cache.del(&user_tasks_key(&to_user_id)).await;
+            for id in moved {
+                let _ = cache.del(&task_key(id)).await;
+                let _ = cache.del(&task_html_key(id)).await;
+            }
+        }
+    }
+
+    /// Invalidate list caches touched by `task` and store its fresh copy
+    async fn refresh_task_cache(&self, task: &Task) {
+        if let Some(cache) = &self.cache {
+            let _ = cache.del(&all_tasks_key()).await;
+            let _ = cache.del(&user_tasks_key(&task.user_id)).await;
+            let _ = cache.set_json(&task_key(&task.id), task).await;
+            let _ = cache.del(&task_html_key(&task.id)).await;
+        }
+    }
+}
+
+fn is_done(status: &TaskStatus) -> bool {
+    matches!(status, TaskStatus::Done)
 }
@@ -1,6 +1,7 @@
+use chrono_tz::Tz;
 use uuid::Uuid;
 
-use crate::domain::{User, CreateUserRequest, Result, ApiError};
+use crate::domain::{User, CreateUserRequest, UpdateProfileRequest, Result, ApiError};
 use crate::repositories::UserRepository;
 
 #[derive(Debug, Clone)]
@@ -25,6 +26,36 @@ impl UserService {
         self.user_repository.find_by_id(id).await
     }
 
+    /// Apply the user's own edits to their profile
+    pub async fn update_profile(&self, id: Uuid, request: UpdateProfileRequest) -> Result<User> {
+        let name = match request.name {
+            Some(name) => {
+                let name = name.trim().to_string();
+                if name.len() < 2 {
+                    return Err(ApiError::ValidationError("Name must be at least 2 characters long".to_string()));
+                }
+                if name.len() > 100 {
+                    return Err(ApiError::ValidationError("Name cannot exceed 100 characters".to_string()));
+                }
+                Some(name)
+            }
+            None => None,
+        };
+
+        let timezone = match request.timezone {
+            Some(timezone) => {
+                let tz: Tz = timezone
+                    .trim()
+                    .parse()
+                    .map_err(|_| ApiError::ValidationError(format!("Unknown timezone: {}", timezone)))?;
+                Some(tz.name().to_string())
+            }
+            None => None,
+        };
+
+        self.user_repository.update_profile(id, name, timezone).await
+    }
+
     pub async fn user_exists(&self, id: Uuid) -> bool {
         self.user_repository.exists(id).await
     }
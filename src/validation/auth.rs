@@ -1,6 +1,6 @@
 use regex::Regex;
 use crate::domain::{ApiError, Result};
-use crate::services::auth_service::{RegisterRequest, LoginRequest};
+use crate::services::auth_service::{RegisterRequest, LoginRequest, ResetPasswordRequest};
 
 pub struct Validator;
 
@@ -29,16 +29,29 @@ impl Validator {
         }
 
         // Validate password
-        if req.password.is_empty() {
+        Self::validate_new_password(&req.password)
+    }
+
+    pub fn validate_reset_password_request(req: &ResetPasswordRequest) -> Result<()> {
+        if req.token.trim().is_empty() {
+            return Err(ApiError::validation_error("Reset token is required"));
+        }
+
+        Self::validate_new_password(&req.new_password)
+    }
+
+    /// Rules every password chosen by a user has to meet
+    pub fn validate_new_password(password: &str) -> Result<()> {
+        if password.is_empty() {
             return Err(ApiError::validation_error("Password is required and cannot be empty"));
         }
-        if req.password.len() < 8 {
+        if password.len() < 8 {
             return Err(ApiError::validation_error("Password must be at least 8 characters long"));
         }
-        if req.password.len() > 128 {
+        if password.len() > 128 {
             return Err(ApiError::validation_error("Password cannot exceed 128 characters"));
         }
-        if !Self::is_strong_password(&req.password) {
+        if !Self::is_strong_password(password) {
             return Err(ApiError::validation_error("Password must contain at least one uppercase letter, one lowercase letter, and one number"));
         }
 
@@ -62,12 +75,12 @@ impl Validator {
         Ok(())
     }
 
-    fn is_valid_email(email: &str) -> bool {
+    pub fn is_valid_email(email: &str) -> bool {
         let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
         email_regex.is_match(email.trim())
     }
 
-    fn is_strong_password(password: &str) -> bool {
+    pub fn is_strong_password(password: &str) -> bool {
         let has_uppercase = password.chars().any(|c| c.is_uppercase());
         let has_lowercase = password.chars().any(|c| c.is_lowercase());
         let has_digit = password.chars().any(|c| c.is_ascii_digit());
@@ -0,0 +1,297 @@
+//! Sign-in through OpenID Connect against an in-process mock provider.
+//!
+//! Needs a migrated database: `DATABASE_URL=... cargo test --test oidc_login -- --ignored`
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Form, Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::rand::SystemRandom;
+use ring::signature::Ed25519KeyPair;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use note_task_api::config::settings::{
+    AuthConfig, EmailVerificationGate, LockoutConfig, MagicLinkConfig, OidcConfig, OidcProviderConfig,
+};
+use note_task_api::domain::{ClientInfo, OidcCallbackRequest};
+use note_task_api::mail::LogMailer;
+use note_task_api::repositories::{
+    MfaRepository, OidcRepository, RoleRepository, SessionRepository, UserRepository, UserTokenRepository,
+};
+use note_task_api::security::{hash_password, JwtKeys};
+use note_task_api::services::auth_service::LoginResponse;
+use note_task_api::services::oidc_service::OidcService;
+use note_task_api::services::{AuthRepositories, AuthService, AuthServices, LockoutService, MfaService, SessionService};
+use note_task_api::ApiError;
+
+const PROVIDER: &str = "mock";
+const CLIENT_ID: &str = "note-task-api";
+
+/// What the next sign-in at the mock provider reports about the user
+#[derive(Clone)]
+struct Identity {
+    sub: String,
+    email: String,
+    email_verified: bool,
+}
+
+/// A code handed out by `/authorize`, waiting to be exchanged at `/token`
+struct IssuedCode {
+    identity: Identity,
+    nonce: String,
+    code_challenge: String,
+}
+
+#[derive(Clone)]
+struct MockProvider {
+    issuer: String,
+    keys: JwtKeys,
+    codes: Arc<Mutex<HashMap<String, IssuedCode>>>,
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    code: String,
+    code_verifier: String,
+    client_id: String,
+}
+
+impl MockProvider {
+    async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let provider = Self {
+            issuer: format!("http://{}", listener.local_addr().unwrap()),
+            keys: signing_keys(),
+            codes: Arc::default(),
+        };
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(provider.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        provider
+    }
+
+    /// Play the browser's round trip through the provider's login page:
+    /// the user signs in as `identity` and comes back with a code
+    fn sign_in(&self, authorization_url: &str, identity: Identity) -> String {
+        let url = reqwest::Url::parse(authorization_url).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+                .unwrap_or_else(|| panic!("authorization URL has no {}", name))
+        };
+        assert_eq!(param("client_id"), CLIENT_ID);
+        assert_eq!(param("code_challenge_method"), "S256");
+
+        let code = uuid::Uuid::new_v4().to_string();
+        let issued = IssuedCode { identity, nonce: param("nonce"), code_challenge: param("code_challenge") };
+        self.codes.lock().unwrap().insert(code.clone(), issued);
+        code
+    }
+}
+
+async fn discovery(State(provider): State<MockProvider>) -> Json<Value> {
+    Json(json!({
+        "issuer": provider.issuer,
+        "authorization_endpoint": format!("{}/authorize", provider.issuer),
+        "token_endpoint": format!("{}/token", provider.issuer),
+        "jwks_uri": format!("{}/jwks", provider.issuer),
+    }))
+}
+
+async fn jwks(State(provider): State<MockProvider>) -> Json<Value> {
+    Json(serde_json::to_value(provider.keys.jwks()).unwrap())
+}
+
+async fn token(State(provider): State<MockProvider>, Form(form): Form<TokenForm>) -> Json<Value> {
+    let issued = provider.codes.lock().unwrap().remove(&form.code).expect("unknown or reused code");
+    assert_eq!(form.client_id, CLIENT_ID);
+    assert_eq!(URL_SAFE_NO_PAD.encode(Sha256::digest(form.code_verifier.as_bytes())), issued.code_challenge);
+
+    let claims = json!({
+        "iss": provider.issuer,
+        "aud": CLIENT_ID,
+        "sub": issued.identity.sub,
+        "exp": chrono::Utc::now().timestamp() + 300,
+        "nonce": issued.nonce,
+        "email": issued.identity.email,
+        "email_verified": issued.identity.email_verified,
+        "name": "Mock User",
+    });
+    Json(json!({ "id_token": provider.keys.encode(&claims).unwrap(), "token_type": "Bearer" }))
+}
+
+/// A fresh Ed25519 key for the provider to sign ID tokens with
+fn signing_keys() -> JwtKeys {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let path = std::env::temp_dir().join(format!("oidc-mock-{}.pem", uuid::Uuid::new_v4()));
+    std::fs::write(&path, pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref()))).unwrap();
+
+    let keys = JwtKeys::from_pem_files(path.to_str().unwrap(), &[]).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    keys
+}
+
+struct TestApp {
+    oidc: OidcService,
+    users: UserRepository,
+    provider: MockProvider,
+}
+
+impl TestApp {
+    async fn start() -> Self {
+        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must point at a migrated database");
+        let pool = PgPool::connect(&database_url).await.unwrap();
+        let provider = MockProvider::start().await;
+
+        let cfg = auth_config();
+        let users = UserRepository::new(pool.clone());
+        let roles = RoleRepository::new(pool.clone());
+        let sessions = SessionService::new(SessionRepository::new(pool.clone()), None, &cfg);
+        let auth = AuthService::new(
+            AuthRepositories { users: users.clone(), roles: roles.clone(), tokens: UserTokenRepository::new(pool.clone()) },
+            AuthServices {
+                mfa: MfaService::new(MfaRepository::new(pool.clone()), users.clone(), roles, cfg.mfa_issuer.clone()),
+                lockout: LockoutService::new(None, users.clone(), cfg.lockout.clone()),
+                sessions,
+            },
+            None,
+            Arc::new(LogMailer::new(None)),
+            JwtKeys::hs256(&cfg.jwt_secret),
+            cfg,
+            "http://app.test".to_string(),
+        );
+
+        let providers = vec![OidcProviderConfig {
+            name: PROVIDER.to_string(),
+            display_name: "Mock".to_string(),
+            issuer: provider.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: None,
+            redirect_uri: "http://app.test/auth/oidc/mock/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+            trust_email: false,
+        }];
+        let oidc = OidcService::new(OidcConfig { providers }, OidcRepository::new(pool), users.clone(), auth);
+
+        Self { oidc, users, provider }
+    }
+
+    async fn sign_in(&self, identity: Identity) -> Result<LoginResponse, ApiError> {
+        let authorization = self.oidc.authorize(PROVIDER).await?;
+        let code = self.provider.sign_in(&authorization.authorization_url, identity);
+        let client = ClientInfo { ip: None, user_agent: Some("oidc-test".to_string()) };
+        self.oidc
+            .callback(PROVIDER, OidcCallbackRequest { code, state: authorization.state }, &client)
+            .await
+    }
+
+    /// A local account registered with a password, as `register` leaves it
+    async fn local_user(&self, email: &str, verified: bool) -> uuid::Uuid {
+        let user = self.users
+            .create_with_password_hash("Local User".to_string(), email.to_string(), hash_password("Str0ng!Passw0rd").unwrap())
+            .await
+            .unwrap();
+        if verified {
+            self.users.mark_email_verified(user.id).await.unwrap();
+        }
+        user.id
+    }
+}
+
+fn auth_config() -> AuthConfig {
+    AuthConfig {
+        jwt_secret: "oidc-test-secret".to_string(),
+        jwt_private_key_path: None,
+        jwt_verification_key_paths: Vec::new(),
+        issuer: "note-task-api".to_string(),
+        audience: "note-task-api".to_string(),
+        expiry_minutes: 5,
+        session_ttl_days: 1,
+        password_reset_ttl_minutes: 30,
+        email_verification: EmailVerificationGate::None,
+        email_verification_ttl_minutes: 60,
+        email_verification_resend_secs: 60,
+        mfa_issuer: "Note Task API".to_string(),
+        lockout: LockoutConfig {
+            account_threshold: 5,
+            ip_threshold: 20,
+            base_lock_secs: 60,
+            max_lock_secs: 3600,
+            failure_window_secs: 900,
+        },
+        magic_link: MagicLinkConfig { ttl_minutes: 15, max_requests: 3, request_window_secs: 900 },
+    }
+}
+
+fn identity(email_verified: bool) -> Identity {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    Identity { sub: format!("mock-{}", id), email: format!("oidc-{}@example.test", &id[..12]), email_verified }
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn new_user_is_created_verified_and_signed_in() {
+    let app = TestApp::start().await;
+    let identity = identity(true);
+
+    let response = app.sign_in(identity.clone()).await.unwrap();
+    assert!(matches!(response, LoginResponse::Token(_)));
+
+    let user = app.users.find_by_email(&identity.email).await.unwrap().expect("user was not created");
+    assert!(user.is_email_verified());
+
+    // The identity is linked now, so the next sign-in finds the same user
+    app.sign_in(identity.clone()).await.unwrap();
+    assert_eq!(app.users.find_by_email(&identity.email).await.unwrap().map(|u| u.id), Some(user.id));
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn verified_local_account_is_linked() {
+    let app = TestApp::start().await;
+    let identity = identity(true);
+    let user_id = app.local_user(&identity.email, true).await;
+
+    app.sign_in(identity.clone()).await.unwrap();
+    assert_eq!(app.users.find_by_email(&identity.email).await.unwrap().map(|u| u.id), Some(user_id));
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn unverified_local_account_is_not_linked() {
+    let app = TestApp::start().await;
+    let identity = identity(true);
+    let user_id = app.local_user(&identity.email, false).await;
+
+    let error = app.sign_in(identity.clone()).await.expect_err("sign-in should be refused");
+    assert!(matches!(error, ApiError::Conflict(_)), "unexpected error: {:?}", error);
+
+    // Refusing must not vouch for the address either
+    let user = app.users.find_by_id(user_id).await.unwrap();
+    assert!(!user.is_email_verified());
+}
+
+#[tokio::test]
+#[ignore = "needs a migrated database in DATABASE_URL"]
+async fn unverified_provider_email_is_refused() {
+    let app = TestApp::start().await;
+    let identity = identity(false);
+
+    let error = app.sign_in(identity.clone()).await.expect_err("sign-in should be refused");
+    assert!(matches!(error, ApiError::Forbidden(_)), "unexpected error: {:?}", error);
+    assert!(app.users.find_by_email(&identity.email).await.unwrap().is_none());
+}